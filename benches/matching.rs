@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use globber::bench_support::{pathological_pattern, pathological_value, paths, patterns};
+use globber::{Glob, GlobList};
+
+fn single_patterns(c: &mut Criterion) {
+    let values = paths(1000, 1);
+    let mut group = c.benchmark_group("single");
+    for pattern in ["src/lib.rs", "*.rs", "src/*", "src/*/file*.rs", "file??.rs", "src/**/*.[rt]s"] {
+        let glob = Glob::new(pattern).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(pattern), &glob, |b, glob| {
+            b.iter(|| values.iter().filter(|value| glob.is_match(black_box(value))).count())
+        });
+    }
+    group.finish();
+}
+
+fn pathological(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pathological");
+    for stars in [4, 16, 64] {
+        let value = pathological_value(1000);
+        let glob = Glob::new(&pathological_pattern(stars)).unwrap();
+        let dfa = Glob::builder(&pathological_pattern(stars)).dfa(true).build().unwrap();
+        group.bench_with_input(BenchmarkId::new("backtracking", stars), &value, |b, value| {
+            b.iter(|| glob.is_match(black_box(value)))
+        });
+        group.bench_with_input(BenchmarkId::new("dfa", stars), &value, |b, value| {
+            b.iter(|| dfa.is_match(black_box(value)))
+        });
+    }
+    group.finish();
+}
+
+fn glob_list(c: &mut Criterion) {
+    let values = paths(1000, 2);
+    let patterns = patterns(10_000, 2);
+    let list = GlobList::build(&patterns).unwrap();
+    let mut optimized = list.clone();
+    optimized.optimize();
+    let mut group = c.benchmark_group("glob_list_10k");
+    group.bench_function("any_match", |b| {
+        b.iter(|| values.iter().filter(|value| list.any_match(black_box(value))).count())
+    });
+    group.bench_function("any_match_optimized", |b| {
+        b.iter(|| values.iter().filter(|value| optimized.any_match(black_box(value))).count())
+    });
+    group.finish();
+}
+
+fn case_insensitive(c: &mut Criterion) {
+    let values = paths(1000, 3);
+    let mut group = c.benchmark_group("case_insensitive");
+    let sensitive = Glob::new("*/file*.jpg").unwrap();
+    let unicode = Glob::builder("*/FILE*.jpg").case_insensitive(true).build().unwrap();
+    let ascii = Glob::builder("*/FILE*.jpg").ascii_case_insensitive(true).build().unwrap();
+    for (name, glob) in [("sensitive", &sensitive), ("unicode", &unicode), ("ascii", &ascii)] {
+        group.bench_function(name, |b| {
+            b.iter(|| values.iter().filter(|value| glob.is_match(black_box(value))).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single_patterns, pathological, glob_list, case_insensitive);
+criterion_main!(benches);
@@ -0,0 +1,377 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! The `glob_pattern!` macro of `globber`, use it through `globber::macros::glob_pattern!` with the `macros` feature.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use proc_macro::{Literal, TokenStream, TokenTree};
+
+/// A `globber::GlobPattern` for a string literal, built at compile time.
+///
+/// Expands to the built pattern, the same one `globber::build_glob_pattern` returns, so nothing is parsed
+/// at runtime. An invalid pattern is a compile error.
+#[proc_macro]
+pub fn glob_pattern(input: TokenStream) -> TokenStream {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal,
+        _ => return compile_error("glob_pattern! takes a single string literal"),
+    };
+    let pattern = match unquote(&literal.to_string()) {
+        Some(pattern) => pattern,
+        None => return compile_error("glob_pattern! takes a single string literal"),
+    };
+    match build(&pattern) {
+        Ok(built) => built.parse().unwrap(),
+        Err(error) => compile_error(&format!("invalid pattern {}: {}", literal, error)),
+    }
+}
+
+/// The errors of `globber::GlobError` a pattern built with the default options can fail with.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Error {
+    EmptyPattern,
+    EmptyWildcardSegment(usize),
+    UnterminatedClass(usize),
+    ReversedRange(usize),
+    UnterminatedBraces(usize),
+    UnterminatedGroup(usize),
+}
+
+impl Error {
+    fn at(self, position: usize) -> Error {
+        match self {
+            Error::EmptyPattern => Error::EmptyPattern,
+            Error::EmptyWildcardSegment(_) => Error::EmptyWildcardSegment(position),
+            Error::UnterminatedClass(_) => Error::UnterminatedClass(position),
+            Error::ReversedRange(_) => Error::ReversedRange(position),
+            Error::UnterminatedBraces(_) => Error::UnterminatedBraces(position),
+            Error::UnterminatedGroup(_) => Error::UnterminatedGroup(position),
+        }
+    }
+}
+
+// same messages as `globber::GlobError`
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyPattern => write!(f, "empty pattern"),
+            Error::EmptyWildcardSegment(position) => write!(f, "two wildcards next to each other at position {}", position),
+            Error::UnterminatedClass(position) => write!(f, "unterminated character class starting at position {}", position),
+            Error::ReversedRange(position) => write!(f, "character class range ends before it starts at position {}", position),
+            Error::UnterminatedBraces(position) => write!(f, "unterminated brace group starting at position {}", position),
+            Error::UnterminatedGroup(position) => write!(f, "unterminated case insensitive group starting at position {}", position),
+        }
+    }
+}
+
+/// The pattern built the way `globber::build_glob_pattern` builds it, as Rust source.
+fn build(pattern: &str) -> Result<String, Error> {
+    if pattern.is_empty() {
+        return Err(Error::EmptyPattern);
+    }
+    if !pattern.contains('{') {
+        return build_without_braces(pattern);
+    }
+    let mut alternatives = expand_braces(pattern)?
+        .iter()
+        .map(|p| build_without_braces(p))
+        .collect::<Result<Vec<String>, Error>>()
+        .map_err(|e| match build_without_braces(pattern) {
+            // positions in the expansions don't match the ones in `pattern`, like globber blame the first brace
+            // for errors only showing up after expanding
+            Err(e) => e,
+            Ok(_) => e.at(pattern.find('{').unwrap_or(0)),
+        })?;
+    if alternatives.len() == 1 {
+        return Ok(alternatives.remove(0));
+    }
+    Ok(format!("::globber::GlobPattern::Alternatives(::std::vec![{}])", alternatives.join(", ")))
+}
+
+fn build_without_braces(pattern: &str) -> Result<String, Error> {
+    if pattern.contains(['?', '[']) || pattern.contains("**") {
+        let segments = build_segments(pattern)?;
+        return Ok(format!("::globber::GlobPattern::Segmented(::std::vec![{}])", segments.join(", ")));
+    }
+    Ok(wildcards_only(pattern))
+}
+
+/// The pattern built the way `globber` builds a pattern without other wildcards than `*`, as Rust source.
+fn wildcards_only(pattern: &str) -> String {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    match parts.len() {
+        1 => format!("::globber::GlobPattern::MatchFull({})", string(pattern)),
+        2 if first.is_empty() && last.is_empty() => "::globber::GlobPattern::MatchAny".to_string(),
+        2 if first.is_empty() => format!("::globber::GlobPattern::MatchEnd({})", string(last)),
+        2 if last.is_empty() => format!("::globber::GlobPattern::MatchStart({})", string(first)),
+        2 => format!("::globber::GlobPattern::MatchBothEnds({}, {})", string(first), string(last)),
+        _ => {
+            let multipart = |variant: &str, s: &str| format!("::globber::Multipart::{}({})", variant, string(s));
+            let (mut multi, middle) = if first.is_empty() {
+                (vec![multipart("AnyUntil", parts[1])], &parts[2..parts.len() - 1])
+            } else {
+                (vec![multipart("ExactStart", first)], &parts[1..parts.len() - 1])
+            };
+            multi.extend(middle.iter().map(|part| multipart("AnyUntil", part)));
+            multi.push(if last.is_empty() {
+                "::globber::Multipart::AnyEnd".to_string()
+            } else {
+                multipart("AnyUntilExactEnd", last)
+            });
+            format!("::globber::GlobPattern::Multipart(::std::vec![{}].into())", multi.join(", "))
+        },
+    }
+}
+
+/// The segments of a pattern with `?`, classes or `**`, as Rust source, the way `globber` splits them
+/// with the default options.
+fn build_segments(pattern: &str) -> Result<Vec<String>, Error> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.char_indices().peekable();
+    let mut previous = None;
+    // where the `(?i:` group the chars are in started
+    let mut group = None;
+    let flush = |segments: &mut Vec<String>, literal: &mut String, group: Option<usize>| {
+        if literal.is_empty() {
+            return;
+        }
+        let literal = std::mem::take(literal);
+        segments.push(match group {
+            // folded by globber, which knows which case folding it was built with
+            Some(_) => format!("::globber::Segment::LiteralIgnoreCase(::globber::macros::__fold_case({:?}))", literal),
+            None => format!("::globber::Segment::Literal({})", string(&literal)),
+        });
+    };
+    while let Some((position, ch)) = chars.next() {
+        match ch {
+            '(' if group.is_none() && pattern[position..].starts_with("(?i:") => {
+                flush(&mut segments, &mut literal, group);
+                group = Some(position);
+                chars.nth(2);
+                continue;
+            },
+            ')' if group.is_some() => {
+                flush(&mut segments, &mut literal, group);
+                group = None;
+                continue;
+            },
+            _ => (),
+        }
+        let wildcard = match ch {
+            '?' => "AnyChar".to_string(),
+            '*' => match chars.next_if(|&(_, ch)| ch == '*') {
+                Some((second, _)) => {
+                    let component_start = matches!(previous, None | Some('/'));
+                    match chars.peek() {
+                        Some(&(_, '/')) if component_start => {
+                            chars.next();
+                            "AnyDirs".to_string()
+                        },
+                        None if component_start => "AnyPath".to_string(),
+                        Some(&(third, '*')) if component_start => return Err(Error::EmptyWildcardSegment(third)),
+                        _ => return Err(Error::EmptyWildcardSegment(second)),
+                    }
+                },
+                None => "AnyChars".to_string(),
+            },
+            '[' => {
+                let (negated, ranges) = parse_class(&mut chars, position)?;
+                let ranges: Vec<String> = ranges.iter().map(|range| format!("{:?}", range)).collect();
+                format!("Class(::globber::macros::__class({}, ::std::vec![{}], {}))", negated, ranges.join(", "), group.is_some())
+            },
+            _ => {
+                literal.push(ch);
+                previous = Some(ch);
+                continue;
+            },
+        };
+        // `**/` took the separator with it, so a following `**` still starts a component
+        previous = Some(if wildcard == "AnyDirs" { '/' } else { ch });
+        flush(&mut segments, &mut literal, group);
+        segments.push(format!("::globber::Segment::{}", wildcard));
+    }
+    if let Some(position) = group {
+        return Err(Error::UnterminatedGroup(position));
+    }
+    flush(&mut segments, &mut literal, group);
+    Ok(segments)
+}
+
+/// Parses a class after its opening `[` at `open` into whether it's negated and its inclusive ranges.
+fn parse_class(chars: &mut Peekable<CharIndices<'_>>, open: usize) -> Result<(bool, Vec<(char, char)>), Error> {
+    let negated = chars.next_if(|&(_, ch)| ch == '!' || ch == '^').is_some();
+    let mut ranges = Vec::new();
+    loop {
+        let (position, first) = chars.next().ok_or(Error::UnterminatedClass(open))?;
+        if first == ']' && !ranges.is_empty() {
+            return Ok((negated, ranges));
+        }
+        let mut ahead = chars.clone();
+        let last = match (ahead.next(), ahead.next()) {
+            (Some((_, '-')), Some((_, last))) if last != ']' => {
+                chars.next();
+                chars.next();
+                last
+            },
+            _ => first,
+        };
+        if last < first {
+            return Err(Error::ReversedRange(position));
+        }
+        ranges.push((first, last));
+    }
+}
+
+/// Expands every `{a,b,...}` group in `pattern` like `globber` does, braces inside classes are left alone.
+fn expand_braces(pattern: &str) -> Result<Vec<String>, Error> {
+    let mut open = Vec::new();
+    find_outside_classes(pattern, 0, |ch, i| {
+        match ch {
+            '{' => open.push(i),
+            '}' => {
+                open.pop();
+            },
+            _ => (),
+        }
+        false
+    });
+    if let Some(&position) = open.first() {
+        return Err(Error::UnterminatedBraces(position));
+    }
+    let mut expanded = Vec::new();
+    expand_into(pattern, &mut expanded);
+    Ok(expanded)
+}
+
+fn expand_into(pattern: &str, expanded: &mut Vec<String>) {
+    let open = match find_outside_classes(pattern, 0, |ch, _| ch == '{') {
+        Some(open) => open,
+        None => return expanded.push(pattern.to_string()),
+    };
+    let mut depth = 0;
+    let mut separators = vec![open];
+    let close = find_outside_classes(pattern, open + 1, |ch, i| {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return true,
+            '}' => depth -= 1,
+            ',' if depth == 0 => separators.push(i),
+            _ => (),
+        }
+        false
+    }).expect("unterminated group");
+    separators.push(close);
+
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    for alternative in separators.windows(2) {
+        let alternative = &pattern[alternative[0] + 1..alternative[1]];
+        expand_into(&format!("{}{}{}", prefix, alternative, suffix), expanded);
+    }
+}
+
+fn find_outside_classes(pattern: &str, from: usize, mut found: impl FnMut(char, usize) -> bool) -> Option<usize> {
+    let mut pos = from;
+    while let Some(ch) = pattern[pos..].chars().next() {
+        if ch == '[' {
+            if let Some(end) = class_end(&pattern[pos + 1..]) {
+                pos += 1 + end + 1;
+                continue;
+            }
+        }
+        if found(ch, pos) {
+            return Some(pos);
+        }
+        pos += ch.len_utf8();
+    }
+    None
+}
+
+fn class_end(class: &str) -> Option<usize> {
+    let mut skip = 0;
+    if class.starts_with(['!', '^']) {
+        skip += 1;
+    }
+    if class[skip..].starts_with(']') {
+        skip += 1;
+    }
+    class[skip..].find(']').map(|end| skip + end)
+}
+
+/// A `String` from `s`, as Rust source.
+fn string(s: &str) -> String {
+    format!("::std::string::String::from({:?})", s)
+}
+
+/// The value of a string literal as written in source, `None` for other literals.
+fn unquote(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw.get(hashes + 1..raw.len() - hashes - 1).map(str::to_string);
+    }
+    let inner = source.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '"' => value.push('"'),
+            '\'' => value.push('\''),
+            // unicode and byte escapes and line continuations aren't worth handling in a pattern
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("::std::compile_error!({})", Literal::string(message)).parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build, Error};
+
+    #[test]
+    fn fails_like_globber() {
+        // the errors and positions globber's own tests expect
+        let cases = [("", Error::EmptyPattern), ("a**", Error::EmptyWildcardSegment(2)), ("***", Error::EmptyWildcardSegment(2)),
+                     ("a*b**", Error::EmptyWildcardSegment(4)), ("?**", Error::EmptyWildcardSegment(2)),
+                     ("**/***", Error::EmptyWildcardSegment(5)), ("*{*a,b}", Error::EmptyWildcardSegment(1)),
+                     ("[abc", Error::UnterminatedClass(0)), ("a[z-a]", Error::ReversedRange(2)),
+                     ("{a,b", Error::UnterminatedBraces(0)), ("a(?i:b", Error::UnterminatedGroup(1))];
+        for &(pattern, error) in cases.iter() {
+            assert_eq!(build(pattern), Err(error), "{}", pattern);
+        }
+        assert_eq!(Error::EmptyWildcardSegment(2).to_string(), "two wildcards next to each other at position 2");
+        assert!(build("{a,[{]}").is_ok() && build("**/**").is_ok());
+    }
+}
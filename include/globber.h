@@ -0,0 +1,101 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+#ifndef GLOBBER_H
+#define GLOBBER_H
+
+/* C bindings of globber, built with the `ffi` feature. Generated with cbindgen, see cbindgen.toml. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ A compiled pattern, see `globber_build`.
+ */
+typedef struct GlobberGlob GlobberGlob;
+
+/*
+ A list of patterns, see `globber_list_new`.
+ */
+typedef struct GlobberList GlobberList;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Builds `pattern`, null if it isn't valid UTF-8 or not a valid pattern. Free it with `globber_free`.
+
+ # Safety
+ `pattern` has to be a NUL terminated string.
+ */
+struct GlobberGlob *globber_build(const char *pattern, bool ignore_case);
+
+/*
+ Whether `value` matches, wildcards match single bytes where it isn't valid UTF-8.
+
+ # Safety
+ `glob` has to come from `globber_build` and `value` has to be a NUL terminated string.
+ */
+bool globber_is_match(const struct GlobberGlob *glob, const char *value);
+
+/*
+ Frees a pattern from `globber_build`, null is ignored.
+
+ # Safety
+ `glob` has to come from `globber_build` and can't be used afterwards.
+ */
+void globber_free(struct GlobberGlob *glob);
+
+/*
+ An empty list, free it with `globber_list_free`.
+ */
+struct GlobberList *globber_list_new(void);
+
+/*
+ Adds `pattern` to the list, negated if it starts with `!` like in a gitignore file.
+ Returns false if it isn't valid UTF-8 or not a valid pattern.
+
+ # Safety
+ `list` has to come from `globber_list_new` and `pattern` has to be a NUL terminated string.
+ */
+bool globber_list_add(struct GlobberList *list, const char *pattern, bool ignore_case);
+
+/*
+ Whether the list accepts `value`, see `GlobList::any_match`. Invalid UTF-8 is replaced by U+FFFD.
+
+ # Safety
+ `list` has to come from `globber_list_new` and `value` has to be a NUL terminated string.
+ */
+bool globber_list_any_match(const struct GlobberList *list, const char *value);
+
+/*
+ Frees a list from `globber_list_new`, null is ignored.
+
+ # Safety
+ `list` has to come from `globber_list_new` and can't be used afterwards.
+ */
+void globber_list_free(struct GlobberList *list);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GLOBBER_H */
@@ -0,0 +1,100 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Inputs for benchmarking the matcher, used by the crate's own benches in `benches/`.
+//!
+//! Everything is generated deterministically, so numbers from different runs and machines compare.
+
+/// `a*a*…*b` with `stars` wildcards, which a naive backtracking matcher takes exponential time on
+/// against [`pathological_value`].
+pub fn pathological_pattern(stars: usize) -> String {
+    let mut pattern = "a*".repeat(stars);
+    pattern.push('b');
+    pattern
+}
+
+/// `len` times `a`, which [`pathological_pattern`] never matches.
+pub fn pathological_value(len: usize) -> String {
+    "a".repeat(len)
+}
+
+/// `n` path-like values, `src/dir3/file17.rs` and the like, some with upper case letters.
+pub fn paths(n: usize, seed: u64) -> Vec<String> {
+    const DIRS: [&str; 6] = ["src", "target", "docs", "tests", "Assets", "node_modules"];
+    const EXTENSIONS: [&str; 6] = ["rs", "toml", "md", "JPG", "js", "txt"];
+    let mut rng = Rng(seed);
+    (0..n)
+        .map(|_| {
+            let dir = DIRS[rng.below(DIRS.len())];
+            let depth = rng.below(4);
+            let mut path = dir.to_string();
+            for _ in 0..depth {
+                path.push_str(&format!("/dir{}", rng.below(10)));
+            }
+            path.push_str(&format!("/file{}.{}", rng.below(100), EXTENSIONS[rng.below(EXTENSIONS.len())]));
+            path
+        })
+        .collect()
+}
+
+/// `n` different patterns of the kinds found in ignore files and routing tables,
+/// a mix of literal, `*`, multipart, `?`, class and `**` patterns.
+pub fn patterns(n: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng(seed);
+    (0..n)
+        .map(|i| match rng.below(6) {
+            0 => format!("src/dir{}/file{}.rs", rng.below(10), i),
+            1 => format!("*.ext{}", i),
+            2 => format!("target/*/build{}*.o", i),
+            3 => format!("file{}.?s", i),
+            4 => format!("docs/[a-f]*{}.md", i),
+            _ => format!("**/cache{}/**", i),
+        })
+        .collect()
+}
+
+/// A xorshift generator, good enough for picking inputs and free of dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        // zero is the one state xorshift can't leave
+        let mut x = self.0.max(1);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        (x % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench_support::{pathological_pattern, pathological_value, paths, patterns};
+    use crate::{Glob, GlobList};
+
+    #[test]
+    fn generated_inputs_are_usable() {
+        let glob = Glob::new(&pathological_pattern(8)).unwrap();
+        assert!(!glob.is_match(&pathological_value(64)));
+        assert_eq!(paths(50, 7), paths(50, 7));
+        assert_eq!(paths(50, 7).len(), 50);
+        let list = GlobList::build(&patterns(200, 7)).unwrap();
+        assert_eq!(list.len(), 200);
+    }
+}
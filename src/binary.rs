@@ -0,0 +1,499 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! A versioned binary format for [`GlobList`], see [`GlobList::to_bytes`].
+//!
+//! The compiled patterns are stored as they are, so reading them back doesn't parse anything.
+//! Layout, integers little endian:
+//! - `GLBR` then the format version as a `u16`
+//! - the id the next added pattern gets as a `u32`
+//! - the case insensitive then the case sensitive patterns, each a `u32` count then the globs
+//! - the entries in insertion order, a `u32` count then id `u32`, bucket `u8`, index `u32`, negated `u8`
+//!
+//! A glob is its source pattern, case mode `u8`, anchoring `u8`, grapheme mode `u8`, normalization
+//! `u8`, whether it has a DFA `u8` and the pattern tree, strings are a `u32` length and UTF-8 bytes.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use crate::{CaseMode, CharClass, Entry, EntryId, Glob, GlobCaseSensitive, GlobIgnoreCase, GlobList, GlobPattern,
+            Multipart, MultipartVec, Segment, Slot};
+
+const MAGIC: &[u8; 4] = b"GLBR";
+/// bumped whenever the layout changes, older versions are rejected rather than misread
+const VERSION: u16 = 3;
+/// how deep alternatives can be nested, built patterns nest them at most twice (braces and
+/// `literal_leading_dot`), deeper ones are rejected before reading them overflows the stack
+const MAX_NESTING: usize = 16;
+
+/// Why [`GlobList::from_bytes`] couldn't read a list.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum FromBytesError {
+    /// Doesn't start with the `GLBR` header.
+    NotAGlobList,
+    /// Written by a version of the format this one can't read.
+    UnsupportedVersion(u16),
+    /// Ends in the middle of the list.
+    Truncated,
+    /// Has something no list could have written, at that byte offset.
+    Invalid { offset: usize },
+}
+
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromBytesError::NotAGlobList => write!(f, "not a serialized glob list"),
+            FromBytesError::UnsupportedVersion(version) =>
+                write!(f, "unsupported glob list format version {}", version),
+            FromBytesError::Truncated => write!(f, "serialized glob list is truncated"),
+            FromBytesError::Invalid { offset } => write!(f, "invalid serialized glob list at byte {}", offset),
+        }
+    }
+}
+
+impl Error for FromBytesError {}
+
+impl GlobList {
+    /// The list in a stable binary format, read back by [`GlobList::from_bytes`] without parsing the
+    /// patterns again, e.g. to precompile a large ruleset once. Hit counts aren't included and
+    /// [`GlobList::optimize`] has to run again after reading it.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        write_len(&mut out, self.next_id);
+        write_len(&mut out, self.ignore_case_patterns.len());
+        for pattern in &self.ignore_case_patterns {
+            write_glob(&mut out, &pattern.0);
+        }
+        write_len(&mut out, self.case_sensitive_patterns.len());
+        for pattern in &self.case_sensitive_patterns {
+            write_glob(&mut out, &pattern.0);
+        }
+        write_len(&mut out, self.entries.len());
+        for entry in &self.entries {
+            write_len(&mut out, entry.id.0);
+            let (bucket, index) = match entry.slot {
+                Slot::IgnoreCase(i) => (0, i),
+                Slot::CaseSensitive(i) => (1, i),
+            };
+            out.push(bucket);
+            write_len(&mut out, index);
+            out.push(entry.negated as u8);
+        }
+        out
+    }
+
+    /// Reads a list written by [`GlobList::to_bytes`], borrowing nothing from `bytes` so they can be
+    /// a memory mapped file. Patterns built with a DFA get it compiled again.
+    pub fn from_bytes(bytes: &[u8]) -> Result<GlobList, FromBytesError> {
+        let header = &bytes[..bytes.len().min(MAGIC.len())];
+        if header != &MAGIC[..header.len()] {
+            return Err(FromBytesError::NotAGlobList);
+        }
+        if bytes.len() < MAGIC.len() + 2 {
+            return Err(FromBytesError::Truncated);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(FromBytesError::UnsupportedVersion(version));
+        }
+        let mut reader = Reader { bytes, offset: MAGIC.len() + 2 };
+        let mut list = GlobList::new();
+        list.next_id = reader.len()?;
+        for _ in 0..reader.len()? {
+            let glob = reader.glob()?;
+            list.ignore_case_patterns.push(GlobIgnoreCase(glob));
+        }
+        for _ in 0..reader.len()? {
+            let glob = reader.glob()?;
+            list.case_sensitive_patterns.push(GlobCaseSensitive(glob));
+        }
+        let count = reader.len()?;
+        // every pattern has exactly one entry, in ascending id order like the list keeps them
+        let mut ignore_case_used = vec![false; list.ignore_case_patterns.len()];
+        let mut case_sensitive_used = vec![false; list.case_sensitive_patterns.len()];
+        for _ in 0..count {
+            let offset = reader.offset;
+            let id = EntryId(reader.len()?);
+            let (slot, used) = match (reader.byte()?, reader.len()?) {
+                (0, i) if i < ignore_case_used.len() => (Slot::IgnoreCase(i), &mut ignore_case_used[i]),
+                (1, i) if i < case_sensitive_used.len() => (Slot::CaseSensitive(i), &mut case_sensitive_used[i]),
+                _ => return Err(FromBytesError::Invalid { offset }),
+            };
+            let negated = reader.flag()?;
+            if *used || id.0 >= list.next_id || list.entries.last().is_some_and(|e| e.id >= id) {
+                return Err(FromBytesError::Invalid { offset });
+            }
+            *used = true;
+            list.entries.push(Entry { id, slot, negated });
+            list.negated_count += negated as usize;
+        }
+        if count != list.ignore_case_patterns.len() + list.case_sensitive_patterns.len() {
+            return Err(FromBytesError::Invalid { offset: reader.offset });
+        }
+        if reader.offset != bytes.len() {
+            return Err(FromBytesError::Invalid { offset: reader.offset });
+        }
+        Ok(list)
+    }
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).expect("glob list too large to serialize");
+    out.extend_from_slice(&len.to_le_bytes());
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_len(out, s.len());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_glob(out: &mut Vec<u8>, glob: &Glob) {
+    write_str(out, &glob.source);
+    out.push(match glob.case {
+        CaseMode::Sensitive => 0,
+        CaseMode::Insensitive => 1,
+        CaseMode::AsciiInsensitive => 2,
+        CaseMode::TurkicInsensitive => 3,
+    });
+    out.push(glob.unanchored as u8);
+    out.push(glob.graphemes as u8);
+    out.push(glob.normalized as u8);
+    out.push(glob.dfa.is_some() as u8);
+    write_pattern(out, &glob.pattern);
+}
+
+fn write_pattern(out: &mut Vec<u8>, pattern: &GlobPattern) {
+    match pattern {
+        GlobPattern::MatchAny => out.push(0),
+        GlobPattern::Multipart(parts) => {
+            out.push(1);
+            write_len(out, parts.len());
+            for part in parts {
+                match part {
+                    Multipart::ExactStart(s) => { out.push(0); write_str(out, s); },
+                    Multipart::AnyUntil(s) => { out.push(1); write_str(out, s); },
+                    Multipart::AnyUntilExactEnd(s) => { out.push(2); write_str(out, s); },
+                    Multipart::AnyEnd => out.push(3),
+                }
+            }
+        },
+        GlobPattern::MatchEnd(s) => { out.push(2); write_str(out, s); },
+        GlobPattern::MatchStart(s) => { out.push(3); write_str(out, s); },
+        GlobPattern::MatchBothEnds(start, end) => {
+            out.push(4);
+            write_str(out, start);
+            write_str(out, end);
+        },
+        GlobPattern::MatchFull(s) => { out.push(5); write_str(out, s); },
+        GlobPattern::Segmented(segments) => {
+            out.push(6);
+            write_len(out, segments.len());
+            for segment in segments {
+                write_segment(out, segment);
+            }
+        },
+        GlobPattern::Alternatives(alternatives) => {
+            out.push(7);
+            write_len(out, alternatives.len());
+            for alternative in alternatives {
+                write_pattern(out, alternative);
+            }
+        },
+    }
+}
+
+fn write_segment(out: &mut Vec<u8>, segment: &Segment) {
+    match segment {
+        Segment::Literal(s) => { out.push(0); write_str(out, s); },
+        Segment::AnyChar => out.push(1),
+        Segment::AnyChars => out.push(2),
+        Segment::Class(class) => {
+            out.push(3);
+            out.push(class.negated as u8);
+            write_len(out, class.ranges.len());
+            for &(first, last) in &class.ranges {
+                out.extend_from_slice(&(first as u32).to_le_bytes());
+                out.extend_from_slice(&(last as u32).to_le_bytes());
+            }
+        },
+        Segment::AnyCharExceptSeparator => out.push(4),
+        Segment::AnyCharsExceptSeparator => out.push(5),
+        Segment::AnyDirs => out.push(6),
+        Segment::AnyPath => out.push(7),
+        Segment::LiteralIgnoreCase(s) => { out.push(8); write_str(out, s); },
+        Segment::AnyVisibleChars => out.push(9),
+        Segment::AnyVisibleDirs => out.push(10),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], FromBytesError> {
+        let end = self.offset.checked_add(n).filter(|&end| end <= self.bytes.len())
+            .ok_or(FromBytesError::Truncated)?;
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, FromBytesError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn flag(&mut self) -> Result<bool, FromBytesError> {
+        let offset = self.offset;
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(FromBytesError::Invalid { offset }),
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32, FromBytesError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn len(&mut self) -> Result<usize, FromBytesError> {
+        self.u32().map(|len| len as usize)
+    }
+
+    fn string(&mut self) -> Result<String, FromBytesError> {
+        let len = self.len()?;
+        let offset = self.offset;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|_| FromBytesError::Invalid { offset })
+    }
+
+    fn char(&mut self) -> Result<char, FromBytesError> {
+        let offset = self.offset;
+        char::from_u32(self.u32()?).ok_or(FromBytesError::Invalid { offset })
+    }
+
+    fn glob(&mut self) -> Result<Glob, FromBytesError> {
+        let source = self.string()?;
+        let offset = self.offset;
+        let case = match self.byte()? {
+            0 => CaseMode::Sensitive,
+            1 => CaseMode::Insensitive,
+            2 => CaseMode::AsciiInsensitive,
+            3 => CaseMode::TurkicInsensitive,
+            _ => return Err(FromBytesError::Invalid { offset }),
+        };
+        let unanchored = self.flag()?;
+        let offset = self.offset;
+        let graphemes = self.flag()?;
+        if graphemes && cfg!(not(feature = "unicode-segmentation")) {
+            // would match by chars instead
+            return Err(FromBytesError::Invalid { offset });
+        }
+        let offset = self.offset;
+        let normalized = self.flag()?;
+        if normalized && cfg!(not(feature = "unicode-normalization")) {
+            return Err(FromBytesError::Invalid { offset });
+        }
+        let offset = self.offset;
+        let has_dfa = self.flag()?;
+        let pattern = self.pattern(0)?;
+        let dfa = if has_dfa {
+            Some(pattern.compile_dfa().map_err(|_| FromBytesError::Invalid { offset })?)
+        } else {
+            None
+        };
+        Ok(Glob { pattern, source, case, unanchored, graphemes, normalized, dfa })
+    }
+
+    /// A pattern inside `nesting` alternatives.
+    fn pattern(&mut self, nesting: usize) -> Result<GlobPattern, FromBytesError> {
+        let offset = self.offset;
+        Ok(match self.byte()? {
+            0 => GlobPattern::MatchAny,
+            1 => {
+                let mut parts = MultipartVec::new();
+                for _ in 0..self.len()? {
+                    let offset = self.offset;
+                    parts.push(match self.byte()? {
+                        0 => Multipart::ExactStart(self.string()?),
+                        1 => Multipart::AnyUntil(self.string()?),
+                        2 => Multipart::AnyUntilExactEnd(self.string()?),
+                        3 => Multipart::AnyEnd,
+                        _ => return Err(FromBytesError::Invalid { offset }),
+                    });
+                }
+                GlobPattern::Multipart(parts)
+            },
+            2 => GlobPattern::MatchEnd(self.string()?),
+            3 => GlobPattern::MatchStart(self.string()?),
+            4 => GlobPattern::MatchBothEnds(self.string()?, self.string()?),
+            5 => GlobPattern::MatchFull(self.string()?),
+            6 => {
+                let mut segments = Vec::new();
+                for _ in 0..self.len()? {
+                    segments.push(self.segment()?);
+                }
+                GlobPattern::Segmented(segments)
+            },
+            7 if nesting < MAX_NESTING => {
+                let mut alternatives = Vec::new();
+                for _ in 0..self.len()? {
+                    alternatives.push(self.pattern(nesting + 1)?);
+                }
+                GlobPattern::Alternatives(alternatives)
+            },
+            _ => return Err(FromBytesError::Invalid { offset }),
+        })
+    }
+
+    fn segment(&mut self) -> Result<Segment, FromBytesError> {
+        let offset = self.offset;
+        Ok(match self.byte()? {
+            0 => Segment::Literal(self.string()?),
+            1 => Segment::AnyChar,
+            2 => Segment::AnyChars,
+            3 => {
+                let negated = self.flag()?;
+                let mut ranges = Vec::new();
+                for _ in 0..self.len()? {
+                    ranges.push((self.char()?, self.char()?));
+                }
+                Segment::Class(CharClass { negated, ranges })
+            },
+            4 => Segment::AnyCharExceptSeparator,
+            5 => Segment::AnyCharsExceptSeparator,
+            6 => Segment::AnyDirs,
+            7 => Segment::AnyPath,
+            8 => Segment::LiteralIgnoreCase(self.string()?),
+            9 => Segment::AnyVisibleChars,
+            10 => Segment::AnyVisibleDirs,
+            _ => return Err(FromBytesError::Invalid { offset }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromBytesError;
+    use crate::{Glob, GlobList};
+
+    fn list() -> GlobList {
+        let mut list = GlobList::build(&[
+            "*.rs".to_string(),
+            "src/**/[a-c]?.txt".to_string(),
+            "{foo,bar}*baz".to_string(),
+            "!target/*".to_string(),
+        ]).unwrap();
+        list.add(Glob::builder("ÄB*").case_insensitive(true).dfa(true).build().unwrap());
+        list.add(Glob::builder("log").unanchored(true).build().unwrap());
+        list
+    }
+
+    #[test]
+    fn round_trips() {
+        let list = list();
+        let read = GlobList::from_bytes(&list.to_bytes()).unwrap();
+        let patterns = |list: &GlobList| list.iter().map(|(id, e)| (id, e.as_glob().as_str().to_string())).collect::<Vec<_>>();
+        assert_eq!(patterns(&read), patterns(&list));
+        for value in &["lib.rs", "target/lib.rs", "src/a/b/ax.txt", "foo-baz", "äbc", "a log line", "other"] {
+            assert_eq!(read.any_match(value), list.any_match(value), "{}", value);
+        }
+        assert_eq!(read.to_bytes(), list.to_bytes());
+    }
+
+    #[test]
+    fn rejects_what_it_did_not_write() {
+        let bytes = list().to_bytes();
+        assert_eq!(GlobList::from_bytes(b"PK\x03\x04zip").unwrap_err(), FromBytesError::NotAGlobList);
+        assert_eq!(GlobList::from_bytes(b"GLB").unwrap_err(), FromBytesError::Truncated);
+        let mut newer = bytes.clone();
+        newer[4] = 9;
+        assert_eq!(GlobList::from_bytes(&newer).unwrap_err(), FromBytesError::UnsupportedVersion(9));
+        for len in 6..bytes.len() {
+            assert!(GlobList::from_bytes(&bytes[..len]).is_err());
+        }
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(matches!(GlobList::from_bytes(&trailing), Err(FromBytesError::Invalid { .. })));
+        assert!(GlobList::from_bytes(&GlobList::new().to_bytes()).unwrap().is_empty());
+
+        let any: &[u8] = &[0];
+        assert_eq!(GlobList::from_bytes(&raw(&[any, any], &[(0, 1, 0), (3, 1, 1)])).unwrap().len(), 2);
+        // both entries on the same pattern
+        assert!(is_invalid(&raw(&[any, any], &[(0, 1, 0), (1, 1, 0)])));
+        // duplicate and out of order ids
+        assert!(is_invalid(&raw(&[any, any], &[(1, 1, 0), (1, 1, 1)])));
+        assert!(is_invalid(&raw(&[any, any], &[(2, 1, 0), (1, 1, 1)])));
+        // past the end of the bucket, in the other bucket, the id the next pattern gets and a missing entry
+        assert!(is_invalid(&raw(&[any, any], &[(0, 1, 0), (1, 1, 2)])));
+        assert!(is_invalid(&raw(&[any], &[(0, 0, 0)])));
+        assert!(is_invalid(&raw(&[any], &[(10, 1, 0)])));
+        assert!(is_invalid(&raw(&[any, any], &[(0, 1, 0)])));
+    }
+
+    /// A list of case sensitive `x` patterns with the pattern trees in `patterns`, and `entries` of
+    /// (id, bucket, index), none of them negated.
+    fn raw(patterns: &[&[u8]], entries: &[(u32, u8, u32)]) -> Vec<u8> {
+        let mut out = b"GLBR".to_vec();
+        out.extend_from_slice(&super::VERSION.to_le_bytes());
+        out.extend_from_slice(&10u32.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&(patterns.len() as u32).to_le_bytes());
+        for pattern in patterns {
+            out.extend_from_slice(&1u32.to_le_bytes());
+            out.extend_from_slice(b"x\0\0\0\0\0");
+            out.extend_from_slice(pattern);
+        }
+        out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for &(id, bucket, index) in entries {
+            out.extend_from_slice(&id.to_le_bytes());
+            out.push(bucket);
+            out.extend_from_slice(&index.to_le_bytes());
+            out.push(0);
+        }
+        out
+    }
+
+    fn is_invalid(bytes: &[u8]) -> bool {
+        matches!(GlobList::from_bytes(bytes), Err(FromBytesError::Invalid { .. }))
+    }
+
+    #[test]
+    fn rejects_broken_patterns() {
+        let alternatives: &[u8] = &[7, 2, 0, 0, 0, 5, 1, 0, 0, 0, b'a', 0];
+        assert!(GlobList::from_bytes(&raw(&[alternatives], &[(0, 1, 0)])).unwrap().any_match("a"));
+        // nested deeper than any built pattern, far too deep to read recursively
+        let mut nested = [7, 1, 0, 0, 0].repeat(1_000_000);
+        nested.push(0);
+        assert!(is_invalid(&raw(&[&nested], &[(0, 1, 0)])));
+        // a multipart with an unknown part, an unknown pattern and an unknown segment
+        assert!(is_invalid(&raw(&[&[1, 1, 0, 0, 0, 4]], &[(0, 1, 0)])));
+        assert!(is_invalid(&raw(&[&[8]], &[(0, 1, 0)])));
+        assert!(is_invalid(&raw(&[&[6, 1, 0, 0, 0, 11]], &[(0, 1, 0)])));
+        let mut trailing = raw(&[&[0]], &[(0, 1, 0)]);
+        trailing.push(0);
+        assert!(is_invalid(&trailing));
+    }
+}
@@ -0,0 +1,143 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Patterns borrowing their literals from the pattern string, see [`GlobPatternRef`].
+
+use crate::{build_glob_pattern, find_literal, glob_match_prebuilt, match_both_ends, GlobError, GlobPattern};
+
+/// A pattern borrowing its literals from the string it was built from instead of copying them,
+/// for patterns read from a long-lived buffer and only used for a while.
+///
+/// Only patterns of literals and single `*` borrow, building and matching them doesn't allocate.
+/// Anything else (`?`, classes, braces, `**`) is built as an owned [`GlobPattern`].
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub enum GlobPatternRef<'a> {
+    MatchAny,
+    MatchEnd(&'a str),
+    MatchStart(&'a str),
+    /// (Start,End)
+    MatchBothEnds(&'a str, &'a str),
+    MatchFull(&'a str),
+    /// The whole pattern, with more than one `*`, split at its wildcards when matching.
+    Multipart(&'a str),
+    Owned(GlobPattern),
+}
+
+impl<'a> GlobPatternRef<'a> {
+    /// Builds `pattern` the same way as [`build_glob_pattern`], with the same errors.
+    pub fn new(pattern: &'a str) -> Result<GlobPatternRef<'a>, GlobError> {
+        if pattern.is_empty() {
+            return Err(GlobError::EmptyPattern);
+        }
+        if pattern.contains(['?', '[', '{']) || pattern.contains("**") {
+            return build_glob_pattern(pattern).map(GlobPatternRef::Owned);
+        }
+        Ok(match pattern.bytes().filter(|&ch| ch == b'*').count() {
+            0 => GlobPatternRef::MatchFull(pattern),
+            1 if pattern == "*" => GlobPatternRef::MatchAny,
+            1 => match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+                (Some(end), _) => GlobPatternRef::MatchEnd(end),
+                (_, Some(start)) => GlobPatternRef::MatchStart(start),
+                _ => {
+                    let wildcard = pattern.find('*').unwrap();
+                    GlobPatternRef::MatchBothEnds(&pattern[..wildcard], &pattern[wildcard + 1..])
+                },
+            },
+            _ => GlobPatternRef::Multipart(pattern),
+        })
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            GlobPatternRef::MatchAny => true,
+            GlobPatternRef::MatchEnd(end) => value.ends_with(end),
+            GlobPatternRef::MatchStart(start) => value.starts_with(start),
+            GlobPatternRef::MatchBothEnds(start, end) => match_both_ends(start, end, value),
+            GlobPatternRef::MatchFull(full) => value == *full,
+            GlobPatternRef::Multipart(pattern) => match_multipart(pattern, value),
+            GlobPatternRef::Owned(pattern) => glob_match_prebuilt(pattern, value),
+        }
+    }
+
+    /// Whether the literals are borrowed rather than built into a [`GlobPattern`].
+    pub fn is_borrowed(&self) -> bool {
+        !matches!(self, GlobPatternRef::Owned(_))
+    }
+
+    /// The same pattern owning its literals, for keeping it after the string it borrows from is gone.
+    pub fn into_owned(self) -> GlobPattern {
+        match self {
+            GlobPatternRef::MatchAny => GlobPattern::MatchAny,
+            GlobPatternRef::MatchEnd(end) => GlobPattern::MatchEnd(end.to_string()),
+            GlobPatternRef::MatchStart(start) => GlobPattern::MatchStart(start.to_string()),
+            GlobPatternRef::MatchBothEnds(start, end) => GlobPattern::MatchBothEnds(start.to_string(), end.to_string()),
+            GlobPatternRef::MatchFull(full) => GlobPattern::MatchFull(full.to_string()),
+            // already checked when it was built
+            GlobPatternRef::Multipart(pattern) => build_glob_pattern(pattern).unwrap(),
+            GlobPatternRef::Owned(pattern) => pattern,
+        }
+    }
+}
+
+/// Matches a pattern with more than one `*` and nothing else, like the owned multipart matcher
+/// each literal between the first and last is found at its leftmost position.
+fn match_multipart(pattern: &str, value: &str) -> bool {
+    let start = &pattern[..pattern.find('*').unwrap()];
+    let end = &pattern[pattern.rfind('*').unwrap() + 1..];
+    if !match_both_ends(start, end, value) {
+        return false;
+    }
+    let mut middle = &value[start.len()..value.len() - end.len()];
+    for literal in pattern[start.len() + 1..pattern.len() - end.len() - 1].split('*') {
+        match find_literal(middle, literal) {
+            Some(found) => middle = &middle[found + literal.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, glob_match_prebuilt, GlobError, GlobPatternRef};
+
+    #[test]
+    fn matches_like_the_owned_pattern() {
+        let patterns = ["*", "abc", "*.rs", "src/*", "a*a", "*a*", "a*b*c", "*ab*ab*", "a*b*", "*a*b", "x?y", "[ab]*", "{a,b}*", "a/**"];
+        let values = ["", "a", "aa", "aba", "abab", "abcab", "abxbyc", "acb", "lib.rs", "src/x", "xzy", "a/b/c", "bcd"];
+        for pattern in &patterns {
+            let borrowed = GlobPatternRef::new(pattern).unwrap();
+            let owned = build_glob_pattern(pattern).unwrap();
+            assert_eq!(borrowed.is_borrowed(), !pattern.contains(['?', '[', '{']) && !pattern.contains("**"), "{}", pattern);
+            for value in &values {
+                assert_eq!(borrowed.is_match(value), glob_match_prebuilt(&owned, value), "{} {}", pattern, value);
+            }
+            assert_eq!(borrowed.into_owned(), owned);
+        }
+    }
+
+    #[test]
+    fn borrows_from_the_pattern() {
+        let config = String::from("*.rs\nsrc/*/mod.rs");
+        let patterns : Vec<GlobPatternRef<'_>> = config.lines().map(|line| GlobPatternRef::new(line).unwrap()).collect();
+        assert_eq!(patterns[0], GlobPatternRef::MatchEnd(".rs"));
+        assert_eq!(patterns[1], GlobPatternRef::MatchBothEnds("src/", "/mod.rs"));
+        assert_eq!(GlobPatternRef::new(""), Err(GlobError::EmptyPattern));
+        assert_eq!(GlobPatternRef::new("a[b").unwrap_err(), build_glob_pattern("a[b").unwrap_err());
+    }
+}
@@ -0,0 +1,118 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Brace expansion, `*.{jpg,png}` is built as the alternatives `*.jpg` and `*.png`.
+
+use crate::GlobError;
+
+/// Expands every `{a,b,...}` group in `pattern`, nested groups included, into the patterns it stands for.
+/// Braces inside a `[...]` class are left alone, an unterminated group is an error.
+///
+/// With `max_segments` it fails with [`GlobError::TooManySegments`] once there are more expansions than that,
+/// each having at least one segment. It stops right there, `{a,b}` repeated expands to exponentially many patterns.
+pub(crate) fn expand_braces(pattern: &str, max_segments: Option<usize>) -> Result<Vec<String>, GlobError> {
+    // checked up front, positions in the expansions don't match the ones in `pattern`
+    let mut open = Vec::new();
+    find_outside_classes(pattern, 0, |ch, i| {
+        match ch {
+            '{' => open.push(i),
+            '}' => {
+                open.pop();
+            },
+            _ => (),
+        }
+        false
+    });
+    if let Some(&position) = open.first() {
+        return Err(GlobError::UnterminatedBraces { position });
+    }
+
+    let mut expanded = Vec::new();
+    let limit = max_segments.unwrap_or(usize::MAX);
+    if !expand_into(pattern, &mut expanded, limit) {
+        return Err(GlobError::TooManySegments { max: limit });
+    }
+    Ok(expanded)
+}
+
+/// Expects the braces in `pattern` to be balanced, returns false once there are more than `limit` expansions.
+fn expand_into(pattern: &str, expanded: &mut Vec<String>, limit: usize) -> bool {
+    let open = match find_outside_classes(pattern, 0, |ch, _| ch == '{') {
+        Some(open) => open,
+        None => {
+            expanded.push(pattern.to_string());
+            return expanded.len() <= limit;
+        },
+    };
+
+    // commas of this group and its closing brace, skipping over nested groups
+    let mut depth = 0;
+    let mut separators = vec![open];
+    let close = find_outside_classes(pattern, open + 1, |ch, i| {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return true,
+            '}' => depth -= 1,
+            ',' if depth == 0 => separators.push(i),
+            _ => (),
+        }
+        false
+    }).expect("unterminated group");
+    separators.push(close);
+
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    for alternative in separators.windows(2) {
+        let alternative = &pattern[alternative[0] + 1..alternative[1]];
+        // later groups (and nested ones) get expanded by the recursion
+        if !expand_into(&format!("{}{}{}", prefix, alternative, suffix), expanded, limit) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Position of the first char from `from` that `found` accepts, not counting chars inside `[...]` classes.
+fn find_outside_classes(pattern: &str, from: usize, mut found: impl FnMut(char, usize) -> bool) -> Option<usize> {
+    let mut pos = from;
+    while let Some(ch) = pattern[pos..].chars().next() {
+        if ch == '[' {
+            if let Some(end) = class_end(&pattern[pos + 1..]) {
+                pos += 1 + end + 1;
+                continue;
+            }
+        }
+        if found(ch, pos) {
+            return Some(pos);
+        }
+        pos += ch.len_utf8();
+    }
+    None
+}
+
+/// Position of the `]` closing a class, `class` starting right after the `[`.
+/// Same rules as parsing a class, `!` or `^` first negates it and a `]` right after that is part of it.
+fn class_end(class: &str) -> Option<usize> {
+    let mut skip = 0;
+    if class.starts_with(['!', '^']) {
+        skip += 1;
+    }
+    if class[skip..].starts_with(']') {
+        skip += 1;
+    }
+    class[skip..].find(']').map(|end| skip + end)
+}
@@ -0,0 +1,70 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Bounding the work a match takes, see [`Glob::is_match_with_budget`](crate::Glob::is_match_with_budget).
+
+use std::error::Error;
+use std::fmt;
+
+/// The match was given up on after the number of steps it was allowed.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct BudgetExceeded {
+    pub max_steps: usize,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "match gave up after {} steps", self.max_steps)
+    }
+}
+
+impl Error for BudgetExceeded {}
+
+/// Steps left for a match, matchers taking one check `step` and stop when it returns false.
+#[derive(Debug)]
+pub(crate) struct Budget {
+    remaining: usize,
+    exceeded: bool,
+}
+
+impl Budget {
+    pub(crate) fn new(max_steps: usize) -> Budget {
+        Budget { remaining: max_steps, exceeded: false }
+    }
+
+    pub(crate) fn unlimited() -> Budget {
+        Budget::new(usize::MAX)
+    }
+
+    pub(crate) fn step(&mut self) -> bool {
+        self.charge(1)
+    }
+
+    /// Takes `steps` at once, for matchers that are linear anyway and aren't counted step by step.
+    pub(crate) fn charge(&mut self, steps: usize) -> bool {
+        match self.remaining.checked_sub(steps) {
+            Some(remaining) => self.remaining = remaining,
+            None => self.exceeded = true,
+        }
+        !self.exceeded
+    }
+
+    pub(crate) fn exceeded(&self) -> bool {
+        self.exceeded
+    }
+}
@@ -0,0 +1,193 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching byte strings that aren't necessarily valid UTF-8 (git output, network captures),
+//! enabled by the `bstr` feature.
+
+use std::io::{self, BufRead};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{glob_match_units, record_hit, CaseMode, Glob, GlobCaseSensitive, GlobIgnoreCase, GlobList, GlobPattern, Slot};
+
+/// Compares byte by byte, so invalid UTF-8 in `value` is fine.
+fn glob_match_bstr(pattern: &GlobPattern, value: &BStr) -> bool {
+    glob_match_units(pattern, || value.iter().copied(), || value.iter().rev().copied())
+}
+
+/// Compares case folded chars, invalid UTF-8 in `value` becomes U+FFFD which only wildcards match,
+/// unless the pattern only folds ASCII letters and the bytes can be compared as they are.
+fn glob_match_bstr_ignore_case(glob: &Glob, value: &BStr) -> bool {
+    if glob.case == CaseMode::AsciiInsensitive {
+        return glob_match_units(
+            &glob.pattern,
+            || value.iter().map(u8::to_ascii_lowercase),
+            || value.iter().rev().map(u8::to_ascii_lowercase));
+    }
+    glob_match_units(
+        &glob.pattern,
+        || value.chars().flat_map(|c| glob.case.fold_char(c)),
+        || value.chars().rev().flat_map(|c| glob.case.fold_char(c).rev()))
+}
+
+impl GlobCaseSensitive {
+    /// Matches a byte string (`&BStr`, `&BString`, `&[u8]`, ...), which doesn't have to be valid UTF-8.
+    pub fn is_match_bstr(&self, value: impl AsRef<[u8]>) -> bool {
+        glob_match_bstr(&self.0.pattern, value.as_ref().as_bstr())
+    }
+}
+
+impl GlobIgnoreCase {
+    /// Matches a byte string (`&BStr`, `&BString`, `&[u8]`, ...), invalid UTF-8 in it can only be matched by wildcards.
+    pub fn is_match_bstr(&self, value: impl AsRef<[u8]>) -> bool {
+        glob_match_bstr_ignore_case(&self.0, value.as_ref().as_bstr())
+    }
+}
+
+impl GlobList {
+    pub fn any_match_bstr(&self, value: impl AsRef<[u8]>) -> bool {
+        let value = value.as_ref().as_bstr();
+        if self.negated_count > 0 {
+            return self.last_matching(|slot| self.slot_is_match_bstr(slot, value)).is_some_and(|e| !e.negated);
+        }
+        self.ignore_case_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| record_hit(&self.ignore_case_hits, i, glob_match_bstr_ignore_case(&p.0, value))) ||
+            self.case_sensitive_patterns
+                .iter()
+                .enumerate()
+                .any(|(i, p)| record_hit(&self.case_sensitive_hits, i, glob_match_bstr(&p.0.pattern, value)))
+    }
+
+    pub fn all_match_bstr(&self, value: impl AsRef<[u8]>) -> bool {
+        let value = value.as_ref().as_bstr();
+        if self.negated_count > 0 {
+            return self.all_match_negated(|slot| self.slot_is_match_bstr(slot, value));
+        }
+        // least likely to match first, see reorder_by_selectivity
+        self.ignore_case_patterns
+            .iter()
+            .enumerate()
+            .rev()
+            .all(|(i, p)| record_hit(&self.ignore_case_hits, i, glob_match_bstr_ignore_case(&p.0, value))) &&
+            self.case_sensitive_patterns
+                .iter()
+                .enumerate()
+                .rev()
+                .all(|(i, p)| record_hit(&self.case_sensitive_hits, i, glob_match_bstr(&p.0.pattern, value)))
+    }
+
+    fn slot_is_match_bstr(&self, slot: Slot, value: &BStr) -> bool {
+        match slot {
+            Slot::CaseSensitive(i) => glob_match_bstr(&self.case_sensitive_patterns[i].0.pattern, value),
+            Slot::IgnoreCase(i) => glob_match_bstr_ignore_case(&self.ignore_case_patterns[i].0, value),
+        }
+    }
+
+    /// Reads `reader` line by line (like bstr's `byte_lines`) and yields the lines matching any pattern.
+    pub fn matching_byte_lines<R: BufRead>(&self, reader: R) -> MatchingByteLines<'_, R> {
+        MatchingByteLines {
+            glob_list: self,
+            reader,
+            invert: false,
+        }
+    }
+}
+
+/// Iterator over the lines of a reader that match a [`GlobList`], created by [`GlobList::matching_byte_lines`].
+///
+/// Line terminators (`\n` or `\r\n`) are stripped from the yielded lines.
+#[derive(Debug)]
+pub struct MatchingByteLines<'a, R> {
+    glob_list: &'a GlobList,
+    reader: R,
+    invert: bool,
+}
+
+impl<R> MatchingByteLines<'_, R> {
+    /// Yield the lines that don't match any pattern instead.
+    pub fn invert(mut self) -> Self {
+        self.invert = !self.invert;
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for MatchingByteLines<'_, R> {
+    type Item = io::Result<BString>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line = Vec::new();
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => return None,
+                Ok(_) => (),
+                Err(e) => return Some(Err(e)),
+            }
+            if line.last() == Some(&b'\n') {
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+            }
+            if self.glob_list.any_match_bstr(&line) != self.invert {
+                return Some(Ok(BString::from(line)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::{BString, ByteSlice};
+
+    use crate::{GlobCaseSensitive, GlobIgnoreCase, GlobList};
+
+    #[test]
+    fn is_match_bstr_invalid_utf8() {
+        let pattern = GlobCaseSensitive::build("*.log").unwrap();
+        assert!(pattern.is_match_bstr(b"caf\xe9.log".as_bstr()));
+        assert!(!pattern.is_match_bstr(b"caf\xe9.lo\xff".as_bstr()));
+        let pattern = GlobCaseSensitive::build("caf*.log").unwrap();
+        assert!(pattern.is_match_bstr(BString::from(&b"caf\xe9\xff.log"[..])));
+        assert!(pattern.is_match_bstr(b"caf\xe9\xff.log"));
+        let pattern = GlobCaseSensitive::build("*\u{e9}*").unwrap();
+        assert!(pattern.is_match_bstr("caf\u{e9} au lait".as_bytes().as_bstr()));
+        assert!(!pattern.is_match_bstr(b"caf\xe9 au lait".as_bstr()));
+    }
+
+    #[test]
+    fn is_match_bstr_ignore_case() {
+        let pattern = GlobIgnoreCase::build("GIT*OUTPUT").unwrap();
+        assert!(pattern.is_match_bstr(b"git \xff output".as_bstr()));
+        assert!(!pattern.is_match_bstr(b"git \xff outpu\xff".as_bstr()));
+    }
+
+    #[test]
+    fn matching_byte_lines() {
+        let glob_list = GlobList::build(&["*.rs".to_string()]).unwrap();
+        let input = &b"src/lib.rs\r\nsrc/\xff.rs\nREADME.md\nsrc/main.rs"[..];
+        let lines : Vec<BString> = glob_list.matching_byte_lines(input).map(Result::unwrap).collect();
+        assert_eq!(lines, vec![
+            BString::from("src/lib.rs"),
+            BString::from(&b"src/\xff.rs"[..]),
+            BString::from("src/main.rs")]);
+        let lines : Vec<BString> = glob_list.matching_byte_lines(input).invert().map(Result::unwrap).collect();
+        assert_eq!(lines, vec![BString::from("README.md")]);
+    }
+}
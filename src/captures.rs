@@ -0,0 +1,149 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::{GlobPattern, Multipart, Segment};
+
+/// The parts of a value matched by each wildcard in a pattern, in pattern order.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Captures<'a> {
+    value: &'a str,
+    /// borrowed from a [`MatchContext`](crate::MatchContext) when matched with one
+    ranges: Cow<'a, [Range<usize>]>,
+}
+
+impl<'a> Captures<'a> {
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The text matched by the wildcard at `index`.
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        self.ranges.get(index).map(|r| &self.value[r.clone()])
+    }
+
+    /// The byte range in the matched value of the wildcard at `index`.
+    pub fn range(&self, index: usize) -> Option<Range<usize>> {
+        self.ranges.get(index).cloned()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.ranges.iter().map(move |r| &self.value[r.clone()])
+    }
+
+    /// Parses the text matched by the wildcard at `index`, e.g. the run number in `build-*.log`.
+    pub fn parse<T: FromStr>(&self, index: usize) -> Result<T, CaptureParseError<T::Err>> {
+        let capture = self.get(index).ok_or(CaptureParseError::NoSuchCapture(index))?;
+        capture.parse().map_err(|error| CaptureParseError::Invalid {
+            index,
+            capture: capture.to_string(),
+            error,
+        })
+    }
+
+    /// Parses the text matched by every wildcard as the same type.
+    pub fn parse_all<T: FromStr>(&self) -> Result<Vec<T>, CaptureParseError<T::Err>> {
+        (0..self.len()).map(|i| self.parse(i)).collect()
+    }
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum CaptureParseError<E> {
+    /// The pattern has fewer wildcards than the index asked for.
+    NoSuchCapture(usize),
+    /// The captured text couldn't be parsed.
+    Invalid {
+        index: usize,
+        capture: String,
+        error: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for CaptureParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureParseError::NoSuchCapture(index) => write!(f, "no capture with index {}", index),
+            CaptureParseError::Invalid { index, capture, error } =>
+                write!(f, "could not parse capture {} ('{}'): {}", index, capture, error),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for CaptureParseError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CaptureParseError::NoSuchCapture(_) => None,
+            CaptureParseError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Turns the literal spans of a match into the ranges between them, which is what the wildcards matched.
+pub(crate) fn captures_from_spans<'a>(pattern: &GlobPattern, value: &'a str, spans: Vec<Range<usize>>) -> Captures<'a> {
+    let mut ranges = Vec::with_capacity(spans.len() + 1);
+    capture_ranges_into(pattern, value, &spans, &mut ranges);
+    Captures { value, ranges: Cow::Owned(ranges) }
+}
+
+/// Same as [`captures_from_spans`], but borrows the ranges from `ranges` after filling it.
+pub(crate) fn captures_from_spans_in<'a>(pattern: &GlobPattern, value: &'a str, spans: &[Range<usize>], ranges: &'a mut Vec<Range<usize>>) -> Captures<'a> {
+    capture_ranges_into(pattern, value, spans, ranges);
+    Captures { value, ranges: Cow::Borrowed(ranges) }
+}
+
+fn capture_ranges_into(pattern: &GlobPattern, value: &str, spans: &[Range<usize>], ranges: &mut Vec<Range<usize>>) {
+    ranges.clear();
+    let (wildcard_start, wildcard_end) = match pattern {
+        GlobPattern::MatchAny => {
+            ranges.push(0..value.len());
+            return;
+        },
+        GlobPattern::MatchFull(_) => (false, false),
+        GlobPattern::MatchStart(_) => (false, true),
+        GlobPattern::MatchEnd(_) => (true, false),
+        GlobPattern::MatchBothEnds(_, _) => (false, false),
+        GlobPattern::Multipart(multi) => (
+            !matches!(multi.first(), Some(Multipart::ExactStart(_))),
+            !matches!(multi.last(), Some(Multipart::AnyUntilExactEnd(_)))),
+        GlobPattern::Segmented(segments) => (
+            !matches!(segments.first(), Some(Segment::Literal(_)) | Some(Segment::LiteralIgnoreCase(_))),
+            !matches!(segments.last(), Some(Segment::Literal(_)) | Some(Segment::LiteralIgnoreCase(_)))),
+        // only ever called with the alternative that matched
+        GlobPattern::Alternatives(_) => unreachable!("captures of alternatives"),
+    };
+
+    let mut pos = 0;
+    for (i, span) in spans.iter().enumerate() {
+        if i > 0 || wildcard_start {
+            ranges.push(pos..span.start);
+        }
+        pos = span.end;
+    }
+    if wildcard_end {
+        ranges.push(pos..value.len());
+    }
+}
@@ -0,0 +1,199 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Case folding for case insensitive matching, both the pattern and the value are folded before comparing.
+//!
+//! With the `unicode` feature chars are folded the way Unicode simple case folding does, one char to one
+//! char, so `ẞ` matches `ß` but not `SS`. `full-case-folding` folds the way full case folding does instead,
+//! where one char can fold to several, `ß` and `ẞ` both fold to `ss`. Without `unicode` only ASCII letters
+//! are folded, which keeps the Unicode case tables out of the binary.
+//!
+//! [`CaseFold::Turkic`] folds `I` to the dotless `ı` and `İ` to `i` first, the way Turkish and Azerbaijani pair them.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Which language's case pairs matching ignoring case goes by, see [`GlobBuilder::case_fold`](crate::GlobBuilder::case_fold).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub enum CaseFold {
+    /// the same for every language, `I` and `i` are the same letter
+    #[default]
+    Default,
+    /// Turkish and Azerbaijani, `I` is the capital of the dotless `ı` and `İ` the capital of `i`
+    Turkic,
+}
+
+/// How a pattern compares letters.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum CaseMode {
+    Sensitive,
+    /// both sides case folded, see [`fold_case`]
+    Insensitive,
+    /// only ASCII letters folded, everything else compared as is
+    AsciiInsensitive,
+    /// both sides case folded with Turkic casing, see [`CaseFold::Turkic`]
+    TurkicInsensitive,
+}
+
+impl CaseMode {
+    /// `value` the way a pattern with this mode sees it.
+    pub(crate) fn fold(self, value: &str) -> Cow<'_, str> {
+        match self {
+            CaseMode::Sensitive => Cow::Borrowed(value),
+            CaseMode::Insensitive => Cow::Owned(fold_case(value)),
+            CaseMode::AsciiInsensitive => Cow::Owned(value.to_ascii_lowercase()),
+            CaseMode::TurkicInsensitive => Cow::Owned(value.chars().flat_map(fold_char_turkic).collect()),
+        }
+    }
+
+    /// Whether chars are folded by the Unicode case tables (or their ASCII subset without `unicode`),
+    /// one char at a time with [`CaseMode::fold_char`].
+    pub(crate) fn folds_chars(self) -> bool {
+        matches!(self, CaseMode::Insensitive | CaseMode::TurkicInsensitive)
+    }
+
+    /// What `ch` folds to for a mode where [`CaseMode::folds_chars`].
+    pub(crate) fn fold_char(self, ch: char) -> Folded {
+        match self {
+            CaseMode::TurkicInsensitive => fold_char_turkic(ch),
+            _ => fold_char(ch),
+        }
+    }
+}
+
+/// What a char folds to.
+#[cfg(not(feature = "full-case-folding"))]
+pub(crate) type Folded = std::iter::Once<char>;
+
+/// What a char folds to, up to 3 chars (e.g. `ΐ`).
+#[cfg(feature = "full-case-folding")]
+pub(crate) type Folded = std::iter::Take<std::array::IntoIter<char, 3>>;
+
+/// Folds a value for case insensitive comparison.
+pub(crate) fn fold_case(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_ascii_lowercase();
+    }
+    value.chars().flat_map(fold_char).collect()
+}
+
+/// Char by char version of [`fold_case`].
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn fold_char(ch: char) -> Folded {
+    std::iter::once(ch.to_ascii_lowercase())
+}
+
+#[cfg(all(feature = "unicode", not(feature = "full-case-folding")))]
+pub(crate) fn fold_char(ch: char) -> Folded {
+    std::iter::once(simple_fold(ch))
+}
+
+#[cfg(feature = "full-case-folding")]
+pub(crate) fn fold_char(ch: char) -> Folded {
+    let mut folded = [ch; 3];
+    let mut len = 0;
+    match ch {
+        _ if ch.is_ascii() => {
+            folded[0] = ch.to_ascii_lowercase();
+            len = 1;
+        },
+        // to_lowercase would give `i` + U+0307, which is what it folds to, but going through
+        // to_uppercase first would lose the dot
+        'İ' => {
+            folded[..2].copy_from_slice(&['i', '\u{307}']);
+            len = 2;
+        },
+        'ı' => len = 1,
+        _ => {
+            // lowercasing first turns `ẞ` into `ß`, which then uppercases to `SS` like `ß` itself does
+            for upper in simple_lowercase(ch).to_uppercase() {
+                for lower in upper.to_lowercase() {
+                    folded[len] = lower;
+                    len += 1;
+                }
+            }
+        },
+    }
+    IntoIterator::into_iter(folded).take(len)
+}
+
+/// [`fold_char`] with the Turkic dotted and dotless `i`, the capitals are folded to their own lowercase
+/// letters, which then fold to themselves.
+fn fold_char_turkic(ch: char) -> Folded {
+    fold_char(match ch {
+        'I' => 'ı',
+        'İ' => 'i',
+        _ => ch,
+    })
+}
+
+/// Simple case folding: `ch` lowercased after uppercasing, which maps every char of a case
+/// to the same one, unless that takes more than one char, then `ch` itself.
+#[cfg(all(feature = "unicode", not(feature = "full-case-folding")))]
+fn simple_fold(ch: char) -> char {
+    match ch {
+        _ if ch.is_ascii() => ch.to_ascii_lowercase(),
+        // the Turkish dotless `ı` uppercases to `I`, but only folds to itself
+        'ı' => ch,
+        // capital sharp s, the lowercase `ß` can't come back from `SS`
+        'ẞ' => 'ß',
+        _ => single(ch.to_uppercase()).map_or(ch, |upper| single(upper.to_lowercase()).unwrap_or(ch)),
+    }
+}
+
+#[cfg(feature = "full-case-folding")]
+fn simple_lowercase(ch: char) -> char {
+    single(ch.to_lowercase()).unwrap_or(ch)
+}
+
+#[cfg(feature = "unicode")]
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+/// Every char folding to `ch` on its own, `ch` itself included, e.g. `k`, `K` and the Kelvin sign `K` for `k`.
+/// Chars folding to several chars with full case folding aren't included.
+pub(crate) fn case_variants(ch: char) -> Vec<char> {
+    let mut variants = vec![ch];
+    if let Some(others) = folded_from().get(&ch) {
+        variants.extend(others.iter().filter(|&&other| other != ch));
+    }
+    variants
+}
+
+/// The chars folding to each folded char other than themselves, found once by folding every char.
+pub(crate) fn folded_from() -> &'static HashMap<char, Vec<char>> {
+    static FOLDED_FROM: OnceLock<HashMap<char, Vec<char>>> = OnceLock::new();
+    FOLDED_FROM.get_or_init(|| {
+        let mut folded_from: HashMap<char, Vec<char>> = HashMap::new();
+        for ch in (0..=char::MAX as u32).filter_map(char::from_u32) {
+            let mut folded = fold_char(ch);
+            if let (Some(to), None) = (folded.next(), folded.next()) {
+                if to != ch {
+                    folded_from.entry(to).or_default().push(ch);
+                }
+            }
+        }
+        folded_from
+    })
+}
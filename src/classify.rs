@@ -0,0 +1,139 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Telling what kind of pattern a [`GlobPattern`] is, e.g. to look literal patterns up in a `HashMap`
+//! instead of matching them one by one.
+
+use crate::{GlobPattern, Multipart, Segment};
+
+/// What kind of matching a pattern does, see [`GlobPattern::kind`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum PatternKind {
+    /// no wildcards, only the value [`GlobPattern::literal_prefix`] returns matches, e.g. `Cargo.toml`
+    Literal,
+    /// values starting with [`GlobPattern::literal_prefix`], e.g. `src/*`
+    Prefix,
+    /// values ending with [`GlobPattern::literal_suffix`], e.g. `*.rs`
+    Suffix,
+    /// every value, e.g. `*`
+    MatchAll,
+    /// anything else, which takes the matcher
+    Other,
+}
+
+impl GlobPattern {
+    /// What kind of matching the pattern does, see [`PatternKind`].
+    ///
+    /// Goes by the compiled pattern, so with a literal separator `src/*` is [`PatternKind::Other`] as the
+    /// `*` stops at `/`, and alternatives (even of literals, `{a,b}`) are too.
+    pub fn kind(&self) -> PatternKind {
+        match self {
+            GlobPattern::MatchAny => PatternKind::MatchAll,
+            GlobPattern::MatchFull(_) => PatternKind::Literal,
+            GlobPattern::MatchStart(_) => PatternKind::Prefix,
+            GlobPattern::MatchEnd(_) => PatternKind::Suffix,
+            GlobPattern::Segmented(segments) => match segments.as_slice() {
+                [Segment::AnyChars] | [Segment::AnyPath] => PatternKind::MatchAll,
+                [Segment::Literal(_)] => PatternKind::Literal,
+                [Segment::Literal(_), Segment::AnyChars] | [Segment::Literal(_), Segment::AnyPath] => PatternKind::Prefix,
+                [Segment::AnyChars, Segment::Literal(_)] => PatternKind::Suffix,
+                _ => PatternKind::Other,
+            },
+            GlobPattern::MatchBothEnds(..) | GlobPattern::Multipart(_) | GlobPattern::Alternatives(_) => PatternKind::Other,
+        }
+    }
+
+    /// Whether the pattern only matches one value, see [`PatternKind::Literal`].
+    pub fn is_literal(&self) -> bool {
+        self.kind() == PatternKind::Literal
+    }
+
+    /// Whether the pattern matches every value, see [`PatternKind::MatchAll`].
+    pub fn is_match_all(&self) -> bool {
+        self.kind() == PatternKind::MatchAll
+    }
+
+    /// An estimate of how much work matching a value takes compared to other patterns, 0 for `*`,
+    /// 1 for comparing a literal and more for every wildcard the matcher may have to backtrack over.
+    /// `*` that can't cross `/` and `**/` cost the most, as they can't simply give up and retry further on.
+    pub fn complexity(&self) -> usize {
+        match self {
+            GlobPattern::MatchAny => 0,
+            GlobPattern::MatchFull(_) | GlobPattern::MatchStart(_) | GlobPattern::MatchEnd(_) => 1,
+            GlobPattern::MatchBothEnds(..) => 2,
+            GlobPattern::Multipart(multi) => multi
+                .iter()
+                .map(|part| match part {
+                    Multipart::AnyEnd => 0,
+                    Multipart::ExactStart(_) | Multipart::AnyUntilExactEnd(_) => 1,
+                    // searching for the literal
+                    Multipart::AnyUntil(_) => 2,
+                })
+                .sum(),
+            GlobPattern::Segmented(segments) => segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Literal(_) | Segment::AnyChar | Segment::AnyCharExceptSeparator => 1,
+                    Segment::LiteralIgnoreCase(_) | Segment::Class(_) | Segment::AnyChars | Segment::AnyPath => 2,
+                    Segment::AnyCharsExceptSeparator | Segment::AnyDirs | Segment::AnyVisibleChars | Segment::AnyVisibleDirs => 4,
+                })
+                .sum(),
+            GlobPattern::Alternatives(alternatives) => alternatives.iter().map(GlobPattern::complexity).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternKind;
+    use crate::{build_glob_pattern, Glob};
+
+    #[test]
+    fn kinds() {
+        let cases = [
+            ("Cargo.toml", PatternKind::Literal),
+            ("src/*", PatternKind::Prefix),
+            ("*.rs", PatternKind::Suffix),
+            ("*", PatternKind::MatchAll),
+            ("a*b", PatternKind::Other),
+            ("a*b*c", PatternKind::Other),
+            ("log-??.txt", PatternKind::Other),
+            ("{a,b}", PatternKind::Other),
+        ];
+        for &(pattern, kind) in cases.iter() {
+            assert_eq!(build_glob_pattern(pattern).unwrap().kind(), kind, "{}", pattern);
+        }
+        let segmented = |pattern| Glob::builder(pattern).literal_separator(true).build().unwrap().pattern().kind();
+        assert_eq!(segmented("Cargo.toml"), PatternKind::Literal);
+        assert_eq!(segmented("src/**"), PatternKind::Prefix);
+        assert_eq!(segmented("**"), PatternKind::MatchAll);
+        assert_eq!(segmented("src/*"), PatternKind::Other);
+        assert!(build_glob_pattern("Cargo.toml").unwrap().is_literal() && !build_glob_pattern("*.toml").unwrap().is_literal());
+        assert!(build_glob_pattern("*").unwrap().is_match_all() && !build_glob_pattern("?*").unwrap().is_match_all());
+    }
+
+    #[test]
+    fn complexity_grows_with_wildcards() {
+        let complexity = |pattern| build_glob_pattern(pattern).unwrap().complexity();
+        assert_eq!(complexity("*"), 0);
+        assert!(complexity("*.rs") < complexity("a*b*c"));
+        assert!(complexity("a*b*c") < complexity("a*b*c*d*e"));
+        assert!(complexity("a*b") < Glob::builder("a*b").literal_separator(true).build().unwrap().pattern().complexity());
+        assert!(complexity("{a,b}") > complexity("a"));
+    }
+}
@@ -0,0 +1,166 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Building patterns out of other patterns instead of concatenating strings, which can turn `src/*` and
+//! `*.rs` into `src/**.rs`.
+
+use crate::segments::as_segments;
+use crate::{GlobPattern, Multipart, MultipartVec, Segment};
+
+impl GlobPattern {
+    /// Matches exactly `value`, whatever chars are in it, `*` included.
+    pub fn literal(value: &str) -> GlobPattern {
+        GlobPattern::MatchFull(value.to_string())
+    }
+
+    /// Matches values starting with `prefix`, `src/*` for `src/`.
+    pub fn starts_with(prefix: &str) -> GlobPattern {
+        GlobPattern::MatchStart(prefix.to_string())
+    }
+
+    /// Values matching this pattern followed by ones matching `next`. A `*` at the end of one and the start of
+    /// the other become one, and alternatives are combined with each other, `{a,b}` then `{c,d}` is `{ac,ad,bc,bd}`.
+    pub fn concat(&self, next: &GlobPattern) -> GlobPattern {
+        match (self, next) {
+            (GlobPattern::Alternatives(alternatives), _) =>
+                GlobPattern::Alternatives(alternatives.iter().map(|p| p.concat(next)).collect()),
+            (_, GlobPattern::Alternatives(alternatives)) =>
+                GlobPattern::Alternatives(alternatives.iter().map(|p| self.concat(p)).collect()),
+            _ => {
+                let mut segments = Vec::new();
+                for segment in as_segments(self).into_iter().chain(as_segments(next)) {
+                    push_segment(&mut segments, segment);
+                }
+                from_segments(segments)
+            },
+        }
+    }
+
+    /// This pattern followed by anything, `src/*` for `src/`.
+    pub fn then_any(&self) -> GlobPattern {
+        self.concat(&GlobPattern::MatchAny)
+    }
+
+    /// This pattern followed by anything ending with `suffix`, `src/*.rs` for `src/` and `.rs`.
+    pub fn ends_with(&self, suffix: &str) -> GlobPattern {
+        self.then_any().with_suffix(suffix)
+    }
+
+    /// `prefix` followed by this pattern, e.g. for putting a user's pattern in a directory, `logs/*.txt`
+    /// for `logs/` and `*.txt`.
+    pub fn with_prefix(&self, prefix: &str) -> GlobPattern {
+        GlobPattern::literal(prefix).concat(self)
+    }
+
+    /// This pattern followed by `suffix`.
+    pub fn with_suffix(&self, suffix: &str) -> GlobPattern {
+        self.concat(&GlobPattern::literal(suffix))
+    }
+}
+
+/// Adds `segment` to `segments`, joining literals and wildcards that can be one.
+pub(crate) fn push_segment(segments: &mut Vec<Segment>, segment: Segment) {
+    let joined = match (segments.last_mut(), &segment) {
+        (_, Segment::Literal(s)) | (_, Segment::LiteralIgnoreCase(s)) if s.is_empty() => true,
+        (Some(Segment::Literal(last)), Segment::Literal(s)) | (Some(Segment::LiteralIgnoreCase(last)), Segment::LiteralIgnoreCase(s)) => {
+            last.push_str(s);
+            true
+        },
+        // `*` already matches whatever the other one does
+        (Some(Segment::AnyChars), Segment::AnyChars) | (Some(Segment::AnyChars), Segment::AnyCharsExceptSeparator) |
+        (Some(Segment::AnyPath), Segment::AnyChars) | (Some(Segment::AnyPath), Segment::AnyCharsExceptSeparator) |
+        (Some(Segment::AnyCharsExceptSeparator), Segment::AnyCharsExceptSeparator) |
+        (Some(Segment::AnyVisibleChars), Segment::AnyVisibleChars) => true,
+        (Some(last @ Segment::AnyCharsExceptSeparator), Segment::AnyChars) => {
+            *last = Segment::AnyChars;
+            true
+        },
+        _ => false,
+    };
+    if !joined {
+        segments.push(segment);
+    }
+}
+
+/// The simplest pattern matching what `segments` do, the way a pattern written with only literals and `*`
+/// is built.
+pub(crate) fn from_segments(segments: Vec<Segment>) -> GlobPattern {
+    if !segments.iter().all(|s| matches!(s, Segment::Literal(_) | Segment::AnyChars)) {
+        return GlobPattern::Segmented(segments);
+    }
+    let literal = |segment: &Segment| match segment {
+        Segment::Literal(s) => s.clone(),
+        _ => unreachable!("only literals and `*` are left"),
+    };
+    match segments.as_slice() {
+        [] => GlobPattern::MatchFull(String::new()),
+        [Segment::AnyChars] => GlobPattern::MatchAny,
+        [s] => GlobPattern::MatchFull(literal(s)),
+        [s, Segment::AnyChars] => GlobPattern::MatchStart(literal(s)),
+        [Segment::AnyChars, s] => GlobPattern::MatchEnd(literal(s)),
+        [start, Segment::AnyChars, end] => GlobPattern::MatchBothEnds(literal(start), literal(end)),
+        _ => {
+            let mut parts = MultipartVec::new();
+            let mut rest = segments.as_slice();
+            if let [start @ Segment::Literal(_), after @ ..] = rest {
+                parts.push(Multipart::ExactStart(literal(start)));
+                rest = after;
+            }
+            // `*` and a literal in turn from here
+            let last = rest.len().div_ceil(2) - 1;
+            for (i, pair) in rest.chunks(2).enumerate() {
+                parts.push(match pair {
+                    [_, s] if i == last => Multipart::AnyUntilExactEnd(literal(s)),
+                    [_, s] => Multipart::AnyUntil(literal(s)),
+                    _ => Multipart::AnyEnd,
+                });
+            }
+            GlobPattern::Multipart(parts)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, Glob, GlobPattern};
+
+    #[test]
+    fn combinators() {
+        let rs = GlobPattern::starts_with("src/").then_any().ends_with(".rs");
+        assert_eq!(rs, build_glob_pattern("src/*.rs").unwrap());
+        assert_eq!(rs.to_string(), "src/*.rs");
+        let user = build_glob_pattern("*.txt").unwrap();
+        assert_eq!(user.with_prefix("logs/"), build_glob_pattern("logs/*.txt").unwrap());
+        assert_eq!(GlobPattern::literal("a*").with_suffix("?").to_string(), "a[*][?]");
+        assert_eq!(build_glob_pattern("*a").unwrap().concat(&build_glob_pattern("*b*").unwrap()), build_glob_pattern("*a*b*").unwrap());
+        assert_eq!(build_glob_pattern("a*").unwrap().concat(&build_glob_pattern("*b*c").unwrap()), build_glob_pattern("a*b*c").unwrap());
+        assert_eq!(build_glob_pattern("*x*").unwrap().concat(&build_glob_pattern("y").unwrap()), build_glob_pattern("*x*y").unwrap());
+    }
+
+    #[test]
+    fn wildcards_and_alternatives() {
+        let files = Glob::builder("**/*.{rs,toml}").literal_separator(true).build().unwrap();
+        let joined = files.pattern().with_prefix("src/");
+        for &(value, expected) in [("src/a/lib.rs", true), ("src/Cargo.toml", true), ("src/a/b.md", false), ("lib.rs", false)].iter() {
+            assert_eq!(crate::glob_match_prebuilt(&joined, value), expected, "{}", value);
+        }
+        let two = build_glob_pattern("{a,b}").unwrap().concat(&build_glob_pattern("{c,d}").unwrap());
+        assert!(["ac", "ad", "bc", "bd"].iter().all(|v| crate::glob_match_prebuilt(&two, v)));
+        assert!(!crate::glob_match_prebuilt(&two, "ab"));
+    }
+}
@@ -0,0 +1,136 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Comparing what patterns match, by running their DFAs side by side.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{Dfa, GlobError, GlobPattern};
+
+impl GlobPattern {
+    /// Whether some value matches both patterns, e.g. `src/*` and `*.rs` both match `src/lib.rs`.
+    ///
+    /// Compiles both patterns to a [`Dfa`], which fails with [`GlobError::DfaTooLarge`] for patterns
+    /// needing too many states. Patterns built to ignore case are compared as they were folded.
+    pub fn overlaps(&self, other: &GlobPattern) -> Result<bool, GlobError> {
+        let (a, b) = (self.compile_dfa()?, other.compile_dfa()?);
+        Ok(find_state_pair(&a, &b, |x, y| Dfa::is_dead(x) || Dfa::is_dead(y), |x, y| {
+            a.is_accepting(x) && b.is_accepting(y)
+        }))
+    }
+
+    /// Whether every value matching this pattern also matches `other`, e.g. `foo*bar` is a subset of `foo*`,
+    /// which makes a `foo*bar` next to `foo*` in a list redundant. Every pattern is a subset of itself.
+    ///
+    /// Fails like [`GlobPattern::overlaps`].
+    pub fn is_subset_of(&self, other: &GlobPattern) -> Result<bool, GlobError> {
+        Ok(is_subset(&self.compile_dfa()?, &other.compile_dfa()?))
+    }
+}
+
+/// Whether every value `a` accepts `b` accepts too.
+pub(crate) fn is_subset(a: &Dfa, b: &Dfa) -> bool {
+    // looking for a value `a` accepts and `b` doesn't
+    !find_state_pair(a, b, |x, _| Dfa::is_dead(x), |x, y| a.is_accepting(x) && !b.is_accepting(y))
+}
+
+/// Whether a value takes `a` and `b` to states `found` is true for, searching breadth first from
+/// their start states and not going on from the states `dead_end` is true for.
+fn find_state_pair(a: &Dfa, b: &Dfa, dead_end: impl Fn(u32, u32) -> bool, found: impl Fn(u32, u32) -> bool) -> bool {
+    // one char from each group the two DFAs together tell apart
+    let mut starts: Vec<u32> = a.boundaries().iter().chain(b.boundaries()).copied().collect();
+    starts.push(0);
+    starts.sort_unstable();
+    starts.dedup();
+    let chars: Vec<char> = starts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(char::MAX as u32 + 1);
+            // a group can start among the surrogates, which aren't chars
+            (start..end).find_map(char::from_u32)
+        })
+        .collect();
+
+    let start = (a.start_state(), b.start_state());
+    let mut seen = HashSet::new();
+    seen.insert(start);
+    let mut queue = VecDeque::from(vec![start]);
+    while let Some((x, y)) = queue.pop_front() {
+        if found(x, y) {
+            return true;
+        }
+        if dead_end(x, y) {
+            continue;
+        }
+        for &ch in &chars {
+            let next = (a.step(x, ch), b.step(y, ch));
+            if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::build_glob_pattern;
+
+    fn overlaps(a: &str, b: &str) -> bool {
+        let (a, b) = (build_glob_pattern(a).unwrap(), build_glob_pattern(b).unwrap());
+        let overlaps = a.overlaps(&b).unwrap();
+        assert_eq!(overlaps, b.overlaps(&a).unwrap());
+        overlaps
+    }
+
+    #[test]
+    fn patterns_overlap() {
+        assert!(overlaps("src/*", "*.rs"));
+        assert!(overlaps("/users/*", "/users/admin"));
+        assert!(overlaps("a*b*c", "*bc"));
+        assert!(overlaps("file?.txt", "*[0-9].txt"));
+        assert!(overlaps("*.{jpg,png}", "holiday.*"));
+        assert!(overlaps("*", "anything"));
+        assert!(!overlaps("*.rs", "*.toml"));
+        assert!(!overlaps("src/*", "tests/*"));
+        assert!(!overlaps("a*a", "a"));
+        assert!(!overlaps("file?.txt", "file??.txt"));
+        assert!(!overlaps("[a-c]*", "[!a-z]*"));
+    }
+
+    fn is_subset_of(a: &str, b: &str) -> bool {
+        build_glob_pattern(a).unwrap().is_subset_of(&build_glob_pattern(b).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn pattern_subsets() {
+        assert!(is_subset_of("foo*bar", "foo*"));
+        assert!(is_subset_of("foo*bar", "*bar"));
+        assert!(is_subset_of("*.rs", "*"));
+        assert!(is_subset_of("src/lib.rs", "src/*.rs"));
+        assert!(is_subset_of("file[0-9].txt", "file?.txt"));
+        assert!(is_subset_of("*.{jpg,png}", "*.*"));
+        assert!(is_subset_of("a*b*c", "a*c"));
+        assert!(is_subset_of("*.rs", "*.rs"));
+        assert!(!is_subset_of("foo*", "foo*bar"));
+        assert!(!is_subset_of("file?.txt", "file[0-9].txt"));
+        assert!(!is_subset_of("*", "?*"));
+        assert!(!is_subset_of("a*c", "a*b*c"));
+    }
+}
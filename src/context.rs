@@ -0,0 +1,120 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::segments::{self, as_segments};
+use crate::{fold_char, matched_spans, CaseMode, GlobPattern};
+
+/// Scratch buffers for matching, created once and passed to the `*_with` methods
+/// (e.g. [`GlobIgnoreCase::is_match_with`](crate::GlobIgnoreCase::is_match_with))
+/// so matching millions of values doesn't allocate for each one.
+///
+/// The buffers only grow, once they fit the longest value matching stops allocating altogether.
+#[derive(Debug,Clone,Default)]
+pub struct MatchContext {
+    /// the case folded value for ignore case patterns
+    pub(crate) folded: String,
+    /// literal spans of the last match
+    pub(crate) spans: Vec<Range<usize>>,
+    /// byte range in the original value of each byte in `folded`
+    original: Vec<(usize, usize)>,
+    /// wildcard ranges handed out as captures
+    pub(crate) captures: Vec<Range<usize>>,
+}
+
+impl MatchContext {
+    pub fn new() -> MatchContext {
+        MatchContext::default()
+    }
+
+    /// Case folds `value` into the buffer, the same way ignore case patterns are built.
+    pub(crate) fn fold(&mut self, value: &str) -> &str {
+        self.folded.clear();
+        self.folded.extend(value.chars().flat_map(fold_char));
+        &self.folded
+    }
+
+    /// Leaves the literal spans of a match in `self.spans` like [`MatchContext::ranges`] with `matched_spans`,
+    /// but folds `value` a char at a time while matching instead of up front, so the spans are found in
+    /// `value` itself and never have to be mapped back from its case folded form.
+    pub(crate) fn spans<'p>(&mut self, pattern: &'p GlobPattern, value: &str, case: CaseMode) -> Option<&'p GlobPattern> {
+        if !matches!(case, CaseMode::Insensitive | CaseMode::TurkicInsensitive) {
+            return self.ranges(pattern, value, case, matched_spans);
+        }
+        let spans = &mut self.spans;
+        let matched = pattern.alternatives().find(|alternative| {
+            let segments = match alternative {
+                GlobPattern::Segmented(segments) => Cow::Borrowed(segments.as_slice()),
+                _ => Cow::Owned(as_segments(alternative)),
+            };
+            segments::match_segments_folded_ranges(&segments, value.as_bytes(), case, spans)
+                && { segments::literal_spans(&segments, spans); true }
+        });
+        // a char folding to several can be split between segments, which only matching the folded value finds
+        #[cfg(feature = "full-case-folding")]
+        if matched.is_none() {
+            return self.ranges(pattern, value, case, matched_spans);
+        }
+        matched
+    }
+
+    /// Runs `find` (e.g. `matched_spans`) on `value` folded as `case` says, leaving the ranges it found
+    /// in `self.spans` as byte ranges in `value` itself rather than in its case folded form.
+    /// Returns the pattern that matched, which is one of the alternatives when `pattern` has any.
+    pub(crate) fn ranges<'p>(&mut self, pattern: &'p GlobPattern, value: &str, case: CaseMode,
+                             find: fn(&'p GlobPattern, &str, &mut Vec<Range<usize>>) -> Option<&'p GlobPattern>) -> Option<&'p GlobPattern> {
+        match case {
+            CaseMode::Sensitive => return find(pattern, value, &mut self.spans),
+            CaseMode::AsciiInsensitive => {
+                // folding ASCII letters keeps every char where it is
+                self.folded.clear();
+                self.folded.push_str(value);
+                self.folded.make_ascii_lowercase();
+                return find(pattern, &self.folded, &mut self.spans);
+            },
+            CaseMode::Insensitive => self.fold(value),
+            CaseMode::TurkicInsensitive => {
+                self.folded.clear();
+                self.folded.extend(value.chars().flat_map(|ch| case.fold_char(ch)));
+                &self.folded
+            },
+        };
+        let matched = find(pattern, &self.folded, &mut self.spans)?;
+        let folded = &self.folded;
+        if folded.len() == value.len() && folded.char_indices().map(|(i, _)| i).eq(value.char_indices().map(|(i, _)| i)) {
+            return Some(matched); // folding didn't move any chars around
+        }
+
+        // map byte positions in the case folded value back to the original chars,
+        // folding can turn one char into several (e.g. 'ß' -> "ss" with full case folding)
+        self.original.clear();
+        for (i, ch) in value.char_indices() {
+            let folded_len: usize = case.fold_char(ch).map(char::len_utf8).sum();
+            self.original.extend(std::iter::repeat_n((i, i + ch.len_utf8()), folded_len));
+        }
+        let original = &self.original;
+        for span in &mut self.spans {
+            // empty spans only mark a position, e.g. between the wildcards of "??"
+            let start = original.get(span.start).map_or(value.len(), |o| o.0);
+            *span = if span.start == span.end { start..start } else { start..original[span.end - 1].1 };
+        }
+        Some(matched)
+    }
+}
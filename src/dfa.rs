@@ -0,0 +1,432 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Compiling patterns to a table driven DFA, which matches in one pass over the value without backtracking.
+
+use std::collections::HashMap;
+
+use crate::case::case_variants;
+use crate::{CaseMode, GlobError, GlobPattern, Multipart, Segment};
+
+/// More states than this is a [`GlobError::DfaTooLarge`], the table would take too much memory.
+const MAX_STATES: usize = 4096;
+/// The state no match can continue from.
+const DEAD: u32 = 0;
+
+/// A pattern compiled by [`GlobPattern::compile_dfa`], matching in time linear in the length of the value.
+///
+/// Chars are grouped into the ranges the pattern tells apart, each state has one transition per group.
+#[derive(Debug,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dfa {
+    /// where each group of chars after the first starts, ascending
+    boundaries: Vec<u32>,
+    /// group of each ASCII char, looked up without searching `boundaries`
+    ascii: Vec<u16>,
+    groups: usize,
+    /// `groups` transitions per state
+    table: Vec<u32>,
+    accepting: Vec<bool>,
+    start: u32,
+}
+
+impl Dfa {
+    /// Number of states, including the one no match can continue from.
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// Whether the whole of `value` matches.
+    pub fn is_match(&self, value: &str) -> bool {
+        self.run(value.chars())
+    }
+
+    /// Matches `value` case folded the way the pattern was, without allocating.
+    pub(crate) fn is_match_folded(&self, value: &str, case: CaseMode) -> bool {
+        match case {
+            CaseMode::Sensitive => self.run(value.chars()),
+            CaseMode::Insensitive | CaseMode::TurkicInsensitive => self.run(value.chars().flat_map(|ch| case.fold_char(ch))),
+            CaseMode::AsciiInsensitive => self.run(value.chars().map(|ch| ch.to_ascii_lowercase())),
+        }
+    }
+
+    fn run(&self, chars: impl Iterator<Item = char>) -> bool {
+        let mut state = self.start;
+        for ch in chars {
+            state = self.step(state, ch);
+            if state == DEAD {
+                return false;
+            }
+        }
+        self.accepting[state as usize]
+    }
+
+    pub(crate) fn start_state(&self) -> u32 {
+        self.start
+    }
+
+    /// The state after `ch` in `state`, [`Dfa::is_dead`] once nothing after it can match.
+    pub(crate) fn step(&self, state: u32, ch: char) -> u32 {
+        self.table[state as usize * self.groups + self.group(ch)]
+    }
+
+    pub(crate) fn is_dead(state: u32) -> bool {
+        state == DEAD
+    }
+
+    pub(crate) fn is_accepting(&self, state: u32) -> bool {
+        self.accepting[state as usize]
+    }
+
+    /// Where each group of chars after the first starts, ascending.
+    pub(crate) fn boundaries(&self) -> &[u32] {
+        &self.boundaries
+    }
+
+    fn group(&self, ch: char) -> usize {
+        match self.ascii.get(ch as usize) {
+            Some(&group) => group as usize,
+            None => self.boundaries.partition_point(|&b| b <= ch as u32),
+        }
+    }
+}
+
+impl GlobPattern {
+    /// Compiles the pattern to a [`Dfa`], which matches without backtracking no matter the pattern,
+    /// see [`GlobBuilder::dfa`](crate::GlobBuilder::dfa).
+    ///
+    /// Fails with [`GlobError::DfaTooLarge`] for patterns needing too many states, which takes a lot
+    /// of wildcards between literals that can overlap.
+    pub fn compile_dfa(&self) -> Result<Dfa, GlobError> {
+        let mut nfa = Nfa::default();
+        let start = nfa.state();
+        nfa.add_pattern(start, self);
+        nfa.to_dfa(start)
+    }
+}
+
+/// Inclusive ranges of chars as `u32`, sorted and not overlapping.
+type CharSet = Vec<(u32, u32)>;
+
+const ALL: (u32, u32) = (0, char::MAX as u32);
+const SEPARATOR: u32 = '/' as u32;
+
+fn single(ch: char) -> CharSet {
+    vec![(ch as u32, ch as u32)]
+}
+
+fn except_separator() -> CharSet {
+    vec![(0, SEPARATOR - 1), (SEPARATOR + 1, char::MAX as u32)]
+}
+
+fn except_dot_and_separator() -> CharSet {
+    complement(&[('.' as u32, '.' as u32), (SEPARATOR, SEPARATOR)])
+}
+
+fn complement(set: &[(u32, u32)]) -> CharSet {
+    let mut complement = Vec::new();
+    let mut next = 0;
+    for &(first, last) in set {
+        if first > next {
+            complement.push((next, first - 1));
+        }
+        next = next.max(last + 1);
+    }
+    if next <= char::MAX as u32 {
+        complement.push((next, char::MAX as u32));
+    }
+    complement
+}
+
+fn normalize(mut set: CharSet) -> CharSet {
+    set.sort_unstable();
+    let mut normalized: CharSet = Vec::with_capacity(set.len());
+    for (first, last) in set {
+        match normalized.last_mut() {
+            Some(previous) if first <= previous.1 + 1 => previous.1 = previous.1.max(last),
+            _ => normalized.push((first, last)),
+        }
+    }
+    normalized
+}
+
+/// A nondeterministic automaton with a state per position in the pattern, turned into the DFA.
+#[derive(Default)]
+struct Nfa {
+    sets: Vec<CharSet>,
+    transitions: Vec<Vec<(usize, usize)>>,
+    /// transitions without consuming a char
+    empty: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+}
+
+impl Nfa {
+    fn state(&mut self) -> usize {
+        self.transitions.push(Vec::new());
+        self.empty.push(Vec::new());
+        self.accepting.push(false);
+        self.transitions.len() - 1
+    }
+
+    fn transition(&mut self, from: usize, set: CharSet, to: usize) {
+        self.sets.push(set);
+        self.transitions[from].push((self.sets.len() - 1, to));
+    }
+
+    /// Adds `pattern` starting at `start`, its last state accepting.
+    fn add_pattern(&mut self, start: usize, pattern: &GlobPattern) {
+        if let GlobPattern::Alternatives(alternatives) = pattern {
+            for alternative in alternatives {
+                self.add_pattern(start, alternative);
+            }
+            return;
+        }
+        let mut end = start;
+        let literal = |nfa: &mut Nfa, end: &mut usize, s: &str| {
+            for ch in s.chars() {
+                *end = nfa.one(*end, single(ch));
+            }
+        };
+        match pattern {
+            GlobPattern::MatchAny => end = self.any_number(end, vec![ALL]),
+            GlobPattern::MatchFull(full) => literal(self, &mut end, full),
+            GlobPattern::MatchStart(start) => {
+                literal(self, &mut end, start);
+                end = self.any_number(end, vec![ALL]);
+            },
+            GlobPattern::MatchEnd(s) => {
+                end = self.any_number(end, vec![ALL]);
+                literal(self, &mut end, s);
+            },
+            GlobPattern::MatchBothEnds(start, s) => {
+                literal(self, &mut end, start);
+                end = self.any_number(end, vec![ALL]);
+                literal(self, &mut end, s);
+            },
+            GlobPattern::Multipart(multi) => {
+                for part in multi {
+                    match part {
+                        Multipart::ExactStart(s) => literal(self, &mut end, s),
+                        Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => {
+                            end = self.any_number(end, vec![ALL]);
+                            literal(self, &mut end, s);
+                        },
+                        Multipart::AnyEnd => end = self.any_number(end, vec![ALL]),
+                    }
+                }
+            },
+            GlobPattern::Segmented(segments) => {
+                for segment in segments {
+                    end = match segment {
+                        Segment::Literal(s) => {
+                            literal(self, &mut end, s);
+                            end
+                        },
+                        Segment::AnyChar => self.one(end, vec![ALL]),
+                        Segment::Class(class) => {
+                            let set = normalize(class.ranges().iter().map(|&(first, last)| (first as u32, last as u32)).collect());
+                            self.one(end, if class.is_negated() { complement(&set) } else { set })
+                        },
+                        Segment::AnyCharExceptSeparator => self.one(end, except_separator()),
+                        Segment::AnyChars | Segment::AnyPath => self.any_number(end, vec![ALL]),
+                        Segment::AnyCharsExceptSeparator => self.any_number(end, except_separator()),
+                        Segment::AnyDirs => self.any_dirs(end),
+                        Segment::AnyVisibleChars => self.any_visible(end),
+                        Segment::AnyVisibleDirs => self.any_visible_dirs(end),
+                        Segment::LiteralIgnoreCase(s) => {
+                            for ch in s.chars() {
+                                let set = normalize(case_variants(ch).into_iter().map(|ch| (ch as u32, ch as u32)).collect());
+                                end = self.one(end, set);
+                            }
+                            end
+                        },
+                    };
+                }
+            },
+            GlobPattern::Alternatives(_) => unreachable!("alternatives are added one by one"),
+        }
+        self.accepting[end] = true;
+    }
+
+    /// One char in `set`.
+    fn one(&mut self, from: usize, set: CharSet) -> usize {
+        let to = self.state();
+        self.transition(from, set, to);
+        to
+    }
+
+    /// Any number of chars in `set`, in a state of its own so nothing else loops back into it.
+    fn any_number(&mut self, from: usize, set: CharSet) -> usize {
+        let to = self.state();
+        self.empty[from].push(to);
+        self.transition(to, set, to);
+        to
+    }
+
+    /// Any number of path components each ending with `/`.
+    fn any_dirs(&mut self, from: usize) -> usize {
+        let dirs = self.state();
+        let component = self.state();
+        self.empty[from].push(dirs);
+        self.transition(dirs, single('/'), dirs);
+        self.transition(dirs, except_separator(), component);
+        self.transition(component, except_separator(), component);
+        self.transition(component, single('/'), dirs);
+        dirs
+    }
+
+    /// Any number of chars where none after a `/` is a `.`.
+    fn any_visible(&mut self, from: usize) -> usize {
+        let chars = self.state();
+        let after_separator = self.state();
+        let to = self.state();
+        self.empty[from].push(chars);
+        self.transition(chars, except_separator(), chars);
+        self.transition(chars, single('/'), after_separator);
+        self.transition(after_separator, single('/'), after_separator);
+        self.transition(after_separator, except_dot_and_separator(), chars);
+        self.empty[chars].push(to);
+        self.empty[after_separator].push(to);
+        to
+    }
+
+    /// Any number of path components each ending with `/`, none starting with a `.`.
+    fn any_visible_dirs(&mut self, from: usize) -> usize {
+        let dirs = self.state();
+        let component = self.state();
+        self.empty[from].push(dirs);
+        self.transition(dirs, single('/'), dirs);
+        self.transition(dirs, except_dot_and_separator(), component);
+        self.transition(component, except_separator(), component);
+        self.transition(component, single('/'), dirs);
+        dirs
+    }
+
+    /// The states reachable from `states` without consuming a char, sorted.
+    fn closure(&self, mut states: Vec<usize>) -> Vec<usize> {
+        let mut i = 0;
+        while i < states.len() {
+            for &next in &self.empty[states[i]] {
+                if !states.contains(&next) {
+                    states.push(next);
+                }
+            }
+            i += 1;
+        }
+        // two transitions can lead to the same state
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+
+    /// Subset construction, each DFA state is the set of NFA states a prefix of the value can be in.
+    fn to_dfa(&self, start: usize) -> Result<Dfa, GlobError> {
+        // groups of chars every set either contains completely or not at all
+        let mut boundaries: Vec<u32> = self.sets
+            .iter()
+            .flatten()
+            .flat_map(|&(first, last)| vec![first, last + 1])
+            .filter(|&b| b > 0 && b <= char::MAX as u32)
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        let groups = boundaries.len() + 1;
+        let group_start = |group: usize| if group == 0 { 0 } else { boundaries[group - 1] };
+        // for each group, whether each set contains it
+        let in_sets: Vec<Vec<bool>> = (0..groups)
+            .map(|g| {
+                let ch = group_start(g);
+                self.sets.iter().map(|set| set.iter().any(|&(first, last)| first <= ch && ch <= last)).collect()
+            })
+            .collect();
+
+        let mut ids: HashMap<Vec<usize>, u32> = HashMap::new();
+        let mut subsets = vec![Vec::new()];
+        ids.insert(Vec::new(), DEAD);
+        let start_subset = self.closure(vec![start]);
+        ids.insert(start_subset.clone(), 1);
+        subsets.push(start_subset);
+
+        let mut table = Vec::new();
+        let mut accepting = Vec::new();
+        let mut next_subset = 0;
+        while next_subset < subsets.len() {
+            let subset = subsets[next_subset].clone();
+            accepting.push(subset.iter().any(|&s| self.accepting[s]));
+            for in_set in &in_sets {
+                let targets: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|&s| &self.transitions[s])
+                    .filter(|&&(set, _)| in_set[set])
+                    .map(|&(_, to)| to)
+                    .collect();
+                let target = self.closure(targets);
+                let id = match ids.get(&target) {
+                    Some(&id) => id,
+                    None => {
+                        if subsets.len() >= MAX_STATES {
+                            return Err(GlobError::DfaTooLarge);
+                        }
+                        let id = subsets.len() as u32;
+                        ids.insert(target.clone(), id);
+                        subsets.push(target);
+                        id
+                    },
+                };
+                table.push(id);
+            }
+            next_subset += 1;
+        }
+
+        let ascii = (0..128u32).map(|ch| boundaries.partition_point(|&b| b <= ch) as u16).collect();
+        Ok(Dfa { boundaries, ascii, groups, table, accepting, start: 1 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, glob_match_prebuilt, Glob, GlobError};
+
+    #[test]
+    fn dfa_matches_like_pattern() {
+        let patterns = ["*", "abc", "a*", "*.rs", "a*b*c", "a*y*c", "*a*b*", "log-??.txt", "[!a-c]*", "[a-c]x", "{*.md,*.rs}",
+                        "src/**/*.rs", "a/**", "**/x", "*ab*ab*", "é*ö"];
+        let values = ["", "abc", "a", "lib.rs", "axbyc", "abab", "log-01.txt", "dx", "bx", "README.md",
+                      "src/lib.rs", "src/a/b/lib.rs", "a/b/c", "x", "q/x", "ababab", "éö", "é-ö", "src/a"];
+        for pattern in patterns.iter() {
+            let built = build_glob_pattern(pattern).unwrap();
+            let dfa = built.compile_dfa().unwrap();
+            for value in values.iter() {
+                assert_eq!(dfa.is_match(value), glob_match_prebuilt(&built, value), "{} on {}", pattern, value);
+            }
+        }
+    }
+
+    #[test]
+    fn dfa_builder_option() {
+        let glob = Glob::builder("src/*/*.RS").case_insensitive(true).literal_separator(true).dfa(true).build().unwrap();
+        assert!(glob.is_match("SRC/a/lib.rs"));
+        assert!(!glob.is_match("src/a/b/lib.rs"));
+        let glob = Glob::builder("*a*a*a*a*a*a*a*a*a*a*b").dfa(true).build().unwrap();
+        assert!(!glob.is_match(&"a".repeat(10_000)));
+        assert!(glob.is_match(&format!("{}b", "a".repeat(100))));
+        // has to remember which of the last 13 chars were `a`
+        let pattern = format!("*a{}", "?".repeat(12));
+        assert_eq!(build_glob_pattern(&pattern).unwrap().compile_dfa().unwrap_err(), GlobError::DfaTooLarge);
+    }
+}
@@ -0,0 +1,137 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Errors laid out for people, see [`GlobError::diagnostic`].
+
+use std::fmt;
+
+use crate::GlobError;
+
+/// A [`GlobError`] together with the pattern it's about, a short message and a fix when there's an obvious one.
+///
+/// `Display` shows the message, the pattern indented on the next line with a `^` under the position below it
+/// and then the suggestion as `did you mean "a*b"?`, for command line tools.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub struct Diagnostic {
+    pub pattern: String,
+    pub error: GlobError,
+    /// byte position in the pattern, `None` for errors about the whole pattern
+    pub position: Option<usize>,
+    pub message: String,
+    /// the pattern with the error fixed
+    pub suggestion: Option<String>,
+}
+
+impl GlobError {
+    /// The error laid out for showing to whoever wrote `pattern`, which has to be the pattern the error is from.
+    pub fn diagnostic(&self, pattern: &str) -> Diagnostic {
+        let message = match self {
+            GlobError::EmptyPattern => "empty pattern".to_string(),
+            GlobError::EmptyWildcardSegment { .. } => "two wildcards next to each other".to_string(),
+            GlobError::UnterminatedClass { .. } => "unterminated character class".to_string(),
+            GlobError::ReversedRange { .. } => "character class range ends before it starts".to_string(),
+            GlobError::UnterminatedBraces { .. } => "unterminated brace group".to_string(),
+            GlobError::UnterminatedGroup { .. } => "unterminated case insensitive group".to_string(),
+            GlobError::UnsupportedRegex { .. } => "regex syntax a glob can't express".to_string(),
+            GlobError::DfaTooLarge | GlobError::PatternTooLong { .. } | GlobError::TooManySegments { .. } => self.to_string(),
+        };
+        Diagnostic {
+            pattern: pattern.to_string(),
+            error: *self,
+            position: self.position().filter(|&p| p <= pattern.len()),
+            message,
+            suggestion: suggestion(self, pattern),
+        }
+    }
+}
+
+/// The pattern fixed in the most likely way, only for errors where that's clear.
+fn suggestion(error: &GlobError, pattern: &str) -> Option<String> {
+    match *error {
+        GlobError::EmptyPattern => Some("*".to_string()),
+        GlobError::EmptyWildcardSegment { position } if pattern.get(position..)?.starts_with('*') => {
+            // one `*` in place of the whole run
+            let start = pattern[..position].trim_end_matches('*').len();
+            let end = pattern.len() - pattern[position..].trim_start_matches('*').len();
+            Some(format!("{}*{}", &pattern[..start], &pattern[end..]))
+        },
+        // the `[` as a class of its own matches it literally
+        GlobError::UnterminatedClass { position } if pattern.get(position..)?.starts_with('[') =>
+            Some(format!("{}[[]{}", &pattern[..position], &pattern[position + 1..])),
+        GlobError::ReversedRange { position } => {
+            let mut chars = pattern.get(position..)?.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(first), Some('-'), Some(last)) => {
+                    let rest = chars.as_str();
+                    Some(format!("{}{}-{}{}", &pattern[..position], last, first, rest))
+                },
+                _ => None,
+            }
+        },
+        GlobError::UnterminatedBraces { .. } => Some(format!("{}}}", pattern)),
+        GlobError::UnterminatedGroup { .. } => Some(format!("{})", pattern)),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.message)?;
+        write!(f, "  {}", self.pattern)?;
+        if let Some(position) = self.position {
+            write!(f, "\n  {}^", " ".repeat(self.pattern[..position].chars().count()))?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\ndid you mean \"{}\"?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, GlobError};
+
+    fn diagnose(pattern: &str) -> crate::Diagnostic {
+        Glob::new(pattern).unwrap_err().diagnostic(pattern)
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(diagnose("a**b").suggestion.as_deref(), Some("a*b"));
+        assert_eq!(diagnose("a***").suggestion.as_deref(), Some("a*"));
+        assert_eq!(diagnose("log[1.txt").suggestion.as_deref(), Some("log[[]1.txt"));
+        assert_eq!(diagnose("[z-a]").suggestion.as_deref(), Some("[a-z]"));
+        assert_eq!(diagnose("{a,b").suggestion.as_deref(), Some("{a,b}"));
+        assert_eq!(diagnose("(?i:a").suggestion.as_deref(), Some("(?i:a)"));
+        assert_eq!(GlobError::DfaTooLarge.diagnostic("x").suggestion, None);
+        for pattern in ["a**b", "log[1.txt", "[z-a]", "{a,b", "(?i:a"] {
+            assert!(Glob::new(diagnose(pattern).suggestion.as_deref().unwrap()).is_ok(), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn caret_under_the_position() {
+        let diagnostic = diagnose("a**b");
+        assert_eq!(diagnostic.position, Some(2));
+        assert_eq!(diagnostic.to_string(), "two wildcards next to each other\n  a**b\n    ^\ndid you mean \"a*b\"?");
+        // counted in chars, not bytes
+        assert_eq!(diagnose("é[b").to_string(), "unterminated character class\n  é[b\n   ^\ndid you mean \"é[[]b\"?");
+        assert_eq!(GlobError::EmptyPattern.diagnostic("").to_string(), "empty pattern\n  \ndid you mean \"*\"?");
+    }
+}
@@ -0,0 +1,180 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Writing compiled patterns back as pattern text.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::{build_glob_pattern, CharClass, GlobError, GlobPattern, Multipart, Segment};
+
+/// Writes an equivalent pattern, not necessarily the one it was built from: `{a,b}` is written
+/// as the alternatives it was expanded into and a pattern built by [`GlobIgnoreCase`](crate::GlobIgnoreCase) is written case folded.
+/// `*` and `?` built with a literal separator are written as they are, the option isn't part of the text.
+impl fmt::Display for GlobPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pattern(f, self, false)
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_segment(f, self, false)
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ranges = self.ranges().to_vec();
+        // a `]` is only part of the class first and a `-` only last, `!` or `^` first would negate it
+        ranges.sort_by_key(|&(first, last)| match (first, last) {
+            (']', _) => 0,
+            ('-', '-') => 3,
+            ('!', _) | ('^', _) => 2,
+            _ => 1,
+        });
+        f.write_char('[')?;
+        if self.is_negated() {
+            f.write_char('!')?;
+        }
+        for (first, last) in ranges {
+            f.write_char(first)?;
+            if first != last {
+                write!(f, "-{}", last)?;
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+fn write_pattern(f: &mut fmt::Formatter<'_>, pattern: &GlobPattern, in_braces: bool) -> fmt::Result {
+    let literal = |f: &mut fmt::Formatter<'_>, s: &str| write_literal(f, s, in_braces);
+    match pattern {
+        GlobPattern::MatchAny => f.write_char('*'),
+        GlobPattern::MatchEnd(end) => {
+            f.write_char('*')?;
+            literal(f, end)
+        },
+        GlobPattern::MatchStart(start) => {
+            literal(f, start)?;
+            f.write_char('*')
+        },
+        GlobPattern::MatchBothEnds(start, end) => {
+            literal(f, start)?;
+            f.write_char('*')?;
+            literal(f, end)
+        },
+        GlobPattern::MatchFull(full) => literal(f, full),
+        GlobPattern::Multipart(multi) => multi.iter().try_for_each(|part| match part {
+            Multipart::ExactStart(start) => literal(f, start),
+            Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => {
+                f.write_char('*')?;
+                literal(f, s)
+            },
+            Multipart::AnyEnd => f.write_char('*'),
+        }),
+        GlobPattern::Segmented(segments) => segments.iter().try_for_each(|segment| write_segment(f, segment, in_braces)),
+        GlobPattern::Alternatives(alternatives) => {
+            f.write_char('{')?;
+            for (i, alternative) in alternatives.iter().enumerate() {
+                if i > 0 {
+                    f.write_char(',')?;
+                }
+                write_pattern(f, alternative, true)?;
+            }
+            f.write_char('}')
+        },
+    }
+}
+
+fn write_segment(f: &mut fmt::Formatter<'_>, segment: &Segment, in_braces: bool) -> fmt::Result {
+    match segment {
+        Segment::Literal(s) => write_literal(f, s, in_braces),
+        Segment::AnyChar | Segment::AnyCharExceptSeparator => f.write_char('?'),
+        // the leading dot rule isn't part of the syntax, it's an option of the builder
+        Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyVisibleChars => f.write_char('*'),
+        Segment::Class(class) => write!(f, "{}", class),
+        Segment::AnyDirs | Segment::AnyVisibleDirs => f.write_str("**/"),
+        Segment::AnyPath => f.write_str("**"),
+        Segment::LiteralIgnoreCase(s) => {
+            f.write_str("(?i:")?;
+            // a `)` would end the group, as a class of one it's still part of it
+            s.split(')').enumerate().try_for_each(|(i, part)| {
+                if i > 0 {
+                    f.write_str("[)]")?;
+                }
+                write_literal(f, part, in_braces)
+            })?;
+            f.write_char(')')
+        },
+    }
+}
+
+/// Writes chars that would mean something else in a pattern as a class of one.
+fn write_literal(f: &mut fmt::Formatter<'_>, literal: &str, in_braces: bool) -> fmt::Result {
+    for ch in literal.chars() {
+        match ch {
+            '*' | '?' | '[' | '{' | '}' | '(' => write!(f, "[{}]", ch)?,
+            ',' if in_braces => f.write_str("[,]")?,
+            _ => f.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for GlobPattern {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<GlobPattern, GlobError> {
+        build_glob_pattern(pattern)
+    }
+}
+
+impl TryFrom<&str> for GlobPattern {
+    type Error = GlobError;
+
+    fn try_from(pattern: &str) -> Result<GlobPattern, GlobError> {
+        build_glob_pattern(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::{glob_match_prebuilt, GlobPattern};
+
+    #[test]
+    fn display_builds_an_equivalent_pattern() {
+        let patterns = ["*", "*.rs", "src*", "a*b", "abc", "*a*b*", "a*b*c", "log-??.txt", "[!a-c]x", "[]a-]",
+                        "src/**/*.rs", "a/**", "*.{jpg,png}", "{a[,]b,c}d", "a}b"];
+        let values = ["", "abc", "a,bd", "cd", "a}b", "lib.rs", "src/a/lib.rs", "log-01.txt", "dx", "]", "-", "a/b/c", "x.png", "a*b*c"];
+        for pattern in patterns.iter() {
+            let built: GlobPattern = pattern.parse().unwrap();
+            let written = built.to_string();
+            let rebuilt = GlobPattern::try_from(written.as_str()).unwrap();
+            for value in values.iter() {
+                assert_eq!(glob_match_prebuilt(&rebuilt, value), glob_match_prebuilt(&built, value),
+                           "{} written as {} on {}", pattern, written, value);
+            }
+        }
+        assert_eq!("*.{jpg,png}".parse::<GlobPattern>().unwrap().to_string(), "{*.jpg,*.png}");
+        assert_eq!("[!a-c]x".parse::<GlobPattern>().unwrap().to_string(), "[!a-c]x");
+    }
+}
@@ -0,0 +1,139 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! DOS and Windows wildcard rules, the way `FindFirstFile` matches file names, see [`GlobBuilder::dos`](crate::GlobBuilder::dos).
+//!
+//! The pattern is expanded into the patterns it stands for with the usual rules, `a??.*` into `a`, `a?`,
+//! `a??`, `a.*`, `a?.*` and `a??.*`, which are then matched as alternatives.
+
+use crate::{build_glob_pattern_with, GlobError, GlobPattern, PatternOptions};
+
+/// Builds `pattern` with DOS rules, `options` without [`PatternOptions::dos`] for each expansion.
+pub(crate) fn build(pattern: &str, options: &PatternOptions) -> Result<GlobPattern, GlobError> {
+    let options = PatternOptions { dos: false, ..options.clone() };
+    let mut alternatives = Vec::new();
+    for expanded in expand(pattern) {
+        match build_glob_pattern_with(&expanded, &options) {
+            Ok(GlobPattern::Alternatives(nested)) => alternatives.extend(nested),
+            Ok(built) => alternatives.push(built),
+            // positions in the expansions don't match the ones in `pattern`
+            Err(e) => return Err(build_glob_pattern_with(pattern, &options).err().unwrap_or(e)),
+        }
+    }
+    let built = if alternatives.len() == 1 { alternatives.remove(0) } else { GlobPattern::Alternatives(alternatives) };
+    match options.max_segments {
+        Some(max) if built.segment_count() > max => Err(GlobError::TooManySegments { max }),
+        _ => Ok(built),
+    }
+}
+
+/// The patterns `pattern` stands for: `*.*` is any name, a name ending in `.*` doesn't need
+/// the extension (`a.*` matches `a`), and `?` at the end of a name or before a `.` can match nothing.
+fn expand(pattern: &str) -> Vec<String> {
+    if pattern == "*.*" || pattern.ends_with("/*.*") {
+        return vec![pattern[..pattern.len() - 2].to_string()];
+    }
+    let mut expanded = vec![String::new()];
+    let mut chars = pattern.char_indices().peekable();
+    while let Some((position, ch)) = chars.next() {
+        match ch {
+            '[' => {
+                // classes are copied as they are, a `?` in one is literal
+                let end = class_end(&pattern[position..]).map_or(pattern.len(), |end| position + end);
+                for e in &mut expanded {
+                    e.push_str(&pattern[position..end]);
+                }
+                while chars.next_if(|&(i, _)| i < end).is_some() {}
+            },
+            '?' => {
+                let mut run = 1;
+                while chars.next_if(|&(_, ch)| ch == '?').is_some() {
+                    run += 1;
+                }
+                let optional = matches!(chars.peek(), None | Some((_, '.')) | Some((_, '/')));
+                let fewest = if optional { 0 } else { run };
+                expanded = expanded
+                    .iter()
+                    .flat_map(|e| (fewest..=run).map(move |n| format!("{}{}", e, "?".repeat(n))))
+                    .collect();
+            },
+            _ => expanded.iter_mut().for_each(|e| e.push(ch)),
+        }
+    }
+    if pattern.ends_with(".*") {
+        // the extension can be left out, along with its dot
+        let without: Vec<String> = expanded
+            .iter()
+            .map(|e| e[..e.len() - 2].to_string())
+            .filter(|e| !e.is_empty() && !e.ends_with('/'))
+            .collect();
+        expanded.extend(without);
+    }
+    // a name can't be empty, `?` on its own still needs a char
+    expanded.retain(|e| !e.is_empty());
+    expanded
+}
+
+/// The byte after the `]` ending the class `pattern` starts with, the first char of a class is
+/// part of it even when it's a `]`.
+fn class_end(pattern: &str) -> Option<usize> {
+    let mut chars = pattern.char_indices().skip(1).peekable();
+    chars.next_if(|&(_, ch)| ch == '!' || ch == '^');
+    chars.next()?;
+    chars.find(|&(_, ch)| ch == ']').map(|(i, _)| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use crate::{Glob, GlobError};
+
+    fn dos(pattern: &str) -> Glob {
+        Glob::builder(pattern).dos(true).build().unwrap()
+    }
+
+    #[test]
+    fn expansions() {
+        assert_eq!(expand("*.*"), vec!["*"]);
+        assert_eq!(expand("a??"), vec!["a", "a?", "a??"]);
+        assert_eq!(expand("a?b"), vec!["a?b"]);
+        assert_eq!(expand("a.*"), vec!["a.*", "a"]);
+        assert_eq!(expand("[?]?"), vec!["[?]", "[?]?"]);
+        assert_eq!(expand("?.?"), vec![".", ".?", "?.", "?.?"]);
+        assert_eq!(expand("?"), vec!["?"]);
+    }
+
+    #[test]
+    fn find_first_file_rules() {
+        let any = dos("*.*");
+        assert!(any.is_match("README") && any.is_match("lib.rs") && any.is_match(".git") && any.is_match(""));
+        let pattern = dos("REPORT??.TXT");
+        assert!(pattern.is_match("REPORT.TXT") && pattern.is_match("REPORT1.TXT") && pattern.is_match("REPORT12.TXT"));
+        assert!(!pattern.is_match("REPORT123.TXT") && !pattern.is_match("REPORT1.TXTX"));
+        let pattern = dos("setup.*");
+        assert!(pattern.is_match("setup") && pattern.is_match("setup.exe") && !pattern.is_match("setupx"));
+        let pattern = dos("a?b");
+        assert!(pattern.is_match("axb") && !pattern.is_match("ab"));
+        let pattern = Glob::builder("*.TXT").dos(true).case_insensitive(true).build().unwrap();
+        assert!(pattern.is_match("notes.txt") && !pattern.is_match("notes"));
+        assert!(dos("docs/*.*").is_match("docs/README"));
+        assert!(!Glob::new("*.*").unwrap().is_match("README"));
+        assert_eq!(Glob::builder("a[b?").dos(true).build().unwrap_err(), GlobError::UnterminatedClass { position: 1 });
+        assert_eq!(Glob::builder("a??").dos(true).max_segments(3).build().unwrap_err(), GlobError::TooManySegments { max: 3 });
+    }
+}
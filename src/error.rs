@@ -0,0 +1,102 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a pattern couldn't be built, positions are byte positions in the pattern.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum GlobError {
+    EmptyPattern,
+    /// Two `*` next to each other, at the position of the second one.
+    EmptyWildcardSegment { position: usize },
+    /// A `[` without a closing `]`, at the position of the `[`.
+    UnterminatedClass { position: usize },
+    /// A range in a class ending before it starts, like `[z-a]`, at the position of its first char.
+    ReversedRange { position: usize },
+    /// A `{` without a closing `}`, at the position of the `{`.
+    UnterminatedBraces { position: usize },
+    /// A `(?i:` group without a closing `)`, at the position of the `(`.
+    UnterminatedGroup { position: usize },
+    /// Regex syntax a glob can't express, see [`GlobPattern::from_simple_regex`](crate::GlobPattern::from_simple_regex),
+    /// at its position in the regex.
+    UnsupportedRegex { position: usize },
+    /// The pattern would need too many states to compile to a [`Dfa`](crate::Dfa).
+    DfaTooLarge,
+    /// The pattern is longer than [`GlobBuilder::max_pattern_len`](crate::GlobBuilder::max_pattern_len) bytes.
+    PatternTooLong { max: usize },
+    /// The pattern has more than [`GlobBuilder::max_segments`](crate::GlobBuilder::max_segments) literals
+    /// and wildcards, counting every alternative of its brace groups.
+    TooManySegments { max: usize },
+}
+
+impl GlobError {
+    pub fn position(&self) -> Option<usize> {
+        match *self {
+            GlobError::EmptyPattern | GlobError::DfaTooLarge |
+            GlobError::PatternTooLong { .. } | GlobError::TooManySegments { .. } => None,
+            GlobError::EmptyWildcardSegment { position } |
+            GlobError::UnterminatedClass { position } |
+            GlobError::ReversedRange { position } |
+            GlobError::UnterminatedBraces { position } |
+            GlobError::UnterminatedGroup { position } |
+            GlobError::UnsupportedRegex { position } => Some(position),
+        }
+    }
+
+    /// The same error at another position.
+    pub(crate) fn at(self, position: usize) -> GlobError {
+        match self {
+            GlobError::EmptyPattern => GlobError::EmptyPattern,
+            GlobError::DfaTooLarge => GlobError::DfaTooLarge,
+            GlobError::PatternTooLong { max } => GlobError::PatternTooLong { max },
+            GlobError::TooManySegments { max } => GlobError::TooManySegments { max },
+            GlobError::EmptyWildcardSegment { .. } => GlobError::EmptyWildcardSegment { position },
+            GlobError::UnterminatedClass { .. } => GlobError::UnterminatedClass { position },
+            GlobError::ReversedRange { .. } => GlobError::ReversedRange { position },
+            GlobError::UnterminatedBraces { .. } => GlobError::UnterminatedBraces { position },
+            GlobError::UnterminatedGroup { .. } => GlobError::UnterminatedGroup { position },
+            GlobError::UnsupportedRegex { .. } => GlobError::UnsupportedRegex { position },
+        }
+    }
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlobError::EmptyPattern => write!(f, "empty pattern"),
+            GlobError::DfaTooLarge => write!(f, "pattern needs too many states to compile to a DFA"),
+            GlobError::PatternTooLong { max } => write!(f, "pattern is longer than {} bytes", max),
+            GlobError::TooManySegments { max } => write!(f, "pattern has more than {} literals and wildcards", max),
+            GlobError::EmptyWildcardSegment { position } =>
+                write!(f, "two wildcards next to each other at position {}", position),
+            GlobError::UnterminatedClass { position } =>
+                write!(f, "unterminated character class starting at position {}", position),
+            GlobError::ReversedRange { position } =>
+                write!(f, "character class range ends before it starts at position {}", position),
+            GlobError::UnterminatedBraces { position } =>
+                write!(f, "unterminated brace group starting at position {}", position),
+            GlobError::UnterminatedGroup { position } =>
+                write!(f, "unterminated case insensitive group starting at position {}", position),
+            GlobError::UnsupportedRegex { position } =>
+                write!(f, "regex syntax a glob can't express at position {}", position),
+        }
+    }
+}
+
+impl Error for GlobError {}
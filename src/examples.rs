@@ -0,0 +1,159 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Values a pattern matches, to show what a pattern means or as test data.
+
+use std::collections::HashSet;
+
+use crate::{CharClass, GlobPattern, Multipart, Segment};
+
+/// What `*` is filled with, in turn.
+const FILLS: [&str; 4] = ["example", "", "a", "some-text"];
+/// What `?` is filled with, in turn.
+const SINGLES: [char; 4] = ['x', 'a', '0', '_'];
+
+impl GlobPattern {
+    /// A value the pattern matches, with the wildcards filled with sample text, `*.rs` gives `example.rs`.
+    pub fn example(&self) -> String {
+        self.nth_example(0)
+    }
+
+    /// Up to `n` different values the pattern matches, fewer if it doesn't match that many (like `Cargo.toml`).
+    /// The first one is [`GlobPattern::example`].
+    pub fn examples(&self, n: usize) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut examples = Vec::with_capacity(n);
+        // a pattern matching only a few values keeps giving the same ones, so give up eventually
+        for k in 0..n.saturating_mul(4) {
+            if examples.len() == n {
+                break;
+            }
+            let example = self.nth_example(k);
+            if seen.insert(example.clone()) {
+                examples.push(example);
+            }
+        }
+        examples
+    }
+
+    fn nth_example(&self, k: usize) -> String {
+        let mut example = String::new();
+        match self {
+            GlobPattern::MatchAny => example.push_str(&fill(k)),
+            GlobPattern::MatchFull(full) => example.push_str(full),
+            GlobPattern::MatchStart(start) => {
+                example.push_str(start);
+                example.push_str(&fill(k));
+            },
+            GlobPattern::MatchEnd(end) => {
+                example.push_str(&fill(k));
+                example.push_str(end);
+            },
+            GlobPattern::MatchBothEnds(start, end) => {
+                example.push_str(start);
+                example.push_str(&fill(k));
+                example.push_str(end);
+            },
+            GlobPattern::Multipart(multi) => {
+                for part in multi {
+                    match part {
+                        Multipart::ExactStart(start) => example.push_str(start),
+                        Multipart::AnyUntil(literal) | Multipart::AnyUntilExactEnd(literal) => {
+                            example.push_str(&fill(k));
+                            example.push_str(literal);
+                        },
+                        Multipart::AnyEnd => example.push_str(&fill(k)),
+                    }
+                }
+            },
+            GlobPattern::Segmented(segments) => {
+                for segment in segments {
+                    match segment {
+                        Segment::Literal(literal) | Segment::LiteralIgnoreCase(literal) => example.push_str(literal),
+                        Segment::AnyChar | Segment::AnyCharExceptSeparator => example.push(SINGLES[k % SINGLES.len()]),
+                        Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyPath | Segment::AnyVisibleChars =>
+                            example.push_str(&fill(k)),
+                        Segment::Class(class) => example.push(class_member(class, k)),
+                        Segment::AnyDirs | Segment::AnyVisibleDirs if k % 2 == 1 => example.push_str("dir/"),
+                        Segment::AnyDirs | Segment::AnyVisibleDirs => (),
+                    }
+                }
+            },
+            GlobPattern::Alternatives(alternatives) =>
+                return alternatives[k % alternatives.len()].nth_example(k / alternatives.len()),
+        }
+        example
+    }
+}
+
+/// What the `k`th example fills a `*` with, never containing `/`.
+fn fill(k: usize) -> String {
+    match FILLS.get(k) {
+        Some(fill) => fill.to_string(),
+        None => format!("{}{}", FILLS[0], k - FILLS.len() + 2),
+    }
+}
+
+/// One of the chars in `class`, a different one for each `k` where there are several.
+fn class_member(class: &CharClass, k: usize) -> char {
+    if class.is_negated() {
+        // printable ASCII first, anything else if the class excludes all of it
+        let members: Vec<char> = ('a'..='z').chain('0'..='9').chain('!'..='~').filter(|&ch| class.contains(ch)).take(8).collect();
+        return match members.get(k % members.len().max(1)) {
+            Some(&ch) => ch,
+            None => (0..=char::MAX as u32).filter_map(char::from_u32).find(|&ch| class.contains(ch))
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        };
+    }
+    let ranges = class.ranges();
+    let (first, last) = ranges[k % ranges.len()];
+    let offset = (k / ranges.len()) as u32 % (last as u32 - first as u32 + 1);
+    // a range spanning the surrogates can land on one
+    char::from_u32(first as u32 + offset).unwrap_or(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, glob_match_prebuilt, Glob};
+
+    #[test]
+    fn examples_match_their_pattern() {
+        let patterns = ["*.rs", "src/*", "a*b", "*.*.test.cs", "file?.txt", "[a-c][!a-z0-9]*.log", "*.{jpg,png}",
+                        "src/**/*.rs", "**/test_*", "target/**", "*", "Cargo.toml", "{a,b{c,d}}*"];
+        for pattern in patterns.iter() {
+            let gp = build_glob_pattern(pattern).unwrap();
+            let examples = gp.examples(10);
+            assert!(examples.len() > 1 || *pattern == "Cargo.toml", "{}", pattern);
+            for example in &examples {
+                assert!(glob_match_prebuilt(&gp, example), "{} {}", pattern, example);
+            }
+        }
+        let glob = Glob::builder("src/*/[!.]*.rs").literal_separator(true).build().unwrap();
+        for example in glob.pattern().examples(10) {
+            assert!(glob.is_match(&example), "{}", example);
+        }
+    }
+
+    #[test]
+    fn example_values() {
+        assert_eq!(build_glob_pattern("*.rs").unwrap().example(), "example.rs");
+        assert_eq!(build_glob_pattern("*.{jpg,png}").unwrap().examples(3), vec!["example.jpg", "example.png", ".jpg"]);
+        assert_eq!(build_glob_pattern("Cargo.toml").unwrap().examples(5), vec!["Cargo.toml"]);
+        assert_eq!(build_glob_pattern("file[0-2].txt").unwrap().examples(5), vec!["file0.txt", "file1.txt", "file2.txt"]);
+    }
+}
@@ -0,0 +1,163 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! C bindings, declared in `include/globber.h`.
+//!
+//! Patterns and lists are opaque handles, built and freed through these functions only.
+//! Strings are NUL terminated, values don't have to be valid UTF-8.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::{Glob, GlobList};
+
+/// A compiled pattern, see `globber_build`.
+pub struct GlobberGlob(Glob);
+
+/// A list of patterns, see `globber_list_new`.
+pub struct GlobberList(GlobList);
+
+/// Builds `pattern`, null if it isn't valid UTF-8 or not a valid pattern. Free it with `globber_free`.
+///
+/// # Safety
+/// `pattern` has to be a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn globber_build(pattern: *const c_char, ignore_case: bool) -> *mut GlobberGlob {
+    let glob = CStr::from_ptr(pattern)
+        .to_str()
+        .ok()
+        .and_then(|pattern| Glob::builder(pattern).case_insensitive(ignore_case).build().ok());
+    match glob {
+        Some(glob) => Box::into_raw(Box::new(GlobberGlob(glob))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Whether `value` matches, wildcards match single bytes where it isn't valid UTF-8.
+///
+/// # Safety
+/// `glob` has to come from `globber_build` and `value` has to be a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn globber_is_match(glob: *const GlobberGlob, value: *const c_char) -> bool {
+    (*glob).0.is_match_bytes(CStr::from_ptr(value).to_bytes())
+}
+
+/// Frees a pattern from `globber_build`, null is ignored.
+///
+/// # Safety
+/// `glob` has to come from `globber_build` and can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn globber_free(glob: *mut GlobberGlob) {
+    if !glob.is_null() {
+        drop(Box::from_raw(glob));
+    }
+}
+
+/// An empty list, free it with `globber_list_free`.
+#[no_mangle]
+pub extern "C" fn globber_list_new() -> *mut GlobberList {
+    Box::into_raw(Box::new(GlobberList(GlobList::new())))
+}
+
+/// Adds `pattern` to the list, negated if it starts with `!` like in a gitignore file.
+/// Returns false if it isn't valid UTF-8 or not a valid pattern.
+///
+/// # Safety
+/// `list` has to come from `globber_list_new` and `pattern` has to be a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn globber_list_add(list: *mut GlobberList, pattern: *const c_char, ignore_case: bool) -> bool {
+    let pattern = match CStr::from_ptr(pattern).to_str() {
+        Ok(pattern) => pattern,
+        Err(_) => return false,
+    };
+    let (pattern, negated) = match pattern.strip_prefix('!') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let glob = match Glob::builder(pattern).case_insensitive(ignore_case).build() {
+        Ok(glob) => glob,
+        Err(_) => return false,
+    };
+    let list = &mut (*list).0;
+    if negated {
+        list.add_negated(glob);
+    } else {
+        list.add(glob);
+    }
+    true
+}
+
+/// Whether the list accepts `value`, see `GlobList::any_match`. Invalid UTF-8 is replaced by U+FFFD.
+///
+/// # Safety
+/// `list` has to come from `globber_list_new` and `value` has to be a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn globber_list_any_match(list: *const GlobberList, value: *const c_char) -> bool {
+    (*list).0.any_match(&CStr::from_ptr(value).to_string_lossy())
+}
+
+/// Frees a list from `globber_list_new`, null is ignored.
+///
+/// # Safety
+/// `list` has to come from `globber_list_new` and can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn globber_list_free(list: *mut GlobberList) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    fn c(s: &[u8]) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn glob_through_ffi() {
+        unsafe {
+            let glob = globber_build(c(b"*.RS").as_ptr(), true);
+            assert!(!glob.is_null());
+            assert!(globber_is_match(glob, c(b"lib.rs").as_ptr()));
+            assert!(globber_is_match(glob, c(b"\xff.rs").as_ptr()));
+            assert!(!globber_is_match(glob, c(b"lib.c").as_ptr()));
+            globber_free(glob);
+            assert!(globber_build(c(b"a**").as_ptr(), false).is_null());
+            assert!(globber_build(c(b"\xff").as_ptr(), false).is_null());
+            globber_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn list_through_ffi() {
+        unsafe {
+            let list = globber_list_new();
+            assert!(globber_list_add(list, c(b"*.rs").as_ptr(), false));
+            assert!(globber_list_add(list, c(b"!target/*").as_ptr(), false));
+            assert!(!globber_list_add(list, c(b"[z-a]").as_ptr(), false));
+            assert!(globber_list_any_match(list, c(b"src/lib.rs").as_ptr()));
+            assert!(!globber_list_any_match(list, c(b"target/gen.rs").as_ptr()));
+            globber_list_free(list);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! POSIX `fnmatch(3)` pattern syntax, see [`Syntax::Fnmatch`].
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::segments::SEPARATOR;
+use crate::{CharClass, GlobError, PatternOptions, Segment};
+
+/// How a pattern is written, see [`GlobBuilder::syntax`](crate::GlobBuilder::syntax).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Default)]
+pub enum Syntax {
+    /// this crate's own: `{a,b}` alternatives, `**` directories and `(?i:...)` groups
+    #[default]
+    Glob,
+    /// POSIX `fnmatch(3)` with `FNM_PATHNAME` and `FNM_PERIOD`, the way shells match file names: `\` escapes
+    /// the next char, classes can have `[:alpha:]` like names in them, `**` is the same as `*`, and an
+    /// unterminated `[` is a literal
+    Fnmatch,
+    /// `.gitignore` rules: `**` directories like the crate's own syntax and `\` escapes the next char,
+    /// while `{`, `}` and `(` are literals
+    Gitignore,
+}
+
+/// Splits an `fnmatch(3)` pattern into segments, like [`build_segments`](crate::segments::build_segments)
+/// does for the crate's own syntax.
+pub(crate) fn build_segments(pattern: &str, options: &PatternOptions) -> Result<Vec<Segment>, GlobError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.char_indices().peekable();
+    let flush = |segments: &mut Vec<Segment>, literal: &mut String| {
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(std::mem::take(literal)));
+        }
+    };
+    while let Some((_, ch)) = chars.next() {
+        let wildcard = match ch {
+            '\\' => {
+                literal.push(chars.next().map_or('\\', |(_, escaped)| escaped));
+                continue;
+            },
+            '?' if options.literal_separator => Segment::AnyCharExceptSeparator,
+            '?' => Segment::AnyChar,
+            '*' => {
+                while chars.next_if(|&(_, ch)| ch == '*').is_some() {}
+                let (one, any) = if options.literal_separator {
+                    (Segment::AnyCharExceptSeparator, Segment::AnyCharsExceptSeparator)
+                } else {
+                    (Segment::AnyChar, Segment::AnyChars)
+                };
+                flush(&mut segments, &mut literal);
+                segments.extend(std::iter::repeat_n(one, options.wildcard_min_len));
+                any
+            },
+            '[' => {
+                let mut ahead = chars.clone();
+                match parse_bracket(&mut ahead, options)? {
+                    Some(class) => {
+                        chars = ahead;
+                        Segment::Class(class)
+                    },
+                    None => {
+                        literal.push('[');
+                        continue;
+                    },
+                }
+            },
+            _ => {
+                literal.push(ch);
+                continue;
+            },
+        };
+        flush(&mut segments, &mut literal);
+        segments.push(wildcard);
+    }
+    flush(&mut segments, &mut literal);
+    Ok(segments)
+}
+
+/// Parses a bracket expression after its `[`, `None` if it isn't terminated and the `[` is a literal.
+/// A `]` right at the start is part of it, `!` or `^` there negates it, `\` escapes a char and `[:name:]`
+/// adds a POSIX class (in the C locale, so ASCII only).
+fn parse_bracket(chars: &mut Peekable<CharIndices<'_>>, options: &PatternOptions) -> Result<Option<CharClass>, GlobError> {
+    let negated = chars.next_if(|&(_, ch)| ch == '!' || ch == '^').is_some();
+    let mut ranges = Vec::new();
+    let mut first_item = true;
+    loop {
+        let (position, mut first) = match chars.next() {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+        match first {
+            ']' if !first_item => break,
+            '[' if chars.peek().map(|&(_, ch)| ch) == Some(':') => {
+                let mut name_chars = chars.clone();
+                name_chars.next();
+                let name: String = name_chars.by_ref().map(|(_, ch)| ch).take_while(|&ch| ch != ':').collect();
+                if let (Some((_, ']')), Some(class)) = (name_chars.next(), posix_class(&name)) {
+                    ranges.extend_from_slice(class);
+                    *chars = name_chars;
+                    first_item = false;
+                    continue;
+                }
+            },
+            '\\' => first = chars.next().map_or('\\', |(_, escaped)| escaped),
+            _ => (),
+        }
+        first_item = false;
+        let mut ahead = chars.clone();
+        let last = match (ahead.next(), ahead.next()) {
+            (Some((_, '-')), Some((_, last))) if last != ']' => {
+                chars.next();
+                chars.next();
+                if last == '\\' {
+                    chars.next().map_or('\\', |(_, escaped)| escaped)
+                } else {
+                    last
+                }
+            },
+            _ => first,
+        };
+        if last < first {
+            return Err(GlobError::ReversedRange { position });
+        }
+        ranges.push((first, last));
+    }
+    if negated && options.literal_separator {
+        ranges.push((SEPARATOR, SEPARATOR));
+    }
+    Ok(Some(CharClass { negated, ranges }))
+}
+
+/// The ranges of a POSIX character class like `alpha`, in the C locale.
+fn posix_class(name: &str) -> Option<&'static [(char, char)]> {
+    Some(match name {
+        "alnum" => &[('0', '9'), ('A', 'Z'), ('a', 'z')],
+        "alpha" => &[('A', 'Z'), ('a', 'z')],
+        "blank" => &[(' ', ' '), ('\t', '\t')],
+        "cntrl" => &[('\0', '\x1f'), ('\x7f', '\x7f')],
+        "digit" => &[('0', '9')],
+        "graph" => &[('!', '~')],
+        "lower" => &[('a', 'z')],
+        "print" => &[(' ', '~')],
+        "punct" => &[('!', '/'), (':', '@'), ('[', '`'), ('{', '~')],
+        "space" => &[('\t', '\r'), (' ', ' ')],
+        "upper" => &[('A', 'Z')],
+        "xdigit" => &[('0', '9'), ('A', 'F'), ('a', 'f')],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, GlobError, Syntax};
+
+    fn fnmatch(pattern: &str) -> Glob {
+        Glob::builder(pattern).syntax(Syntax::Fnmatch).build().unwrap()
+    }
+
+    #[test]
+    fn pathname_and_period() {
+        let star = fnmatch("*");
+        assert!(star.is_match("file") && !star.is_match(".bashrc") && !star.is_match("a/b"));
+        let rs = fnmatch("*/*.rs");
+        assert!(rs.is_match("src/lib.rs") && !rs.is_match("src/.hidden.rs") && !rs.is_match(".git/a.rs"));
+        assert!(!fnmatch("*.txt").is_match(".txt"));
+        assert!(!fnmatch("?bashrc").is_match(".bashrc") && !fnmatch("[.]bashrc").is_match(".bashrc"));
+        assert!(fnmatch(".*").is_match(".bashrc") && fnmatch("a.*").is_match("a.b"));
+        // `**` is just `*`
+        assert!(!fnmatch("**.rs").is_match("src/lib.rs"));
+        let any_separator = Glob::builder("*.rs").syntax(Syntax::Fnmatch).literal_separator(false).build().unwrap();
+        assert!(any_separator.is_match("src/lib.rs") && !any_separator.is_match(".lib.rs"));
+    }
+
+    #[test]
+    fn escapes_and_braces() {
+        assert!(fnmatch("a\\*b").is_match("a*b") && !fnmatch("a\\*b").is_match("axb"));
+        assert!(fnmatch("\\[x]").is_match("[x]"));
+        assert!(fnmatch("{a,b}").is_match("{a,b}") && !fnmatch("{a,b}").is_match("a"));
+        assert!(fnmatch("(?i:a)").is_match("(?i:a)"));
+    }
+
+    #[test]
+    fn brackets() {
+        let class = fnmatch("[[:digit:][:upper:]]x");
+        assert!(class.is_match("7x") && class.is_match("Qx") && !class.is_match("qx"));
+        assert!(fnmatch("[]a]").is_match("]") && fnmatch("[!]a]").is_match("b") && !fnmatch("[!]a]").is_match("]"));
+        assert!(fnmatch("[^a]").is_match("b") && !fnmatch("a[!x]b").is_match("a/b"));
+        assert!(fnmatch("[a-c]").is_match("b") && fnmatch("[\\]]").is_match("]"));
+        // unterminated, so a literal
+        assert!(fnmatch("a[b").is_match("a[b"));
+        assert!(matches!(Glob::builder("[z-a]").syntax(Syntax::Fnmatch).build(), Err(GlobError::ReversedRange { position: 1 })));
+    }
+}
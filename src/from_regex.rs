@@ -0,0 +1,124 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Building patterns from simple regexes, for moving filters over from regexes to globs.
+
+use crate::{build_glob_pattern, GlobError, GlobPattern};
+
+/// Chars with a meaning in a glob, written as a class of one to match them literally.
+const GLOB_META: &str = "*?[]{}";
+
+impl GlobPattern {
+    /// Builds a pattern from a regex using only literals, `.`, `.*`, `.+`, `\`-escaped punctuation
+    /// and the anchors `^` and `$`, e.g. `.*foo.*` or `^log-..\.txt$` (`log-??.txt`).
+    ///
+    /// Like a regex it matches anywhere in a value unless it's anchored. Other regex syntax
+    /// is a [`GlobError::UnsupportedRegex`] at its position in `regex`.
+    pub fn from_simple_regex(regex: &str) -> Result<GlobPattern, GlobError> {
+        build_glob_pattern(&simple_regex_to_glob(regex)?)
+    }
+}
+
+fn simple_regex_to_glob(regex: &str) -> Result<String, GlobError> {
+    let (anchored_start, body) = match regex.strip_prefix('^') {
+        Some(body) => (true, body),
+        None => (false, regex),
+    };
+    let offset = regex.len() - body.len();
+    let mut glob = String::new();
+    let push_star = |glob: &mut String| {
+        // `**` means something else in a glob
+        if !glob.ends_with('*') {
+            glob.push('*');
+        }
+    };
+    if !anchored_start {
+        push_star(&mut glob);
+    }
+
+    let mut anchored_end = false;
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let position = offset + i;
+        match ch {
+            '.' => match chars.next_if(|&(_, next)| next == '*' || next == '+') {
+                Some((_, '*')) => push_star(&mut glob),
+                Some(_) => {
+                    glob.push('?');
+                    push_star(&mut glob);
+                },
+                None => glob.push('?'),
+            },
+            '\\' => match chars.next() {
+                Some((_, escaped)) if escaped.is_ascii_punctuation() => push_literal(&mut glob, escaped),
+                _ => return Err(GlobError::UnsupportedRegex { position }),
+            },
+            '$' if chars.peek().is_none() => anchored_end = true,
+            '^' | '$' | '*' | '+' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' =>
+                return Err(GlobError::UnsupportedRegex { position }),
+            _ => push_literal(&mut glob, ch),
+        }
+    }
+
+    if !anchored_end {
+        push_star(&mut glob);
+    }
+    Ok(glob)
+}
+
+fn push_literal(glob: &mut String, ch: char) {
+    if GLOB_META.contains(ch) {
+        glob.push('[');
+        glob.push(ch);
+        glob.push(']');
+    } else {
+        glob.push(ch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::simple_regex_to_glob;
+    use crate::{glob_match_prebuilt, GlobError, GlobPattern};
+
+    #[test]
+    fn simple_regex_to_glob_translates() {
+        assert_eq!(simple_regex_to_glob(".*foo.*").unwrap(), "*foo*");
+        assert_eq!(simple_regex_to_glob("^log-..\\.txt$").unwrap(), "log-??.txt");
+        assert_eq!(simple_regex_to_glob("^a.+b").unwrap(), "a?*b*");
+        assert_eq!(simple_regex_to_glob("\\*\\[x\\]$").unwrap(), "*[*][[]x[]]");
+        assert_eq!(simple_regex_to_glob("^$").unwrap(), "");
+    }
+
+    #[test]
+    fn from_simple_regex_matches_like_regex() {
+        let pattern = GlobPattern::from_simple_regex("foo").unwrap();
+        assert!(glob_match_prebuilt(&pattern, "a foo b"));
+        let pattern = GlobPattern::from_simple_regex("^src/.*\\.rs$").unwrap();
+        assert!(glob_match_prebuilt(&pattern, "src/lib.rs"));
+        assert!(!glob_match_prebuilt(&pattern, "src/lib.rs.bak"));
+        let pattern = GlobPattern::from_simple_regex("^a\\{b\\}\\?$").unwrap();
+        assert!(glob_match_prebuilt(&pattern, "a{b}?"));
+        assert!(!glob_match_prebuilt(&pattern, "a{b}x"));
+
+        assert_eq!(GlobPattern::from_simple_regex("a|b").unwrap_err(), GlobError::UnsupportedRegex { position: 1 });
+        assert_eq!(GlobPattern::from_simple_regex("^\\d+").unwrap_err(), GlobError::UnsupportedRegex { position: 1 });
+        assert_eq!(GlobPattern::from_simple_regex("a$b").unwrap_err(), GlobError::UnsupportedRegex { position: 1 });
+        assert_eq!(GlobPattern::from_simple_regex("^$").unwrap_err(), GlobError::EmptyPattern);
+    }
+}
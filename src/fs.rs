@@ -0,0 +1,255 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Walking directories for the paths matching a pattern, enabled by the `fs` feature.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{braces, Glob, GlobError};
+
+/// Paths below the current directory (or below the pattern's own directory if it starts with one)
+/// matching `pattern`, e.g. `src/**/*.rs`. `*`, `?` and classes don't match `/`, see
+/// [`GlobBuilder::literal_separator`](crate::GlobBuilder::literal_separator).
+///
+/// Directories are only read when something below them could still match, entries are returned
+/// in order of their names with a directory before the entries in it. Symlinks to directories aren't followed.
+/// Errors in the pattern are returned up front, errors reading a directory as items.
+pub fn glob(pattern: &str) -> Result<Paths, GlobError> {
+    // the whole pattern first, for errors with positions that refer to it
+    Glob::builder(pattern).literal_separator(true).build()?;
+
+    let alternatives = braces::expand_braces(pattern, None)?
+        .iter()
+        .map(|p| components(p))
+        .collect::<Result<Vec<_>, GlobError>>()?;
+
+    // leading directories without wildcards don't need to be walked to be found
+    let mut base_len = 0;
+    let mut rest = pattern;
+    while let Some(separator) = rest.find('/') {
+        if rest[..separator].contains(['*', '?', '[', '{']) {
+            break;
+        }
+        base_len += separator + 1;
+        rest = &rest[separator + 1..];
+    }
+    let base = &pattern[..base_len];
+
+    let mut paths = Paths {
+        alternatives,
+        stack: Vec::new(),
+        error: None,
+    };
+    let mut states = paths.start();
+    for name in base.split('/').filter(|name| !name.is_empty()) {
+        states = paths.advance(&states, Path::new(name));
+    }
+    let root = PathBuf::from(base);
+    if base.is_empty() || fs::metadata(&root).is_ok_and(|m| m.is_dir()) {
+        paths.stack.push(Visit { path: root, is_dir: true, states });
+    }
+    Ok(paths)
+}
+
+/// A part of a pattern between separators.
+#[derive(Debug)]
+enum Component {
+    /// boxed, a glob is much larger than the other variants
+    Name(Box<Glob>),
+    /// `**` followed by more components, any number of directories
+    AnyDirs,
+    /// `**` at the end, everything below
+    AnyPath,
+}
+
+fn components(pattern: &str) -> Result<Vec<Component>, GlobError> {
+    let names: Vec<&str> = pattern.split('/').filter(|name| !name.is_empty()).collect();
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, &name)| match name {
+            "**" if i + 1 == names.len() => Ok(Component::AnyPath),
+            "**" => Ok(Component::AnyDirs),
+            _ => Glob::builder(name).literal_separator(true).build().map(|glob| Component::Name(Box::new(glob))),
+        })
+        .collect()
+}
+
+/// (alternative, component) pairs, where a component index past the end means the path matches.
+type States = Vec<(usize, usize)>;
+
+#[derive(Debug)]
+struct Visit {
+    path: PathBuf,
+    is_dir: bool,
+    states: States,
+}
+
+/// Iterator over the paths matching a pattern, see [`glob`].
+#[derive(Debug)]
+pub struct Paths {
+    alternatives: Vec<Vec<Component>>,
+    /// next visit last
+    stack: Vec<Visit>,
+    /// reading a directory that matched itself failed, returned after it
+    error: Option<io::Error>,
+}
+
+impl Paths {
+    fn start(&self) -> States {
+        let mut states = Vec::new();
+        for alternative in 0..self.alternatives.len() {
+            self.add_state(&mut states, alternative, 0);
+        }
+        states
+    }
+
+    /// Adds a state, and the following ones if `**` can match no directories there.
+    fn add_state(&self, states: &mut States, alternative: usize, mut component: usize) {
+        loop {
+            if !states.contains(&(alternative, component)) {
+                states.push((alternative, component));
+            }
+            match self.alternatives[alternative].get(component) {
+                Some(Component::AnyDirs) => component += 1,
+                _ => return,
+            }
+        }
+    }
+
+    /// The states after going into `name` from `states`.
+    fn advance(&self, states: &States, name: &Path) -> States {
+        let mut next = Vec::new();
+        for &(alternative, component) in states {
+            match self.alternatives[alternative].get(component) {
+                Some(Component::Name(glob)) if glob.is_match_path(name) =>
+                    self.add_state(&mut next, alternative, component + 1),
+                Some(Component::AnyDirs) => self.add_state(&mut next, alternative, component),
+                Some(Component::AnyPath) => {
+                    self.add_state(&mut next, alternative, component);
+                    self.add_state(&mut next, alternative, component + 1);
+                },
+                _ => {},
+            }
+        }
+        next
+    }
+
+    fn is_match(&self, states: &States) -> bool {
+        states.iter().any(|&(alternative, component)| component == self.alternatives[alternative].len())
+    }
+
+    fn can_descend(&self, states: &States) -> bool {
+        states.iter().any(|&(alternative, component)| component < self.alternatives[alternative].len())
+    }
+
+    /// Pushes the entries of `dir` that something could still match, first name on top.
+    fn read_dir(&mut self, dir: &Path, states: &States) -> io::Result<()> {
+        let read = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(read)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let states = self.advance(states, Path::new(&name));
+            if states.is_empty() {
+                continue;
+            }
+            entries.push(Visit {
+                path: dir.join(&name),
+                is_dir: entry.file_type()?.is_dir(),
+                states,
+            });
+        }
+        entries.sort_by(|a, b| b.path.cmp(&a.path));
+        self.stack.extend(entries);
+        Ok(())
+    }
+}
+
+impl Iterator for Paths {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        while let Some(visit) = self.stack.pop() {
+            let is_match = self.is_match(&visit.states) && !visit.path.as_os_str().is_empty();
+            if visit.is_dir && self.can_descend(&visit.states) {
+                if let Err(e) = self.read_dir(&visit.path, &visit.states) {
+                    if !is_match {
+                        return Some(Err(e));
+                    }
+                    self.error = Some(e);
+                }
+            }
+            if is_match {
+                return Some(Ok(visit.path));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::GlobError;
+
+    /// A fresh directory with `files` in it.
+    fn tree(name: &str, files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("globber-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        root
+    }
+
+    fn glob(root: &Path, pattern: &str) -> Vec<String> {
+        let root = root.to_str().unwrap().replace('\\', "/");
+        super::glob(&format!("{}/{}", root, pattern))
+            .unwrap()
+            .map(|path| path.unwrap().strip_prefix(&root).unwrap().to_str().unwrap().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn walks_matching_paths() {
+        let root = tree("walk", &["src/lib.rs", "src/a/b/mod.rs", "src/a/notes.txt", "tests/it.rs", "Cargo.toml"]);
+        assert_eq!(glob(&root, "src/**/*.rs"), ["src/a/b/mod.rs", "src/lib.rs"]);
+        assert_eq!(glob(&root, "*/*.rs"), ["src/lib.rs", "tests/it.rs"]);
+        assert_eq!(glob(&root, "{src,tests}/*.rs"), ["src/lib.rs", "tests/it.rs"]);
+        assert_eq!(glob(&root, "src/a/**"), ["src/a/b", "src/a/b/mod.rs", "src/a/notes.txt"]);
+        assert_eq!(glob(&root, "Cargo.toml"), ["Cargo.toml"]);
+        assert!(glob(&root, "missing/*.rs").is_empty());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pattern_errors() {
+        assert_eq!(super::glob("src/[a-").unwrap_err(), GlobError::UnterminatedClass { position: 4 });
+        assert_eq!(super::glob("src/a**").unwrap_err(), GlobError::EmptyWildcardSegment { position: 6 });
+    }
+}
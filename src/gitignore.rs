@@ -0,0 +1,213 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching paths against `.gitignore` style rules, enabled by the `gitignore` feature.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{EntryId, Glob, GlobError, GlobList, MatchOutcome, Syntax};
+
+/// Rules read from a `.gitignore` style file, matched against `/` separated paths relative to its directory.
+///
+/// Like git, lines starting with `#` are comments and `!` re-includes what an earlier rule ignored.
+/// A rule ending with `/` only matches directories, and a rule with a `/` anywhere else is anchored
+/// to the directory of the file instead of matching at any depth. `*`, `?` and classes don't match `/`,
+/// `\` escapes the next char and braces are literals, see [`Syntax::Gitignore`].
+#[derive(Debug,Clone,Default)]
+pub struct GitignoreList {
+    list: GlobList,
+    /// rules ending with `/`
+    dir_only: Vec<EntryId>,
+}
+
+/// A rule that couldn't be built, `line` counts from 1 and the error's position refers to the line as written.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub struct GitignoreError {
+    pub line: usize,
+    pub error: GlobError,
+}
+
+impl fmt::Display for GitignoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for GitignoreError {}
+
+impl GitignoreList {
+    pub fn new() -> GitignoreList {
+        GitignoreList::default()
+    }
+
+    /// Reads the rules from the contents of a `.gitignore` file.
+    pub fn parse(contents: &str) -> Result<GitignoreList, GitignoreError> {
+        let mut list = GitignoreList::new();
+        for (i, line) in contents.lines().enumerate() {
+            list.add_line(line).map_err(|error| GitignoreError { line: i + 1, error })?;
+        }
+        Ok(list)
+    }
+
+    /// Adds the rule on `line`, blank lines and comments are skipped.
+    pub fn add_line(&mut self, line: &str) -> Result<(), GlobError> {
+        let line = trim_trailing_spaces(line);
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        // `removed` bytes of the line aren't part of the pattern, for error positions
+        // `\#` and `\!` start a pattern with the char itself, the escape is left to the pattern
+        let (negated, mut removed) = match line.strip_prefix('!') {
+            Some(_) => (true, 1),
+            None => (false, 0),
+        };
+        let mut body = &line[removed..];
+        let dir_only = body.len() > 1 && body.ends_with('/');
+        if dir_only {
+            body = &body[..body.len() - 1];
+        }
+        let anchored = body.contains('/');
+        if let Some(rest) = body.strip_prefix('/') {
+            body = rest;
+            removed += 1;
+        }
+        // an unanchored rule matches a file or directory of that name anywhere
+        let added = if anchored || body == "**" || body.starts_with("**/") { "" } else { "**/" };
+
+        let glob = Glob::builder(&format!("{}{}", added, body))
+            .syntax(Syntax::Gitignore)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| match e.position() {
+                Some(position) => e.at(position - added.len() + removed),
+                None => e,
+            })?;
+        let id = if negated {
+            self.list.add_negated(glob)
+        } else {
+            self.list.add(glob)
+        };
+        if dir_only {
+            self.dir_only.push(id);
+        }
+        Ok(())
+    }
+
+    /// What the last rule matching `path` says about it, `Included` means it's ignored and `Excluded`
+    /// that a `!` rule re-included it. Directories `path` is in aren't looked at, see [`GitignoreList::is_ignored`].
+    pub fn matched(&self, path: &str, is_dir: bool) -> MatchOutcome<'_> {
+        let last = self.list
+            .matching(path)
+            .filter(|m| is_dir || !self.dir_only.contains(&m.id))
+            .last();
+        match last {
+            Some(m) if m.negated => MatchOutcome::Excluded(m),
+            Some(m) => MatchOutcome::Included(m),
+            None => MatchOutcome::Unmatched,
+        }
+    }
+
+    /// Whether `path` or any directory it's in is ignored. Like in git, a rule can't re-include
+    /// something in an ignored directory.
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_end_matches('/');
+        let mut parents = path.match_indices('/').map(|(i, _)| &path[..i]);
+        parents.any(|parent| self.matched(parent, true).is_included()) || self.matched(path, is_dir).is_included()
+    }
+
+    /// The rules as patterns, unanchored ones with a leading `**/`.
+    pub fn as_glob_list(&self) -> &GlobList {
+        &self.list
+    }
+}
+
+/// Trailing spaces are dropped unless escaped with `\`.
+fn trim_trailing_spaces(line: &str) -> &str {
+    let trimmed = line.trim_end_matches(' ');
+    if trimmed.len() < line.len() && trimmed.ends_with('\\') {
+        // keep the escaped space, the pattern drops the backslash
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GitignoreError, GitignoreList};
+    use crate::{GlobError, MatchOutcome};
+
+    #[test]
+    fn gitignore_rules() {
+        let rules = GitignoreList::parse("\
+# build output
+target/
+*.log
+!keep.log
+/Cargo.lock
+docs/*.html
+").unwrap();
+        assert!(rules.is_ignored("target", true));
+        assert!(rules.is_ignored("target/debug/globber", false));
+        assert!(rules.is_ignored("crates/a/target/x.rs", false));
+        assert!(!rules.is_ignored("target", false));
+        assert!(rules.is_ignored("logs/a.log", false));
+        assert!(!rules.is_ignored("logs/keep.log", false));
+        assert!(rules.is_ignored("Cargo.lock", false));
+        assert!(!rules.is_ignored("crates/a/Cargo.lock", false));
+        assert!(rules.is_ignored("docs/index.html", false));
+        assert!(!rules.is_ignored("docs/api/index.html", false));
+        assert!(!rules.is_ignored("src/lib.rs", false));
+        assert!(matches!(rules.matched("keep.log", false), MatchOutcome::Excluded(m) if m.pattern == "**/keep.log"));
+    }
+
+    #[test]
+    fn double_star_alone_ignores_everything() {
+        let rules = GitignoreList::parse("**\n!*.rs\n").unwrap();
+        assert!(rules.is_ignored("a", false) && rules.is_ignored("a/b.txt", false));
+        assert!(!rules.is_ignored("lib.rs", false));
+        assert!(matches!(rules.matched("a", false), MatchOutcome::Included(m) if m.pattern == "**"));
+    }
+
+    #[test]
+    fn braces_and_groups_are_literals() {
+        let rules = GitignoreList::parse("*.{log,tmp}\n(?i:a)\n").unwrap();
+        assert!(!rules.is_ignored("a.log", false) && !rules.is_ignored("A", false));
+        assert!(rules.is_ignored("x.{log,tmp}", false) && rules.is_ignored("dir/(?i:a)", false));
+    }
+
+    #[test]
+    fn ignored_directory_can_not_be_reincluded() {
+        let rules = GitignoreList::parse("build/\n!build/keep.txt\n").unwrap();
+        assert!(!rules.matched("build/keep.txt", false).is_included());
+        assert!(rules.is_ignored("build/keep.txt", false));
+    }
+
+    #[test]
+    fn escapes_and_errors() {
+        let rules = GitignoreList::parse("\\#notes\n\\!important\n").unwrap();
+        assert!(rules.is_ignored("#notes", false) && rules.is_ignored("!important", false));
+        let rules = GitignoreList::parse("\\*.txt\nx\\!\nspace\\ \n").unwrap();
+        assert!(rules.is_ignored("*.txt", false) && !rules.is_ignored("a.txt", false));
+        assert!(rules.is_ignored("x!", false) && rules.is_ignored("space ", false) && !rules.is_ignored("space", false));
+        assert_eq!(GitignoreList::parse("ok\n!/src/[a-").unwrap_err(),
+                   GitignoreError { line: 2, error: GlobError::UnterminatedClass { position: 6 } });
+    }
+}
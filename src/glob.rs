@@ -0,0 +1,534 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::budget::Budget;
+use crate::{build_glob_pattern_with, glob_match_budgeted, BudgetExceeded, captures, glob_match_bytes_folded, glob_match_folded_prebuilt,
+            glob_match_prebuilt, glob_match_prebuilt_anchored, glob_match_prebuilt_chunks, glob_match_spans_prebuilt,
+            glob_match_units, matched_range, Dfa, Anchoring, CaseFold, CaseMode, CaptureParseError, Captures, GlobError, GlobPattern, MatchContext, MatchOptions, PatternOptions, Syntax};
+
+/// A compiled pattern together with the options it was built with, see [`Glob::builder`].
+#[derive(Debug,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Glob {
+    /// case folded when matching ignores case
+    pub(crate) pattern: GlobPattern,
+    pub(crate) source: String,
+    pub(crate) case: CaseMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) unanchored: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) graphemes: bool,
+    /// values are normalized to NFC before matching, the pattern already is
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) normalized: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) dfa: Option<Dfa>,
+}
+
+/// Options for building a [`Glob`], created by [`Glob::builder`].
+#[derive(Debug,Clone)]
+pub struct GlobBuilder<'a> {
+    pattern: &'a str,
+    case: CaseMode,
+    case_fold: CaseFold,
+    options: PatternOptions,
+    max_pattern_len: Option<usize>,
+    normalize: bool,
+    dfa: bool,
+}
+
+impl GlobBuilder<'_> {
+    /// Whether matching ignores case, off by default.
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.case = if yes { CaseMode::Insensitive } else { CaseMode::Sensitive };
+        self
+    }
+
+    /// Whether matching ignores the case of ASCII letters only, off by default. Other chars are
+    /// compared as they are, which skips the Unicode case tables and is faster on the ASCII
+    /// values of logs and paths. Replaces [`GlobBuilder::case_insensitive`].
+    pub fn ascii_case_insensitive(mut self, yes: bool) -> Self {
+        self.case = if yes { CaseMode::AsciiInsensitive } else { CaseMode::Sensitive };
+        self
+    }
+
+    /// Which language's case pairs [`GlobBuilder::case_insensitive`] goes by, [`CaseFold::Default`] by default.
+    /// With [`CaseFold::Turkic`] `I` matches `ı` and `İ` matches `i`, rather than `I` matching `i`.
+    pub fn case_fold(mut self, fold: CaseFold) -> Self {
+        self.case_fold = fold;
+        self
+    }
+
+    /// Whether the pattern matches anywhere in a value instead of the whole value, off by default.
+    /// `foo*bar` then matches `log: foo went to the bar`, as if it was written `*foo*bar*`.
+    ///
+    /// Everything matching (and [`Glob::find`]) goes by this, though [`Glob::captures`] then includes what comes
+    /// before and after the match.
+    pub fn unanchored(mut self, yes: bool) -> Self {
+        self.options.unanchored = yes;
+        self
+    }
+
+    /// Whether `*`, `?` and negated classes stop at `/`, off by default.
+    ///
+    /// With or without it `**/` matches any number of directories and a trailing `/**` everything below,
+    /// so `src/**/*.rs` matches `src/lib.rs` and `src/a/b/lib.rs`.
+    pub fn literal_separator(mut self, yes: bool) -> Self {
+        self.options.literal_separator = yes;
+        self
+    }
+
+    /// Whether wildcards don't match a `.` at the start of a path component, off by default, so `*` and
+    /// `**/*` don't match `.git` or `src/.env` while `.*` and `**/.git` still do. A `*` in front of a `.`
+    /// can't match nothing there either, `*.txt` doesn't match `.txt`.
+    /// Hidden files are then only matched by a pattern with a literal `.` in that place, as shells and
+    /// the `glob` crate do.
+    pub fn literal_leading_dot(mut self, yes: bool) -> Self {
+        self.options.literal_leading_dot = yes;
+        self
+    }
+
+    /// Sets case sensitivity, [`GlobBuilder::literal_separator`] and [`GlobBuilder::literal_leading_dot`] from `options`, for code written
+    /// against the `glob` crate's options.
+    pub fn match_options(self, options: MatchOptions) -> Self {
+        self.case_insensitive(!options.case_sensitive)
+            .literal_separator(options.require_literal_separator)
+            .literal_leading_dot(options.require_literal_leading_dot)
+    }
+
+    /// The fewest chars each `*` matches, 0 by default. With 1 a `*` matches one or more chars,
+    /// `*.rs` then doesn't match `.rs`. `**` still matches any number of directories, none included.
+    ///
+    /// The `*` is built as that many `?` followed by `*`, so [`Glob::captures`] has one capture for each of them.
+    pub fn wildcard_min_len(mut self, min_len: usize) -> Self {
+        self.options.wildcard_min_len = min_len;
+        self
+    }
+
+    /// Whether wildcards follow DOS and Windows rules, like `FindFirstFile` does, off by default.
+    /// `*.*` then matches any name, even without a `.`, a name ending in `.*` matches without the extension
+    /// (`setup.*` matches `setup`), and `?` at the end of a name or before a `.` can match nothing, so
+    /// `REPORT??.TXT` matches `REPORT.TXT` and `REPORT12.TXT`.
+    pub fn dos(mut self, yes: bool) -> Self {
+        self.options.dos = yes;
+        self
+    }
+
+    /// How the pattern is written, [`Syntax::Glob`] by default. [`Syntax::Fnmatch`] matches like POSIX
+    /// `fnmatch(3)` with `FNM_PATHNAME` and `FNM_PERIOD`: `*`, `?` and classes don't match `/`, nor a `.`
+    /// at the start of a name, so `*` doesn't match `.bashrc` and `*/*.rs` doesn't match `src/.hidden.rs`.
+    /// Calling [`GlobBuilder::literal_separator`] with `false` afterwards leaves `FNM_PATHNAME` out, a `.`
+    /// after a `/` then still has to be matched literally, see [`GlobBuilder::literal_leading_dot`].
+    /// [`Syntax::Gitignore`] is the crate's own syntax without braces or groups and with `\` escapes.
+    pub fn syntax(mut self, syntax: Syntax) -> Self {
+        self.options.syntax = syntax;
+        if syntax == Syntax::Fnmatch {
+            self.options.literal_separator = true;
+            self.options.literal_leading_dot = true;
+        }
+        self
+    }
+
+    /// The longest pattern in bytes that builds, longer ones fail with [`GlobError::PatternTooLong`].
+    /// Unlimited by default, for bounding the cost of patterns coming from users.
+    pub fn max_pattern_len(mut self, max: usize) -> Self {
+        self.max_pattern_len = Some(max);
+        self
+    }
+
+    /// The most literals and wildcards a pattern can have, see [`GlobPattern::segment_count`], more fail with
+    /// [`GlobError::TooManySegments`]. Unlimited by default, for bounding the cost of patterns coming from users.
+    ///
+    /// Brace groups count every alternative, so `{a,b}{c,d}` has four literals. Expanding them stops once
+    /// there are too many, a pattern of repeated groups can't take exponential time to reject.
+    pub fn max_segments(mut self, max: usize) -> Self {
+        self.options.max_segments = Some(max);
+        self
+    }
+
+    /// Whether [`Glob::is_match`] goes by extended grapheme clusters instead of chars, off by default,
+    /// enabled by the `unicode-segmentation` feature. A `?` or class then matches a whole emoji or a letter
+    /// with its combining accents, and literals only match whole ones, so `cafe*` doesn't match `café`
+    /// written with a combining accent. Lists and sets of patterns go by it too, other ways of matching
+    /// (prefixes, byte strings, captures, budgets, ...) still go by chars, and [`GlobBuilder::dfa`] is ignored.
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn graphemes(mut self, yes: bool) -> Self {
+        self.options.graphemes = yes;
+        self
+    }
+
+    /// Whether the pattern and values are compared in Unicode NFC, off by default, enabled by the
+    /// `unicode-normalization` feature. `café*` then matches `café` whether the `é` is one char or an `e`
+    /// followed by a combining accent, the way macOS returns file names. Values are normalized for matching
+    /// and prefixes, suffixes and substrings, not for captures or finding ranges.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize(mut self, yes: bool) -> Self {
+        self.normalize = yes;
+        self
+    }
+
+    /// Whether [`Glob::is_match`] uses the pattern compiled to a [`Dfa`], off by default.
+    /// Matching then takes time linear in the length of the value no matter the pattern,
+    /// building fails with [`GlobError::DfaTooLarge`] if it would take too many states.
+    pub fn dfa(mut self, yes: bool) -> Self {
+        self.dfa = yes;
+        self
+    }
+
+    pub fn build(&self) -> Result<Glob, GlobError> {
+        if let Some(max) = self.max_pattern_len {
+            if self.pattern.len() > max {
+                return Err(GlobError::PatternTooLong { max });
+            }
+        }
+        let case = match (self.case, self.case_fold) {
+            (CaseMode::Insensitive, CaseFold::Turkic) => CaseMode::TurkicInsensitive,
+            (case, _) => case,
+        };
+        #[cfg(feature = "unicode-normalization")]
+        let normalized = if self.normalize { crate::normalize::nfc(self.pattern) } else { Cow::Borrowed(self.pattern) };
+        #[cfg(not(feature = "unicode-normalization"))]
+        let normalized = Cow::Borrowed(self.pattern);
+        let pattern = build_glob_pattern_with(&case.fold(&normalized), &self.options)
+            // folding can move things around, the position should refer to the pattern itself
+            .map_err(|e| build_glob_pattern_with(self.pattern, &self.options).err().unwrap_or(e))?;
+        Ok(Glob {
+            source: self.pattern.to_string(),
+            case,
+            unanchored: self.options.unanchored,
+            graphemes: self.options.graphemes,
+            normalized: self.normalize,
+            dfa: if self.dfa && !self.options.graphemes { Some(pattern.compile_dfa()?) } else { None },
+            pattern,
+        })
+    }
+}
+
+impl Glob {
+    /// Builds a case sensitive pattern, same as `Glob::builder(pattern).build()`.
+    pub fn new(pattern: &str) -> Result<Glob, GlobError> {
+        Glob::builder(pattern).build()
+    }
+
+    pub fn builder(pattern: &str) -> GlobBuilder<'_> {
+        GlobBuilder {
+            pattern,
+            case: CaseMode::Sensitive,
+            case_fold: CaseFold::Default,
+            options: PatternOptions::default(),
+            max_pattern_len: None,
+            normalize: false,
+            dfa: false,
+        }
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case != CaseMode::Sensitive
+    }
+
+    /// Whether the pattern only ignores the case of ASCII letters, see [`GlobBuilder::ascii_case_insensitive`].
+    pub fn is_ascii_case_insensitive(&self) -> bool {
+        self.case == CaseMode::AsciiInsensitive
+    }
+
+    /// Which language's case pairs the pattern goes by when ignoring case, see [`GlobBuilder::case_fold`].
+    pub fn case_fold(&self) -> CaseFold {
+        match self.case {
+            CaseMode::TurkicInsensitive => CaseFold::Turkic,
+            _ => CaseFold::Default,
+        }
+    }
+
+    /// Whether the pattern matches anywhere in a value, see [`GlobBuilder::unanchored`].
+    pub fn is_unanchored(&self) -> bool {
+        self.unanchored
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The compiled pattern, case folded when matching ignores case.
+    pub fn pattern(&self) -> &GlobPattern {
+        &self.pattern
+    }
+
+    /// `value` the way the pattern sees it.
+    fn folded<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        if let Cow::Owned(value) = self.normalized(value) {
+            return Cow::Owned(self.case.fold(&value).into_owned());
+        }
+        self.case.fold(value)
+    }
+
+    /// `value` normalized the way the pattern is, see [`GlobBuilder::normalize`].
+    #[cfg(feature = "unicode-normalization")]
+    fn normalized<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        if self.normalized { crate::normalize::nfc(value) } else { Cow::Borrowed(value) }
+    }
+
+    #[cfg(not(feature = "unicode-normalization"))]
+    fn normalized<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        Cow::Borrowed(value)
+    }
+
+    /// Matches `folded`, a value already case folded the way the pattern is.
+    pub(crate) fn matches_folded(&self, folded: &str) -> bool {
+        if let Cow::Owned(folded) = self.normalized(folded) {
+            return self.matches_folded(&folded);
+        }
+        #[cfg(feature = "unicode-segmentation")]
+        if self.graphemes {
+            return crate::graphemes::is_match(&self.pattern, folded);
+        }
+        glob_match_prebuilt(&self.pattern, folded)
+    }
+
+    /// Doesn't allocate, when ignoring case `value` is case folded one char at a time while matching.
+    pub fn is_match(&self, value: &str) -> bool {
+        if let Cow::Owned(value) = self.normalized(value) {
+            return self.is_match(&value);
+        }
+        #[cfg(feature = "unicode-segmentation")]
+        if self.graphemes {
+            return crate::graphemes::is_match(&self.pattern, &self.folded(value));
+        }
+        if let Some(dfa) = &self.dfa {
+            return dfa.is_match_folded(value, self.case);
+        }
+        glob_match_folded_prebuilt(&self.pattern, value, self.case)
+    }
+
+    /// Whether `value` is the start of some value that matches, see [`GlobPattern::is_match_prefix`].
+    pub fn is_match_prefix(&self, value: &str) -> bool {
+        crate::prefix::is_match_prefix(&self.pattern, &self.normalized(value), self.case)
+    }
+
+    /// Same as [`Glob::is_match`], but gives up after `max_steps` steps, for running patterns from users
+    /// against values from users. A step is trying a part of the pattern at a place in `value`, patterns
+    /// without `?`, classes or `**` (and any with a [DFA](GlobBuilder::dfa)) take one per byte of `value`.
+    pub fn is_match_with_budget(&self, value: &str, max_steps: usize) -> Result<bool, BudgetExceeded> {
+        let mut budget = Budget::new(max_steps);
+        let matched = match &self.dfa {
+            Some(dfa) if budget.charge(value.len()) => Some(dfa.is_match_folded(value, self.case)),
+            Some(_) => None,
+            None => glob_match_budgeted(&self.pattern, value, self.case, &mut budget),
+        };
+        matched.ok_or(BudgetExceeded { max_steps })
+    }
+
+    /// Same as [`Glob::is_match`], but case folds `value` into `ctx` instead of allocating.
+    pub fn is_match_with(&self, ctx: &mut MatchContext, value: &str) -> bool {
+        match self.case {
+            CaseMode::Insensitive => self.matches_folded(ctx.fold(value)),
+            _ => self.is_match(value),
+        }
+    }
+
+    /// Same as [`Glob::is_match`], the whole value has to match unless the pattern is [unanchored](GlobBuilder::unanchored).
+    pub fn match_full(&self, value: &str) -> bool {
+        self.is_match(value)
+    }
+
+    /// Whether the pattern matches the start of `value`, as if it ended with `*`.
+    pub fn match_prefix(&self, value: &str) -> bool {
+        glob_match_prebuilt_anchored(&self.pattern, &self.folded(value), Anchoring::Prefix)
+    }
+
+    /// Whether the pattern matches the end of `value`, as if it started with `*`.
+    pub fn match_suffix(&self, value: &str) -> bool {
+        glob_match_prebuilt_anchored(&self.pattern, &self.folded(value), Anchoring::Suffix)
+    }
+
+    /// Whether the pattern matches anywhere within `value`, as if it started and ended with `*`.
+    pub fn match_within(&self, value: &str) -> bool {
+        glob_match_prebuilt_anchored(&self.pattern, &self.folded(value), Anchoring::Within)
+    }
+
+    /// Matches `chunks` as if they were concatenated into one string, without concatenating them.
+    ///
+    /// A [DFA](GlobBuilder::dfa) runs over the chunks as they are. Without one a pattern with `?`, classes or `**`
+    /// collects the chars of the chunks into one buffer first, and with normalizing or grapheme clusters
+    /// the chunks are joined and matched like [`Glob::is_match`].
+    pub fn is_match_chunks(&self, chunks: &[&str]) -> bool {
+        if self.normalized || self.graphemes {
+            return self.is_match(&chunks.concat());
+        }
+        if let Some(dfa) = &self.dfa {
+            let state = chunks
+                .iter()
+                .flat_map(|c| c.chars())
+                .fold(dfa.start_state(), |state, ch| crate::stream::step(dfa, self.case, state, ch));
+            return dfa.is_accepting(state);
+        }
+        // fold char by char instead of allocating folded chunks
+        match self.case {
+            CaseMode::Sensitive => glob_match_prebuilt_chunks(&self.pattern, chunks),
+            CaseMode::Insensitive | CaseMode::TurkicInsensitive => glob_match_units(
+                &self.pattern,
+                || chunks.iter().flat_map(|c| c.chars()).flat_map(|c| self.case.fold_char(c)),
+                || chunks.iter().rev().flat_map(|c| c.chars().rev()).flat_map(|c| self.case.fold_char(c).rev())),
+            CaseMode::AsciiInsensitive => glob_match_units(
+                &self.pattern,
+                || chunks.iter().flat_map(|c| c.bytes()).map(|b| b.to_ascii_lowercase()),
+                || chunks.iter().rev().flat_map(|c| c.bytes().rev()).map(|b| b.to_ascii_lowercase())),
+        }
+    }
+
+    /// Matches a byte string without converting it to a `String` first, e.g. a network payload or a file name.
+    ///
+    /// Wildcards match a UTF-8 char where there is one and a single byte where the bytes aren't valid UTF-8.
+    /// When ignoring case invalid UTF-8 is compared as U+FFFD, which only wildcards match.
+    pub fn is_match_bytes(&self, value: &[u8]) -> bool {
+        glob_match_bytes_folded(&self.pattern, value, self.case)
+    }
+
+    /// Matches a path without converting it to a `String` first, the pattern separates directories with `/`.
+    ///
+    /// On Windows `\\` in `path` matches `/` in the pattern too. On Unix the path is matched as bytes,
+    /// see [`Glob::is_match_bytes`], elsewhere invalid parts become U+FFFD which only wildcards match.
+    pub fn is_match_path(&self, path: &Path) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            self.is_match_bytes(path.as_os_str().as_bytes())
+        }
+        #[cfg(not(unix))]
+        {
+            let value = path.to_string_lossy();
+            #[cfg(windows)]
+            let value = value.replace('\\', "/");
+            self.is_match(&value)
+        }
+    }
+
+    /// Byte ranges of the literal parts of the pattern within `value`, or `None` if it doesn't match.
+    ///
+    /// The ranges refer to `value` itself, not to its case folded form.
+    pub fn match_spans(&self, value: &str) -> Option<Vec<Range<usize>>> {
+        if self.case == CaseMode::Sensitive {
+            return glob_match_spans_prebuilt(&self.pattern, value);
+        }
+        let mut ctx = MatchContext::new();
+        ctx.spans(&self.pattern, value, self.case)?;
+        Some(ctx.spans)
+    }
+
+    /// The byte range of `value` the match covers, or `None` if it doesn't match, e.g. `4..7` for `*.rs`
+    /// on `main.rs`. Leading and trailing `*` aren't part of it, for a pattern of only `*` it's empty.
+    ///
+    /// The range refers to `value` itself, not to its case folded form.
+    pub fn find(&self, value: &str) -> Option<Range<usize>> {
+        let mut ctx = MatchContext::new();
+        ctx.ranges(&self.pattern, value, self.case, matched_range)?;
+        ctx.spans.pop()
+    }
+
+    /// The byte range of every match within `haystack` in order, not overlapping, e.g. to highlight every
+    /// `ERROR*timeout` in a log line. Each one is found like [`Glob::find`] in what's left after the previous one.
+    ///
+    /// Only an [unanchored](GlobBuilder::unanchored) pattern can match more than once, otherwise it's
+    /// the same as [`Glob::find`].
+    pub fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        let mut pos = Some(0);
+        std::iter::from_fn(move || {
+            let start = pos?;
+            let found = self.find(&haystack[start..]).map(|r| start + r.start..start + r.end);
+            pos = match &found {
+                Some(r) if self.unanchored && r.is_empty() =>
+                    // step past it so an empty match isn't found again
+                    haystack[r.end..].chars().next().map(|ch| r.end + ch.len_utf8()),
+                Some(r) if self.unanchored => Some(r.end),
+                _ => None,
+            };
+            found
+        })
+    }
+
+    /// The text matched by each wildcard, or `None` if `value` doesn't match.
+    ///
+    /// The captures refer to `value` itself, not to its case folded form.
+    pub fn captures<'a>(&self, value: &'a str) -> Option<Captures<'a>> {
+        let mut ctx = MatchContext::new();
+        let matched = ctx.spans(&self.pattern, value, self.case)?;
+        Some(captures::captures_from_spans(matched, value, ctx.spans))
+    }
+
+    /// Same as [`Glob::captures`], but keeps the case folded value and the captures in `ctx` instead of allocating.
+    pub fn captures_with<'a>(&self, ctx: &'a mut MatchContext, value: &'a str) -> Option<Captures<'a>> {
+        let matched = ctx.spans(&self.pattern, value, self.case)?;
+        Some(captures::captures_from_spans_in(matched, value, &ctx.spans, &mut ctx.captures))
+    }
+
+    /// The text matched by each wildcard parsed as `T`, or `None` if `value` doesn't match.
+    pub fn captures_as<T: FromStr>(&self, value: &str) -> Option<Result<Vec<T>, CaptureParseError<T::Err>>> {
+        self.captures(value).map(|c| c.parse_all())
+    }
+}
+
+/// Builds a case sensitive pattern, same as [`Glob::new`].
+impl FromStr for Glob {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<Glob, GlobError> {
+        Glob::new(pattern)
+    }
+}
+
+impl TryFrom<&str> for Glob {
+    type Error = GlobError;
+
+    fn try_from(pattern: &str) -> Result<Glob, GlobError> {
+        Glob::new(pattern)
+    }
+}
+
+/// The pattern as it was written, see [`Glob::as_str`].
+impl fmt::Display for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Compares the compiled patterns and how they treat case, not how they were written,
+/// so `*.JPG` and `*.jpg` are equal when both ignore case.
+impl PartialEq for Glob {
+    fn eq(&self, other: &Glob) -> bool {
+        self.case == other.case && self.graphemes == other.graphemes && self.normalized == other.normalized && self.pattern == other.pattern
+    }
+}
+
+impl Eq for Glob {}
+
+impl Hash for Glob {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.case.hash(state);
+        self.graphemes.hash(state);
+        self.normalized.hash(state);
+        self.pattern.hash(state);
+    }
+}
@@ -0,0 +1,168 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching by extended grapheme clusters instead of chars, see [`GlobBuilder::graphemes`](crate::GlobBuilder::graphemes).
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{fold_case, glob_match_prebuilt, GlobPattern, Segment};
+
+/// Matches `value` (already case folded as the pattern is) a grapheme at a time, a `?` or class takes a whole
+/// grapheme and literals only match whole graphemes, so `cafe` doesn't match the start of `café` written with a
+/// combining accent. Only segmented patterns are built in grapheme mode, others go by chars.
+pub(crate) fn is_match(pattern: &GlobPattern, value: &str) -> bool {
+    match pattern {
+        GlobPattern::Segmented(segments) => match_segments(segments, &value.graphemes(true).collect::<Vec<_>>()),
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(|p| is_match(p, value)),
+        _ => glob_match_prebuilt(pattern, value),
+    }
+}
+
+/// Tracks every grapheme position each segment can start at, so no backtracking is needed.
+fn match_segments(segments: &[Segment], value: &[&str]) -> bool {
+    let mut reachable = vec![false; value.len() + 1];
+    reachable[0] = true;
+    for segment in segments {
+        let mut next = vec![false; value.len() + 1];
+        for start in (0..=value.len()).filter(|&i| reachable[i]) {
+            let rest = &value[start..];
+            match segment {
+                Segment::Literal(literal) => {
+                    if let Some(len) = literal_len(literal, rest, false) {
+                        next[start + len] = true;
+                    }
+                },
+                Segment::LiteralIgnoreCase(literal) => {
+                    if let Some(len) = literal_len(literal, rest, true) {
+                        next[start + len] = true;
+                    }
+                },
+                Segment::AnyChar => {
+                    if !rest.is_empty() {
+                        next[start + 1] = true;
+                    }
+                },
+                Segment::AnyCharExceptSeparator => {
+                    if rest.first().is_some_and(|&g| g != "/") {
+                        next[start + 1] = true;
+                    }
+                },
+                Segment::Class(class) => {
+                    let mut chars = rest.first().map_or("", |g| g).chars();
+                    let matched = match (chars.next(), chars.next()) {
+                        (None, _) => false,
+                        (Some(ch), None) => class.contains(ch),
+                        // several chars can't be one char in the class, but aren't any of them either
+                        (Some(_), Some(_)) => class.is_negated(),
+                    };
+                    if matched {
+                        next[start + 1] = true;
+                    }
+                },
+                Segment::AnyChars | Segment::AnyPath => next[start..].iter_mut().for_each(|n| *n = true),
+                Segment::AnyCharsExceptSeparator => {
+                    let len = rest.iter().position(|&g| g == "/").unwrap_or(rest.len());
+                    next[start..=start + len].iter_mut().for_each(|n| *n = true);
+                },
+                Segment::AnyDirs => {
+                    next[start] = true;
+                    for (i, &g) in rest.iter().enumerate() {
+                        if g == "/" {
+                            next[start + i + 1] = true;
+                        }
+                    }
+                },
+                Segment::AnyVisibleChars | Segment::AnyVisibleDirs => {
+                    let stop = (start..value.len()).find(|&i| hidden_at(value, i)).unwrap_or(value.len());
+                    next[start] = true;
+                    for i in start..stop {
+                        if matches!(segment, Segment::AnyVisibleChars) || value[i] == "/" {
+                            next[i + 1] = true;
+                        }
+                    }
+                },
+            }
+        }
+        reachable = next;
+    }
+    reachable[value.len()]
+}
+
+/// Whether a path component starting with `.` starts at grapheme `i`.
+fn hidden_at(value: &[&str], i: usize) -> bool {
+    value[i].starts_with('.') && (i == 0 || value[i - 1] == "/")
+}
+
+/// How many graphemes at the start of `value` make up `literal`, compared case folded when `ignore_case`.
+fn literal_len(literal: &str, value: &[&str], ignore_case: bool) -> Option<usize> {
+    let mut len = 0;
+    for expected in literal.graphemes(true) {
+        let &grapheme = value.get(len)?;
+        let equal = if ignore_case { fold_case(grapheme) == expected } else { grapheme == expected };
+        if !equal {
+            return None;
+        }
+        len += 1;
+    }
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, GlobList, GlobSet};
+
+    fn graphemes(pattern: &str) -> Glob {
+        Glob::builder(pattern).graphemes(true).build().unwrap()
+    }
+
+    #[test]
+    fn wildcards_match_whole_graphemes() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let accented = "cafe\u{301}";
+        assert!(graphemes("?").is_match(family) && !Glob::new("?").unwrap().is_match(family));
+        assert!(graphemes("caf?").is_match(accented) && !Glob::new("caf?").unwrap().is_match(accented));
+        assert!(graphemes("[!x]").is_match("e\u{301}") && !graphemes("[e]").is_match("e\u{301}"));
+        assert!(graphemes("??").is_match("\u{1F1F8}\u{1F1EA}\u{1F1EB}\u{1F1F7}")); // two flags
+        // literals don't match part of a grapheme
+        assert!(!graphemes("cafe*").is_match(accented) && Glob::new("cafe*").unwrap().is_match(accented));
+        assert!(graphemes("caf*").is_match(accented) && graphemes(&format!("*{}", "e\u{301}")).is_match(accented));
+        assert!(graphemes("*").is_match("") && graphemes("a*b").is_match("ab") && !graphemes("a*b").is_match("ba"));
+    }
+
+    #[test]
+    fn graphemes_with_other_options() {
+        let pattern = Glob::builder("src/**/?.txt").graphemes(true).literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("src/a/b/e\u{301}.txt") && pattern.is_match("src/\u{1F600}.txt"));
+        assert!(!pattern.is_match("src/ab.txt"));
+        let pattern = Glob::builder("É?").graphemes(true).case_insensitive(true).build().unwrap();
+        assert!(pattern.is_match("éa\u{308}"));
+        let pattern = Glob::builder("(?i:x)?").graphemes(true).build().unwrap();
+        assert!(pattern.is_match("Xa\u{308}") && !pattern.is_match("Xab"));
+        let pattern = Glob::builder("?").graphemes(true).unanchored(true).dfa(true).build().unwrap();
+        assert!(pattern.is_match("e\u{301}") && pattern.as_str() == "?");
+        assert!(graphemes("caf?").is_match_chunks(&["cafe", "\u{301}"]));
+        assert_ne!(graphemes("?"), Glob::new("?").unwrap());
+
+        let mut list = GlobList::new();
+        list.add(graphemes("caf?"));
+        assert!(list.any_match("cafe\u{301}") && list.all_match("cafe\u{301}"));
+        assert!(GlobList::from_bytes(&list.to_bytes()).unwrap().any_match("cafe\u{301}"));
+        let set = GlobSet::new(vec![graphemes("*.?")]);
+        assert_eq!(set.matches("a.e\u{301}"), vec![0]);
+    }
+}
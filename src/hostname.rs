@@ -0,0 +1,90 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching host names a DNS label at a time, for certificate names and virtual hosts.
+
+use crate::{Glob, GlobError};
+
+/// A pattern for host names where wildcards stay within one label, `*.example.com` matches
+/// `www.example.com` but neither `a.b.example.com` nor `example.com`.
+///
+/// The pattern is split into labels at every `.` (so braces and classes can't have one in them) and each
+/// label is matched like a [`Glob`] ignoring ASCII case, as DNS does. A trailing `.` on the pattern or the
+/// host name is ignored.
+#[derive(Debug,Clone)]
+pub struct HostnameGlob {
+    source: String,
+    labels: Vec<Glob>,
+}
+
+impl HostnameGlob {
+    pub fn new(pattern: &str) -> Result<HostnameGlob, GlobError> {
+        let mut labels = Vec::new();
+        let mut start = 0;
+        for label in trim_root(pattern).split('.') {
+            let glob = Glob::builder(label)
+                .ascii_case_insensitive(true)
+                .build()
+                .map_err(|e| e.position().map_or(e, |p| e.at(start + p)))?;
+            labels.push(glob);
+            start += label.len() + 1;
+        }
+        Ok(HostnameGlob { source: pattern.to_string(), labels })
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether `host` has as many labels as the pattern and each of them matches its label of the pattern.
+    pub fn is_match(&self, host: &str) -> bool {
+        let mut labels = trim_root(host).split('.');
+        self.labels.iter().all(|glob| labels.next().is_some_and(|label| !label.is_empty() && glob.is_match(label)))
+            && labels.next().is_none()
+    }
+}
+
+/// `name` without the `.` of the root zone at the end.
+fn trim_root(name: &str) -> &str {
+    name.strip_suffix('.').unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GlobError, HostnameGlob};
+
+    #[test]
+    fn wildcards_stay_in_one_label() {
+        let glob = HostnameGlob::new("*.example.com").unwrap();
+        for &(host, expected) in [("a.example.com", true), ("WWW.Example.COM.", true), ("a.b.example.com", false),
+                                  ("example.com", false), (".example.com", false), ("a.example.org", false)].iter() {
+            assert_eq!(glob.is_match(host), expected, "{}", host);
+        }
+        let glob = HostnameGlob::new("api-?.*.internal").unwrap();
+        assert!(glob.is_match("api-1.eu.internal"));
+        assert!(!glob.is_match("api-1.eu.west.internal"));
+        assert!(HostnameGlob::new("{www,api}.example.com").unwrap().is_match("api.example.com"));
+    }
+
+    #[test]
+    fn errors_point_into_the_pattern() {
+        assert!(matches!(HostnameGlob::new("a.[bc.com"), Err(GlobError::UnterminatedClass { position: 2 })));
+        assert_eq!(HostnameGlob::new("*.example.com.").unwrap().as_str(), "*.example.com.");
+    }
+}
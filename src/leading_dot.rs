@@ -0,0 +1,194 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Keeping wildcards from matching the `.` of hidden files, like `FNM_PERIOD` does for `fnmatch(3)`.
+//!
+//! The segments are rewritten rather than the matchers changed: a `?` or class at the start of a path
+//! component becomes a class without `.`, and `*` and `**` become [`Segment::AnyVisibleChars`] and
+//! [`Segment::AnyVisibleDirs`], which stop in front of a `.` starting a component. Where a single char
+//! wildcard could match a `/` in front of another wildcard that `/` is split off, so whatever follows a
+//! `/` is always known to be at the start of a component, which the DFA and regexes rely on.
+
+use crate::segments::SEPARATOR;
+use crate::{CharClass, GlobPattern, Segment};
+
+/// `segments` with wildcards not matching a `.` at the start of a path component. A `*` there can't be
+/// empty in front of a `.` either, `*.txt` doesn't match `.txt`, though `**/.git` does match `.git`,
+/// and doesn't start with a `/` even without a literal separator.
+pub(crate) fn restrict(segments: &[Segment]) -> GlobPattern {
+    let mut alternatives = Vec::new();
+    expand(segments, Vec::new(), &mut alternatives);
+    if alternatives.len() == 1 {
+        GlobPattern::Segmented(alternatives.remove(0))
+    } else {
+        GlobPattern::Alternatives(alternatives.into_iter().map(GlobPattern::Segmented).collect())
+    }
+}
+
+/// Adds the ways `segments` can match from the start of a component to `built` into `alternatives`.
+fn expand(segments: &[Segment], mut built: Vec<Segment>, alternatives: &mut Vec<Vec<Segment>>) {
+    let mut at_start = true;
+    for (i, segment) in segments.iter().enumerate() {
+        let rest = &segments[i + 1..];
+        match segment {
+            Segment::Literal(s) | Segment::LiteralIgnoreCase(s) => {
+                built.push(segment.clone());
+                at_start = s.ends_with(SEPARATOR);
+            },
+            Segment::AnyChar | Segment::AnyCharExceptSeparator | Segment::Class(_) => {
+                let single = match at_start {
+                    true => match excluding(segment, '.') {
+                        Some(single) => single,
+                        // only matches a `.`
+                        None => return,
+                    },
+                    false => segment.clone(),
+                };
+                let literal_next = matches!(rest.first(), None | Some(Segment::Literal(_)) | Some(Segment::LiteralIgnoreCase(_)));
+                if literal_next || !matches_separator(&single) {
+                    built.push(single);
+                } else {
+                    let mut separated = built.clone();
+                    separated.push(Segment::Literal(SEPARATOR.to_string()));
+                    expand(rest, separated, alternatives);
+                    match excluding(&single, SEPARATOR) {
+                        Some(single) => built.push(single),
+                        None => return,
+                    }
+                }
+                at_start = false;
+            },
+            Segment::AnyDirs | Segment::AnyVisibleDirs => {
+                built.push(Segment::AnyVisibleDirs);
+                at_start = true;
+            },
+            _ if !at_start => built.push(visible(segment)),
+            Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyPath | Segment::AnyVisibleChars => {
+                // matching nothing, the next char still isn't a `.`
+                if !starts_with_dot(rest) {
+                    expand(rest, built.clone(), alternatives);
+                }
+                built.push(Segment::Class(CharClass { negated: true, ranges: vec![('.', '.'), (SEPARATOR, SEPARATOR)] }));
+                built.push(visible(segment));
+                at_start = false;
+            },
+        }
+    }
+    alternatives.push(built);
+}
+
+/// The wildcard that can span path components as one that stops in front of hidden ones.
+fn visible(segment: &Segment) -> Segment {
+    match segment {
+        Segment::AnyChars | Segment::AnyPath => Segment::AnyVisibleChars,
+        Segment::AnyDirs => Segment::AnyVisibleDirs,
+        _ => segment.clone(),
+    }
+}
+
+/// A single char wildcard that doesn't match `ch` (a `.` or `/`), `None` if that leaves nothing.
+fn excluding(segment: &Segment, ch: char) -> Option<Segment> {
+    Some(match segment {
+        Segment::AnyChar if ch == SEPARATOR => Segment::AnyCharExceptSeparator,
+        Segment::AnyCharExceptSeparator if ch == SEPARATOR => segment.clone(),
+        Segment::AnyChar => Segment::Class(CharClass { negated: true, ranges: vec![(ch, ch)] }),
+        Segment::AnyCharExceptSeparator => Segment::Class(CharClass { negated: true, ranges: vec![(ch, ch), (SEPARATOR, SEPARATOR)] }),
+        Segment::Class(class) if class.negated => {
+            let mut ranges = class.ranges.clone();
+            ranges.push((ch, ch));
+            Segment::Class(CharClass { negated: true, ranges })
+        },
+        Segment::Class(class) => {
+            let mut ranges = Vec::new();
+            for &(first, last) in &class.ranges {
+                if first <= ch && ch <= last {
+                    // `.` and `/` are ASCII, so the chars either side of them are too
+                    if first < ch {
+                        ranges.push((first, char::from(ch as u8 - 1)));
+                    }
+                    if last > ch {
+                        ranges.push((char::from(ch as u8 + 1), last));
+                    }
+                } else {
+                    ranges.push((first, last));
+                }
+            }
+            if ranges.is_empty() {
+                return None;
+            }
+            Segment::Class(CharClass { negated: false, ranges })
+        },
+        _ => segment.clone(),
+    })
+}
+
+fn matches_separator(segment: &Segment) -> bool {
+    match segment {
+        Segment::AnyChar => true,
+        Segment::Class(class) => class.contains(SEPARATOR),
+        _ => false,
+    }
+}
+
+fn starts_with_dot(segments: &[Segment]) -> bool {
+    matches!(segments.first(), Some(Segment::Literal(s)) | Some(Segment::LiteralIgnoreCase(s)) if s.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Glob;
+
+    fn hidden(pattern: &str, literal_separator: bool, dfa: bool) -> Glob {
+        Glob::builder(pattern).literal_leading_dot(true).literal_separator(literal_separator).dfa(dfa).build().unwrap()
+    }
+
+    #[test]
+    fn wildcards_skip_hidden_components() {
+        for &dfa in &[false, true] {
+            for &separator in &[false, true] {
+                let all = hidden("**/*", separator, dfa);
+                assert!(all.is_match("src/lib.rs") && all.is_match("a/b/c"));
+                assert!(!all.is_match(".git") && !all.is_match(".git/config") && !all.is_match("a/b/.git/config"));
+                let below = hidden("src/**", separator, dfa);
+                assert!(below.is_match("src/a/b.rs") && !below.is_match("src/.cache/b") && !below.is_match("src/a/.b"));
+                let rs = hidden("*.rs", separator, dfa);
+                assert!(rs.is_match("lib.rs") && !rs.is_match(".rs") && !rs.is_match(".lib.rs"));
+                assert!(!hidden("?git", separator, dfa).is_match(".git") && !hidden("[.a]git", separator, dfa).is_match(".git"));
+                assert!(hidden("[.a]git", separator, dfa).is_match("agit"));
+                // a literal `.` matches hidden files
+                assert!(hidden(".*", separator, dfa).is_match(".git") && hidden("**/.git", separator, dfa).is_match(".git"));
+                assert!(hidden("**/.git/*", separator, dfa).is_match("a/.git/config"));
+                assert!(hidden("a*", separator, dfa).is_match("a.b"));
+            }
+        }
+        // without a literal separator `*` still matches `/`, just not a `.` right after it
+        for &dfa in &[false, true] {
+            let star = hidden("*", false, dfa);
+            assert!(star.is_match("a/b") && !star.is_match("a/.b"));
+            let any = hidden("a?*", false, dfa);
+            assert!(any.is_match("a/b") && any.is_match("a.b") && !any.is_match("a/.b"));
+        }
+        assert!(!hidden("*", true, false).is_match("a/b"));
+    }
+
+    #[test]
+    fn bytes() {
+        let glob = hidden("src/**/*.rs", true, false);
+        assert!(glob.is_match_bytes(b"src/a/lib.rs") && !glob.is_match_bytes(b"src/.a/lib.rs"));
+    }
+}
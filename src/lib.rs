@@ -16,82 +16,1069 @@
     along with this program.  If not, see <https://www.gnu.org/licenses/>.
 */
 
-#[derive(Debug,Clone)]
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use case::{fold_case, fold_char, CaseMode};
+use literal_map::LiteralMap;
+
+// lets `::globber` paths from the proc macros resolve in the crate's own tests
+extern crate self as globber;
+
+mod binary;
+mod borrowed;
+mod braces;
+mod budget;
+mod captures;
+mod case;
+mod classify;
+mod combine;
+mod compare;
+mod context;
+mod dfa;
+mod diagnostic;
+mod display;
+mod dos;
+mod error;
+mod examples;
+mod fnmatch;
+mod from_regex;
+mod glob;
+mod hostname;
+mod leading_dot;
+mod lines;
+mod lint;
+mod literal_map;
+mod match_options;
+pub mod mime;
+mod multipart_vec;
+mod pattern_builder;
+mod pattern_file;
+mod prefix;
+mod replace;
+mod route;
+mod rules;
+mod segments;
+mod set;
+mod shared;
+mod static_glob;
+mod stream;
+mod tagged;
+mod to_regex;
+mod version;
+#[cfg(feature = "bstr")]
+mod byte_strings;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "gitignore")]
+mod gitignore;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(any(test, feature = "reference"))]
+pub mod reference;
+#[cfg(feature = "macros")]
+pub mod macros;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "unicode-segmentation")]
+mod graphemes;
+#[cfg(feature = "unicode-normalization")]
+mod normalize;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(any(test, feature = "bench"))]
+pub mod bench_support;
+
+pub use binary::FromBytesError;
+pub use borrowed::GlobPatternRef;
+pub use budget::BudgetExceeded;
+pub use case::CaseFold;
+pub use captures::{CaptureParseError, Captures};
+pub use classify::PatternKind;
+pub use context::MatchContext;
+pub use dfa::Dfa;
+pub use diagnostic::Diagnostic;
+pub use error::GlobError;
+pub use fnmatch::Syntax;
+pub use glob::{Glob, GlobBuilder};
+pub use hostname::HostnameGlob;
+pub use lines::FilterLines;
+pub use lint::{lint, lint_with, Lint};
+pub use match_options::MatchOptions;
+pub use multipart_vec::MultipartVec;
+pub use pattern_builder::PatternBuilder;
+pub use pattern_file::PatternFileError;
+pub use route::{RouteGlob, RouteParams};
+pub use rules::{Action, Rule, RuleSet};
+pub use segments::{CharClass, Segment};
+pub use set::GlobSet;
+#[cfg(feature = "bstr")]
+pub use byte_strings::MatchingByteLines;
+#[cfg(feature = "gitignore")]
+pub use gitignore::{GitignoreError, GitignoreList};
+pub use shared::{SharedGlob, SharedGlobList};
+pub use static_glob::StaticGlob;
+pub use stream::StreamMatcher;
+pub use tagged::TaggedGlobList;
+pub use version::VersionGlob;
+
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GlobPattern {
     MatchAny,
-    Multipart(Vec<Multipart>),
+    Multipart(MultipartVec),
     MatchEnd(String),
     MatchStart(String),
     /// (Start,End)
     MatchBothEnds(String,String),
-    MatchFull(String)
+    MatchFull(String),
+    /// Patterns with `?` or `[...]`, which the other variants can't express.
+    Segmented(Vec<Segment>),
+    /// Expanded from `{a,b}` groups, matches if any of them does.
+    Alternatives(Vec<GlobPattern>),
 }
 
-#[derive(Debug,Clone)]
-pub struct GlobCaseSensitive(GlobPattern);
+impl GlobPattern {
+    /// The number of literals and wildcards in the pattern, summed over its alternatives.
+    /// Building and matching take time proportional to it, see [`GlobBuilder::max_segments`].
+    pub fn segment_count(&self) -> usize {
+        match self {
+            GlobPattern::MatchAny | GlobPattern::MatchFull(_) => 1,
+            GlobPattern::MatchEnd(_) | GlobPattern::MatchStart(_) => 2,
+            GlobPattern::MatchBothEnds(..) => 3,
+            GlobPattern::Multipart(multi) => multi
+                .iter()
+                .map(|part| match part {
+                    Multipart::ExactStart(_) | Multipart::AnyEnd => 1,
+                    // the `*` before the literal
+                    Multipart::AnyUntil(_) | Multipart::AnyUntilExactEnd(_) => 2,
+                })
+                .sum(),
+            GlobPattern::Segmented(segments) => segments.len(),
+            GlobPattern::Alternatives(alternatives) => alternatives.iter().map(GlobPattern::segment_count).sum(),
+        }
+    }
+
+    /// Length in bytes of the shortest value that can match.
+    pub fn min_len(&self) -> usize {
+        match self {
+            GlobPattern::MatchAny => 0,
+            GlobPattern::MatchEnd(s) | GlobPattern::MatchStart(s) | GlobPattern::MatchFull(s) => s.len(),
+            // start and end can't overlap, "a*a" needs at least "aa"
+            GlobPattern::MatchBothEnds(start, end) => start.len() + end.len(),
+            GlobPattern::Multipart(multi) => multi
+                .iter()
+                .map(|part| match part {
+                    Multipart::ExactStart(s) | Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => s.len(),
+                    Multipart::AnyEnd => 0,
+                })
+                .sum(),
+            GlobPattern::Segmented(segments) => segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Literal(s) => s.len(),
+                    // a char can fold to up to three
+                    Segment::LiteralIgnoreCase(s) => s.chars().count().div_ceil(3),
+                    Segment::AnyChar | Segment::AnyCharExceptSeparator | Segment::Class(_) => 1,
+                    Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyDirs | Segment::AnyPath |
+                    Segment::AnyVisibleChars | Segment::AnyVisibleDirs => 0,
+                })
+                .sum(),
+            GlobPattern::Alternatives(alternatives) => alternatives.iter().map(GlobPattern::min_len).min().unwrap_or(0),
+        }
+    }
+
+    /// Length in bytes of the longest value that can match, `None` if there's no limit.
+    pub fn max_len(&self) -> Option<usize> {
+        match self {
+            GlobPattern::MatchFull(s) => Some(s.len()),
+            GlobPattern::Segmented(segments) => segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Literal(s) => Some(s.len()),
+                    Segment::LiteralIgnoreCase(s) => Some(s.chars().count() * 4),
+                    Segment::AnyChar | Segment::AnyCharExceptSeparator | Segment::Class(_) => Some(4), // the longest UTF-8 char
+                    Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyDirs | Segment::AnyPath |
+                    Segment::AnyVisibleChars | Segment::AnyVisibleDirs => None,
+                })
+                .sum(),
+            GlobPattern::Alternatives(alternatives) => alternatives
+                .iter()
+                .map(GlobPattern::max_len)
+                .try_fold(0, |max, len| len.map(|len| max.max(len))),
+            _ => None,
+        }
+    }
+
+    /// Whether a value `len` bytes long could match at all, for skipping patterns without matching.
+    ///
+    /// For a pattern built by [`GlobIgnoreCase`] this is the length of the case folded value.
+    pub fn can_match_len(&self, len: usize) -> bool {
+        len >= self.min_len() && self.max_len().is_none_or(|max| len <= max)
+    }
+
+    /// The literal every matching value starts with, empty if there's none, e.g. `src/foo` for `src/foo*`.
+    /// A directory walker only has to look in `src/` then.
+    ///
+    /// For a pattern built ignoring case this is case folded, and a `(?i:...)` group ends it.
+    pub fn literal_prefix(&self) -> &str {
+        match self {
+            GlobPattern::MatchAny | GlobPattern::MatchEnd(_) => "",
+            GlobPattern::MatchFull(s) | GlobPattern::MatchStart(s) | GlobPattern::MatchBothEnds(s, _) => s,
+            GlobPattern::Multipart(multi) => match multi.first() {
+                Some(Multipart::ExactStart(start)) => start,
+                _ => "",
+            },
+            GlobPattern::Segmented(segments) => match segments.first() {
+                Some(Segment::Literal(start)) => start,
+                _ => "",
+            },
+            GlobPattern::Alternatives(alternatives) => common_affix(alternatives.iter().map(GlobPattern::literal_prefix), false),
+        }
+    }
+
+    /// The literal every matching value ends with, empty if there's none, e.g. `.rs` for `src/**/*.rs`.
+    ///
+    /// For a pattern built ignoring case this is case folded, and a `(?i:...)` group ends it.
+    pub fn literal_suffix(&self) -> &str {
+        match self {
+            GlobPattern::MatchAny | GlobPattern::MatchStart(_) => "",
+            GlobPattern::MatchFull(s) | GlobPattern::MatchEnd(s) | GlobPattern::MatchBothEnds(_, s) => s,
+            GlobPattern::Multipart(multi) => match multi.last() {
+                Some(Multipart::AnyUntilExactEnd(end)) => end,
+                _ => "",
+            },
+            GlobPattern::Segmented(segments) => match segments.last() {
+                Some(Segment::Literal(end)) => end,
+                _ => "",
+            },
+            GlobPattern::Alternatives(alternatives) => common_affix(alternatives.iter().map(GlobPattern::literal_suffix), true),
+        }
+    }
+}
+
+/// The longest start (or end with `suffix`) all of `literals` share, cut at a char boundary.
+fn common_affix<'a>(mut literals: impl Iterator<Item = &'a str>, suffix: bool) -> &'a str {
+    let mut common = literals.next().unwrap_or("");
+    for literal in literals {
+        let shared = if suffix {
+            common.chars().rev().zip(literal.chars().rev()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum()
+        } else {
+            common.chars().zip(literal.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum()
+        };
+        common = if suffix { &common[common.len() - shared..] } else { &common[..shared] };
+    }
+    common
+}
+
+/// A case sensitive [`Glob`].
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobCaseSensitive(Glob);
 impl GlobCaseSensitive {
-    pub fn build(pattern: &str) -> Result<GlobCaseSensitive, ()> {
-        build_glob_pattern(pattern).map(GlobCaseSensitive)
+    pub fn build(pattern: &str) -> Result<GlobCaseSensitive, GlobError> {
+        Glob::new(pattern).map(GlobCaseSensitive)
+    }
+
+    pub fn as_glob(&self) -> &Glob {
+        &self.0
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
     }
 
     pub fn is_match(&self, value: &str) -> bool {
-        glob_match_prebuilt(&self.0, value)
+        self.0.is_match(value)
+    }
+
+    /// Same as [`GlobCaseSensitive::is_match`], which doesn't allocate anyway,
+    /// for code that matches both kinds of patterns with a [`MatchContext`].
+    pub fn is_match_with(&self, ctx: &mut MatchContext, value: &str) -> bool {
+        self.0.is_match_with(ctx, value)
+    }
+
+    /// Same as [`GlobCaseSensitive::is_match`], the whole value has to match.
+    pub fn match_full(&self, value: &str) -> bool {
+        self.0.match_full(value)
+    }
+
+    /// Whether the pattern matches the start of `value`, as if it ended with `*`.
+    pub fn match_prefix(&self, value: &str) -> bool {
+        self.0.match_prefix(value)
+    }
+
+    /// Whether the pattern matches the end of `value`, as if it started with `*`.
+    pub fn match_suffix(&self, value: &str) -> bool {
+        self.0.match_suffix(value)
+    }
+
+    /// Whether the pattern matches anywhere within `value`, as if it started and ended with `*`.
+    pub fn match_within(&self, value: &str) -> bool {
+        self.0.match_within(value)
+    }
+
+    /// Matches `chunks` as if they were concatenated into one string, without concatenating them.
+    pub fn is_match_chunks(&self, chunks: &[&str]) -> bool {
+        self.0.is_match_chunks(chunks)
+    }
+
+    /// Matches a byte string, which doesn't have to be valid UTF-8, see [`Glob::is_match_bytes`].
+    pub fn is_match_bytes(&self, value: &[u8]) -> bool {
+        self.0.is_match_bytes(value)
+    }
+
+    /// Matches a path without converting it to a `String` first, see [`Glob::is_match_path`].
+    pub fn is_match_path(&self, path: &Path) -> bool {
+        self.0.is_match_path(path)
+    }
+
+    /// Byte ranges of the literal parts of the pattern within `value`, or `None` if it doesn't match.
+    pub fn match_spans(&self, value: &str) -> Option<Vec<Range<usize>>> {
+        self.0.match_spans(value)
+    }
+
+    /// The byte range of `value` the match covers, see [`Glob::find`].
+    pub fn find(&self, value: &str) -> Option<Range<usize>> {
+        self.0.find(value)
+    }
+
+    /// The byte range of every match within `haystack`, see [`Glob::find_iter`].
+    pub fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.0.find_iter(haystack)
+    }
+
+    /// `value` with the first match replaced by `replacement`, which can refer to wildcards, see [`Glob::replace`].
+    pub fn replace<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.0.replace(value, replacement)
+    }
+
+    /// `value` with every match replaced by `replacement`, see [`Glob::replace_all`].
+    pub fn replace_all<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.0.replace_all(value, replacement)
+    }
+
+    /// The text matched by each wildcard, or `None` if `value` doesn't match.
+    pub fn captures<'a>(&self, value: &'a str) -> Option<Captures<'a>> {
+        self.0.captures(value)
+    }
+
+    /// Same as [`GlobCaseSensitive::captures`], but keeps the captures in `ctx` instead of allocating.
+    pub fn captures_with<'a>(&self, ctx: &'a mut MatchContext, value: &'a str) -> Option<Captures<'a>> {
+        self.0.captures_with(ctx, value)
+    }
+
+    /// The text matched by each wildcard parsed as `T`, or `None` if `value` doesn't match.
+    pub fn captures_as<T: FromStr>(&self, value: &str) -> Option<Result<Vec<T>, CaptureParseError<T::Err>>> {
+        self.0.captures_as(value)
     }
 }
-#[derive(Debug,Clone)]
-pub struct GlobIgnoreCase(GlobPattern);
+
+/// A case insensitive [`Glob`].
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobIgnoreCase(Glob);
 impl GlobIgnoreCase {
-    pub fn build(pattern: &str) -> Result<GlobIgnoreCase, ()> {
-        build_glob_pattern(&pattern.to_uppercase()).map(GlobIgnoreCase)
+    pub fn build(pattern: &str) -> Result<GlobIgnoreCase, GlobError> {
+        Glob::builder(pattern).case_insensitive(true).build().map(GlobIgnoreCase)
+    }
+
+    /// Same as [`GlobIgnoreCase::build`], pairing letters the way `fold` says, e.g. [`CaseFold::Turkic`]
+    /// for Turkish file names where `DOSYA` is `dosya` but `I` isn't `i`.
+    pub fn build_with_fold(pattern: &str, fold: CaseFold) -> Result<GlobIgnoreCase, GlobError> {
+        Glob::builder(pattern).case_insensitive(true).case_fold(fold).build().map(GlobIgnoreCase)
+    }
+
+    /// Matches `folded`, which is `value` case folded, unless the pattern folds some other way
+    /// (ASCII letters only or Turkic) and folds `value` itself.
+    fn is_match_folded(&self, value: &str, folded: &str) -> bool {
+        match self.0.case {
+            CaseMode::Insensitive => self.0.matches_folded(folded),
+            _ => self.0.is_match(value),
+        }
+    }
+
+    /// Same as [`GlobIgnoreCase::is_match_folded`], folding `value` into `folded` the first time a pattern
+    /// needs it folded that way.
+    fn is_match_lazily(&self, value: &str, folded: &mut Option<String>) -> bool {
+        match self.0.case {
+            CaseMode::Insensitive => self.0.matches_folded(folded.get_or_insert_with(|| fold_case(value))),
+            _ => self.0.is_match(value),
+        }
+    }
+
+    pub fn as_glob(&self) -> &Glob {
+        &self.0
+    }
+
+    /// The pattern as it was written, the compiled pattern is case folded but this keeps the original casing.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    /// Same as [`GlobIgnoreCase::is_match`], but case folds `value` into `ctx` instead of allocating.
+    pub fn is_match_with(&self, ctx: &mut MatchContext, value: &str) -> bool {
+        self.0.is_match_with(ctx, value)
+    }
+
+    /// Same as [`GlobIgnoreCase::is_match`], the whole value has to match.
+    pub fn match_full(&self, value: &str) -> bool {
+        self.0.match_full(value)
+    }
+
+    /// Whether the pattern matches the start of `value`, as if it ended with `*`.
+    pub fn match_prefix(&self, value: &str) -> bool {
+        self.0.match_prefix(value)
+    }
+
+    /// Whether the pattern matches the end of `value`, as if it started with `*`.
+    pub fn match_suffix(&self, value: &str) -> bool {
+        self.0.match_suffix(value)
+    }
+
+    /// Whether the pattern matches anywhere within `value`, as if it started and ended with `*`.
+    pub fn match_within(&self, value: &str) -> bool {
+        self.0.match_within(value)
+    }
+
+    /// Matches `chunks` as if they were concatenated into one string, without concatenating them.
+    pub fn is_match_chunks(&self, chunks: &[&str]) -> bool {
+        self.0.is_match_chunks(chunks)
+    }
+
+    /// Matches a byte string, which doesn't have to be valid UTF-8, see [`Glob::is_match_bytes`].
+    pub fn is_match_bytes(&self, value: &[u8]) -> bool {
+        self.0.is_match_bytes(value)
+    }
+
+    /// Matches a path without converting it to a `String` first, see [`Glob::is_match_path`].
+    pub fn is_match_path(&self, path: &Path) -> bool {
+        self.0.is_match_path(path)
+    }
+
+    /// Byte ranges of the literal parts of the pattern within `value`, or `None` if it doesn't match.
+    ///
+    /// The ranges refer to `value` itself, not to its case folded form.
+    pub fn match_spans(&self, value: &str) -> Option<Vec<Range<usize>>> {
+        self.0.match_spans(value)
+    }
+
+    /// The byte range of `value` the match covers, see [`Glob::find`].
+    pub fn find(&self, value: &str) -> Option<Range<usize>> {
+        self.0.find(value)
+    }
+
+    /// The byte range of every match within `haystack`, see [`Glob::find_iter`].
+    pub fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.0.find_iter(haystack)
+    }
+
+    /// `value` with the first match replaced by `replacement`, which can refer to wildcards, see [`Glob::replace`].
+    pub fn replace<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.0.replace(value, replacement)
+    }
+
+    /// `value` with every match replaced by `replacement`, see [`Glob::replace_all`].
+    pub fn replace_all<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.0.replace_all(value, replacement)
+    }
+
+    /// The text matched by each wildcard, or `None` if `value` doesn't match.
+    ///
+    /// The captures refer to `value` itself, not to its case folded form.
+    pub fn captures<'a>(&self, value: &'a str) -> Option<Captures<'a>> {
+        self.0.captures(value)
+    }
+
+    /// Same as [`GlobIgnoreCase::captures`], but keeps the case folded value and the captures in `ctx` instead of allocating.
+    pub fn captures_with<'a>(&self, ctx: &'a mut MatchContext, value: &'a str) -> Option<Captures<'a>> {
+        self.0.captures_with(ctx, value)
+    }
+
+    /// The text matched by each wildcard parsed as `T`, or `None` if `value` doesn't match.
+    pub fn captures_as<T: FromStr>(&self, value: &str) -> Option<Result<Vec<T>, CaptureParseError<T::Err>>> {
+        self.0.captures_as(value)
+    }
+}
+
+impl From<GlobCaseSensitive> for Glob {
+    fn from(glob: GlobCaseSensitive) -> Glob {
+        glob.0
+    }
+}
+
+impl From<GlobIgnoreCase> for Glob {
+    fn from(glob: GlobIgnoreCase) -> Glob {
+        glob.0
+    }
+}
+
+impl FromStr for GlobCaseSensitive {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<GlobCaseSensitive, GlobError> {
+        GlobCaseSensitive::build(pattern)
+    }
+}
+
+impl TryFrom<&str> for GlobCaseSensitive {
+    type Error = GlobError;
+
+    fn try_from(pattern: &str) -> Result<GlobCaseSensitive, GlobError> {
+        GlobCaseSensitive::build(pattern)
+    }
+}
+
+/// The pattern as it was written.
+impl fmt::Display for GlobCaseSensitive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for GlobIgnoreCase {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<GlobIgnoreCase, GlobError> {
+        GlobIgnoreCase::build(pattern)
+    }
+}
+
+impl TryFrom<&str> for GlobIgnoreCase {
+    type Error = GlobError;
+
+    fn try_from(pattern: &str) -> Result<GlobIgnoreCase, GlobError> {
+        GlobIgnoreCase::build(pattern)
+    }
+}
+
+/// The pattern as it was written, not case folded.
+impl fmt::Display for GlobIgnoreCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Identifies a pattern in a [`GlobList`].
+///
+/// Ids are handed out in insertion order and never reused within the same list,
+/// so they can be logged and compared across runs built from the same patterns.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryId(usize);
+
+impl EntryId {
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+/// A pattern in a [`GlobList`], borrowed from whichever bucket it's stored in.
+#[derive(Debug,Clone,Copy)]
+pub enum GlobListEntry<'a> {
+    CaseSensitive(&'a GlobCaseSensitive),
+    IgnoreCase(&'a GlobIgnoreCase),
+}
+
+impl<'a> GlobListEntry<'a> {
+    pub fn is_case_sensitive(&self) -> bool {
+        matches!(self, GlobListEntry::CaseSensitive(_))
+    }
+
+    pub fn as_glob(&self) -> &'a Glob {
+        match self {
+            GlobListEntry::CaseSensitive(p) => p.as_glob(),
+            GlobListEntry::IgnoreCase(p) => p.as_glob(),
+        }
+    }
+
+    /// The pattern as it was written, in its original casing even if it ignores case.
+    pub fn as_str(&self) -> &'a str {
+        self.as_glob().as_str()
     }
 
     pub fn is_match(&self, value: &str) -> bool {
-        glob_match_prebuilt(&self.0, &value.to_uppercase())
+        match self {
+            GlobListEntry::CaseSensitive(p) => p.is_match(value),
+            GlobListEntry::IgnoreCase(p) => p.is_match(value),
+        }
+    }
+}
+
+/// A pattern in a [`GlobList`] that matched a value, see [`GlobList::first_match`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct GlobMatch<'a> {
+    pub id: EntryId,
+    /// The pattern as it was written, without the `!` of a negated pattern.
+    pub pattern: &'a str,
+    pub case_sensitive: bool,
+    pub negated: bool,
+}
+
+/// What a [`GlobList`] with negated patterns makes of a value, see [`GlobList::matched`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum MatchOutcome<'a> {
+    /// The last pattern matching the value is a normal pattern.
+    Included(GlobMatch<'a>),
+    /// The last pattern matching the value is a negated (`!pattern`) one.
+    Excluded(GlobMatch<'a>),
+    /// No pattern matched the value.
+    Unmatched,
+}
+
+impl MatchOutcome<'_> {
+    pub fn is_included(&self) -> bool {
+        matches!(self, MatchOutcome::Included(_))
+    }
+}
+
+#[derive(Debug,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Slot {
+    CaseSensitive(usize),
+    IgnoreCase(usize),
+}
+
+/// How often a pattern in a [`GlobList`] was checked and how often it matched.
+/// Atomic so matching can keep taking `&self`.
+#[derive(Debug,Default)]
+struct HitCounter {
+    checked: AtomicUsize,
+    hits: AtomicUsize,
+}
+
+impl HitCounter {
+    fn record(&self, hit: bool) {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Observed hit rate, with `estimate` weighted in as if it was one previous check.
+    fn rate(&self, estimate: f64) -> f64 {
+        let checked = self.checked.load(Ordering::Relaxed) as f64;
+        let hits = self.hits.load(Ordering::Relaxed) as f64;
+        (hits + estimate) / (checked + 1.0)
+    }
+}
+
+impl Clone for HitCounter {
+    fn clone(&self) -> HitCounter {
+        HitCounter {
+            checked: AtomicUsize::new(self.checked.load(Ordering::Relaxed)),
+            hits: AtomicUsize::new(self.hits.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Records `hit` if hits are being tracked and passes it on.
+fn record_hit(counters: &[HitCounter], index: usize, hit: bool) -> bool {
+    if let Some(counter) = counters.get(index) {
+        counter.record(hit);
+    }
+    hit
+}
+
+/// A rough guess of how likely `pattern` is to match an arbitrary value,
+/// the more literal chars the less likely.
+fn estimated_match_rate(pattern: &GlobPattern) -> f64 {
+    match pattern {
+        GlobPattern::MatchAny => 1.0,
+        GlobPattern::MatchFull(_) => 0.0, // only ever matches one value
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().map(estimated_match_rate).sum::<f64>().min(1.0),
+        _ => 1.0 / (1.0 + literal_len(pattern) as f64),
     }
 }
 
+/// Number of chars in the literal parts of `pattern`.
+fn literal_len(pattern: &GlobPattern) -> usize {
+    match pattern {
+        GlobPattern::MatchAny => 0,
+        GlobPattern::MatchEnd(s) | GlobPattern::MatchStart(s) | GlobPattern::MatchFull(s) => s.chars().count(),
+        GlobPattern::MatchBothEnds(start, end) => start.chars().count() + end.chars().count(),
+        GlobPattern::Multipart(multi) => multi
+            .iter()
+            .map(|part| match part {
+                Multipart::ExactStart(s) | Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => s.chars().count(),
+                Multipart::AnyEnd => 0,
+            })
+            .sum(),
+        GlobPattern::Segmented(segments) => segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(s) | Segment::LiteralIgnoreCase(s) => s.chars().count(),
+                _ => 0,
+            })
+            .sum(),
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().map(literal_len).min().unwrap_or(0),
+    }
+}
+
+/// Keeps the patterns (and hit counters, if any) where `keep` is true, returning where each old index moved to.
+fn retain_slots<T>(patterns: &mut Vec<T>, counters: &mut Vec<HitCounter>, keep: &[bool]) -> Vec<usize> {
+    let mut moved_to = Vec::with_capacity(keep.len());
+    let mut kept = 0;
+    for &keep in keep {
+        moved_to.push(kept);
+        kept += keep as usize;
+    }
+    let mut keep_iter = keep.iter();
+    patterns.retain(|_| *keep_iter.next().unwrap());
+    if !counters.is_empty() {
+        let mut keep_iter = keep.iter();
+        counters.retain(|_| *keep_iter.next().unwrap());
+    }
+    moved_to
+}
+
+/// Sorts `patterns` (and their counters) by descending match rate, returns the new index of each old index.
+fn sort_by_match_rate<T>(patterns: &mut Vec<T>, counters: &mut Vec<HitCounter>, pattern: impl Fn(&T) -> &GlobPattern) -> Vec<usize> {
+    let rates : Vec<f64> = patterns
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let estimate = estimated_match_rate(pattern(p));
+            counters.get(i).map_or(estimate, |c| c.rate(estimate))
+        })
+        .collect();
+    let mut order : Vec<usize> = (0..patterns.len()).collect();
+    order.sort_by(|a, b| rates[*b].total_cmp(&rates[*a])); // stable, equally likely patterns keep their order
+
+    let mut new_index = vec![0; order.len()];
+    for (new, old) in order.iter().enumerate() {
+        new_index[*old] = new;
+    }
+    let mut taken : Vec<Option<T>> = patterns.drain(..).map(Some).collect();
+    patterns.extend(order.iter().map(|old| taken[*old].take().unwrap()));
+    if !counters.is_empty() {
+        let mut taken : Vec<Option<HitCounter>> = counters.drain(..).map(Some).collect();
+        counters.extend(order.iter().map(|old| taken[*old].take().unwrap()));
+    }
+    new_index
+}
+
+#[derive(Debug,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Entry {
+    id: EntryId,
+    slot: Slot,
+    negated: bool,
+}
+
 #[derive(Debug,Clone,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlobList {
     ignore_case_patterns: Vec<GlobIgnoreCase>,
     case_sensitive_patterns: Vec<GlobCaseSensitive>,
+    /// insertion order across both buckets, everything user visible follows this
+    entries: Vec<Entry>,
+    next_id: usize,
+    /// with any negated patterns the buckets can't be matched on their own, see `matched`
+    negated_count: usize,
+    /// one per pattern in the matching bucket while hit tracking is enabled, otherwise empty,
+    /// not serialized since the counts only make sense for the process that recorded them
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ignore_case_hits: Vec<HitCounter>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    case_sensitive_hits: Vec<HitCounter>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    track_hits: bool,
+    /// the patterns indexed by their literal parts, built by `optimize` and dropped on any change
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: Option<GlobSet>,
+    /// the literal patterns hashed, built on the first `any_match` needing it and dropped on any change
+    #[cfg_attr(feature = "serde", serde(skip))]
+    literals: OnceLock<LiteralMap>,
 }
 
 impl GlobList {
     pub fn new() -> GlobList {
-        GlobList {
-            ignore_case_patterns: Vec::new(),
-            case_sensitive_patterns: Vec::new(),
+        GlobList::default()
+    }
+
+    /// Patterns starting with `!` are negated, see [`GlobList::add_negated`].
+    pub fn build(patterns: &[String]) -> Result<GlobList, GlobError> {
+        GlobList::build_with_options(patterns, MatchOptions::new())
+    }
+
+    /// Patterns starting with `!` are negated, see [`GlobList::add_negated`].
+    pub fn build_ignore_case(patterns: &[String]) -> Result<GlobList, GlobError> {
+        GlobList::build_with_options(patterns, MatchOptions { case_sensitive: false, ..MatchOptions::new() })
+    }
+
+    /// Patterns starting with `!` are negated, see [`GlobList::add_negated`], and each is built with `options`.
+    pub fn build_with_options(patterns: &[String], options: MatchOptions) -> Result<GlobList, GlobError> {
+        let mut list = GlobList::new();
+        for pattern in patterns {
+            list.add_pattern(pattern, options)?;
+        }
+        Ok(list)
+    }
+
+    /// Builds and adds `pattern`, negated if it starts with `!`.
+    pub(crate) fn add_pattern(&mut self, pattern: &str, options: MatchOptions) -> Result<EntryId, GlobError> {
+        Ok(match pattern.strip_prefix('!') {
+            Some(negated) => self.add_negated(Glob::builder(negated).match_options(options).build()
+                .map_err(|e| e.at(e.position().map_or(0, |p| p + 1)))?),
+            None => self.add(Glob::builder(pattern).match_options(options).build()?),
+        })
+    }
+
+    pub fn add_ignore_case(&mut self, pattern: GlobIgnoreCase) -> EntryId {
+        self.ignore_case_patterns.push(pattern);
+        if self.track_hits {
+            self.ignore_case_hits.push(HitCounter::default());
+        }
+        self.push_entry(Slot::IgnoreCase(self.ignore_case_patterns.len() - 1), false)
+    }
+
+    pub fn add_case_sensitive(&mut self, pattern: GlobCaseSensitive) -> EntryId {
+        self.case_sensitive_patterns.push(pattern);
+        if self.track_hits {
+            self.case_sensitive_hits.push(HitCounter::default());
+        }
+        self.push_entry(Slot::CaseSensitive(self.case_sensitive_patterns.len() - 1), false)
+    }
+
+    /// Adds `glob` to the bucket matching its case sensitivity.
+    pub fn add(&mut self, glob: Glob) -> EntryId {
+        if glob.is_case_insensitive() {
+            self.add_ignore_case(GlobIgnoreCase(glob))
+        } else {
+            self.add_case_sensitive(GlobCaseSensitive(glob))
+        }
+    }
+
+    /// Adds a pattern excluding what it matches, like `!target/*` in a gitignore file.
+    ///
+    /// Once a list has negated patterns the last pattern in insertion order matching a value decides,
+    /// so `["*.rs", "!target/*"]` matches Rust files except those under `target`, see [`GlobList::matched`].
+    /// `any_match` is then whether a value is included, and `all_match` whether every normal pattern
+    /// and no negated pattern matches it.
+    pub fn add_negated(&mut self, glob: Glob) -> EntryId {
+        let id = self.add(glob);
+        self.entries.last_mut().unwrap().negated = true;
+        self.negated_count += 1;
+        id
+    }
+
+    /// Records how often each pattern is checked and matches in `any_match`/`all_match`,
+    /// which [`GlobList::reorder_by_selectivity`] then uses instead of only guessing.
+    /// Disabling it throws away what was recorded so far.
+    pub fn set_hit_tracking(&mut self, enabled: bool) {
+        self.track_hits = enabled;
+        self.ignore_case_hits.clear();
+        self.case_sensitive_hits.clear();
+        if enabled {
+            self.ignore_case_hits.resize_with(self.ignore_case_patterns.len(), HitCounter::default);
+            self.case_sensitive_hits.resize_with(self.case_sensitive_patterns.len(), HitCounter::default);
+        }
+    }
+
+    /// Reorders the patterns internally so the ones most likely to match are checked first,
+    /// making `any_match` (and `all_match`, which checks them the other way around) stop earlier on average.
+    ///
+    /// How likely a pattern is to match is estimated from the length of its literal parts,
+    /// and from its hit rate when hit tracking is enabled.
+    /// Only the evaluation order changes, iteration and ids still follow insertion order.
+    pub fn reorder_by_selectivity(&mut self) {
+        let ignore_case = sort_by_match_rate(&mut self.ignore_case_patterns, &mut self.ignore_case_hits, |p| &p.0.pattern);
+        let case_sensitive = sort_by_match_rate(&mut self.case_sensitive_patterns, &mut self.case_sensitive_hits, |p| &p.0.pattern);
+        for Entry { slot, .. } in &mut self.entries {
+            *slot = match *slot {
+                Slot::IgnoreCase(i) => Slot::IgnoreCase(ignore_case[i]),
+                Slot::CaseSensitive(i) => Slot::CaseSensitive(case_sensitive[i]),
+            };
+        }
+        self.literals = OnceLock::new();
+    }
+
+    /// Removes patterns equal to a later pattern that is negated the same way, returning their ids.
+    ///
+    /// Only the last of them can decide what the list makes of a value, see [`GlobList::matched`],
+    /// so that's the one kept. Patterns are equal when they're compiled the same, see [`Glob`].
+    pub fn dedup(&mut self) -> Vec<EntryId> {
+        let mut seen = HashSet::new();
+        let mut duplicate = vec![false; self.entries.len()];
+        for (i, e) in self.entries.iter().enumerate().rev() {
+            duplicate[i] = !seen.insert((self.entry(e.slot).as_glob(), e.negated));
         }
+        self.remove_entries(&duplicate)
     }
 
-    pub fn build(patterns: &[String]) -> Result<GlobList, ()> {
-        let patterns : Result<Vec<GlobCaseSensitive>,()> = patterns
+    /// Removes every pattern written as `pattern`, a negated one if it starts with `!` like in
+    /// [`GlobList::build`], returning their ids.
+    pub fn remove(&mut self, pattern: &str) -> Vec<EntryId> {
+        let (pattern, negated) = match pattern.strip_prefix('!') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+        let remove : Vec<bool> = self.entries
             .iter()
-            .map(|p| GlobCaseSensitive::build(p))
+            .map(|e| e.negated == negated && self.entry(e.slot).as_glob().as_str() == pattern)
             .collect();
-        patterns.map(|ps| GlobList {
-            case_sensitive_patterns: ps,
-            ignore_case_patterns: Vec::new(),
-        })
+        self.remove_entries(&remove)
+    }
+
+    /// Keeps only the patterns `keep` returns true for, called in insertion order.
+    /// Returns the ids of the removed ones.
+    pub fn retain(&mut self, mut keep: impl FnMut(EntryId, GlobListEntry<'_>) -> bool) -> Vec<EntryId> {
+        let remove : Vec<bool> = self.entries.iter().map(|e| !keep(e.id, self.entry(e.slot))).collect();
+        self.remove_entries(&remove)
+    }
+
+    /// Removes every pattern, ids handed out afterwards still don't reuse old ones.
+    pub fn clear(&mut self) {
+        self.ignore_case_patterns.clear();
+        self.case_sensitive_patterns.clear();
+        self.entries.clear();
+        self.index = None;
+        self.literals = OnceLock::new();
+        self.negated_count = 0;
+        self.ignore_case_hits.clear();
+        self.case_sensitive_hits.clear();
+    }
+
+    /// Removes the entries `remove` is true for, one per entry in insertion order, returning their ids.
+    fn remove_entries(&mut self, remove: &[bool]) -> Vec<EntryId> {
+        self.index = None;
+        self.literals = OnceLock::new();
+        let mut removed = Vec::new();
+        let mut keep_ignore_case = vec![true; self.ignore_case_patterns.len()];
+        let mut keep_case_sensitive = vec![true; self.case_sensitive_patterns.len()];
+        for (e, _) in self.entries.iter().zip(remove).filter(|(_, remove)| **remove) {
+            removed.push(e.id);
+            match e.slot {
+                Slot::IgnoreCase(i) => keep_ignore_case[i] = false,
+                Slot::CaseSensitive(i) => keep_case_sensitive[i] = false,
+            }
+        }
+        if removed.is_empty() {
+            return removed;
+        }
+
+        let ignore_case = retain_slots(&mut self.ignore_case_patterns, &mut self.ignore_case_hits, &keep_ignore_case);
+        let case_sensitive = retain_slots(&mut self.case_sensitive_patterns, &mut self.case_sensitive_hits, &keep_case_sensitive);
+        self.entries.retain(|e| removed.binary_search(&e.id).is_err());
+        for e in &mut self.entries {
+            e.slot = match e.slot {
+                Slot::IgnoreCase(i) => Slot::IgnoreCase(ignore_case[i]),
+                Slot::CaseSensitive(i) => Slot::CaseSensitive(case_sensitive[i]),
+            };
+        }
+        self.negated_count = self.entries.iter().filter(|e| e.negated).count();
+        removed
     }
 
-    pub fn build_ignore_case(patterns: &[String]) -> Result<GlobList, ()> {
-        let patterns : Result<Vec<GlobIgnoreCase>,()> = patterns
+    /// Removes duplicate patterns like [`GlobList::dedup`] and, without negated patterns, patterns that only
+    /// match values a broader pattern of the same case sensitivity matches too, like `src/*.rs` next to `*.rs`.
+    /// Then indexes the rest by their literal starts and ends like a [`GlobSet`], so `any_match` only
+    /// checks the patterns that could match a value. Returns the ids of the removed patterns.
+    ///
+    /// What `any_match` and `matched` accept stays the same, `all_match`, `matches` and `count_matches`
+    /// only see the patterns that are left. Adding or removing patterns drops the index again.
+    pub fn optimize(&mut self) -> Vec<EntryId> {
+        let mut removed = self.dedup();
+        if self.negated_count == 0 {
+            removed.extend(self.remove_subsumed());
+            removed.sort_unstable();
+        }
+        let globs = self.entries.iter().map(|e| self.entry(e.slot).as_glob().clone()).collect();
+        self.index = Some(GlobSet::new(globs));
+        removed
+    }
+
+    /// Removes patterns matching a subset of what another pattern with the same case mode matches,
+    /// the earlier one of patterns matching the same values is kept.
+    fn remove_subsumed(&mut self) -> Vec<EntryId> {
+        // patterns too large to compile are left alone
+        let dfas: Vec<Option<Dfa>> = self.entries
             .iter()
-            .map(|p| GlobIgnoreCase::build(p))
+            .map(|e| self.entry(e.slot).as_glob().pattern.compile_dfa().ok())
             .collect();
-        patterns.map(|ps| GlobList {
-            case_sensitive_patterns: Vec::new(),
-            ignore_case_patterns: ps,
-        })
+        let pattern = |i: usize| &self.entry(self.entries[i].slot).as_glob().pattern;
+        let case = |i: usize| self.entry(self.entries[i].slot).as_glob().case;
+        // a pattern can only be a subset of another matching its example, which rules out
+        // most pairs without comparing their DFAs
+        let examples: Vec<String> = (0..self.entries.len()).map(|i| pattern(i).example()).collect();
+        let mut remove = vec![false; self.entries.len()];
+        for (i, a) in dfas.iter().enumerate() {
+            let a = match a {
+                Some(a) => a,
+                None => continue,
+            };
+            remove[i] = dfas.iter().enumerate().any(|(j, b)| match b {
+                Some(b) if j != i && !remove[j] && case(i) == case(j) && glob_match_prebuilt(pattern(j), &examples[i]) =>
+                    compare::is_subset(a, b) && (j < i || !compare::is_subset(b, a)),
+                _ => false,
+            });
+        }
+        self.remove_entries(&remove)
     }
 
-    pub fn add_ignore_case(&mut self, pattern: GlobIgnoreCase) {
-        self.ignore_case_patterns.push(pattern);
+    /// The index built by `optimize`, unless hits are tracked per pattern.
+    fn usable_index(&self) -> Option<&GlobSet> {
+        if self.track_hits {
+            None
+        } else {
+            self.index.as_ref()
+        }
     }
 
-    pub fn add_case_sensitive(&mut self, pattern: GlobCaseSensitive) {
-        self.case_sensitive_patterns.push(pattern);
+    fn push_entry(&mut self, slot: Slot, negated: bool) -> EntryId {
+        self.index = None;
+        self.literals = OnceLock::new();
+        let id = EntryId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry { id, slot, negated });
+        if negated {
+            self.negated_count += 1;
+        }
+        id
+    }
+
+    fn entry(&self, slot: Slot) -> GlobListEntry<'_> {
+        match slot {
+            Slot::CaseSensitive(i) => GlobListEntry::CaseSensitive(&self.case_sensitive_patterns[i]),
+            Slot::IgnoreCase(i) => GlobListEntry::IgnoreCase(&self.ignore_case_patterns[i]),
+        }
+    }
+
+    /// Iterates the patterns in insertion order, regardless of case sensitivity.
+    pub fn iter(&self) -> impl Iterator<Item = (EntryId, GlobListEntry<'_>)> + '_ {
+        self.entries.iter().map(move |e| (e.id, self.entry(e.slot)))
+    }
+
+    pub fn get(&self, id: EntryId) -> Option<GlobListEntry<'_>> {
+        self.entries
+            .iter()
+            .find(|e| e.id == id)
+            .map(|e| self.entry(e.slot))
+    }
+
+    /// Whether the pattern with `id` is negated, see [`GlobList::add_negated`].
+    pub fn is_negated(&self, id: EntryId) -> bool {
+        self.entries.iter().any(|e| e.id == id && e.negated)
+    }
+
+    /// Number of patterns, negated ones included.
+    pub fn len(&self) -> usize {
+        self.entries.len()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -99,29 +1086,126 @@ impl GlobList {
             self.case_sensitive_patterns.is_empty()
     }
 
+    /// The first pattern in insertion order matching `value`, e.g. to tell which rule excluded a path.
+    pub fn first_match(&self, value: &str) -> Option<GlobMatch<'_>> {
+        self.matching(value).next()
+    }
+
+    /// Every pattern matching `value`, in insertion order.
+    pub fn matches(&self, value: &str) -> Vec<GlobMatch<'_>> {
+        self.matching(value).collect()
+    }
+
+    fn matching<'a, 'v>(&'a self, value: &'v str) -> impl Iterator<Item = GlobMatch<'a>> + use<'a, 'v> {
+        // only fold once, and only if it gets to an ignore case pattern
+        let mut folded = None;
+        self.entries.iter().filter_map(move |e| {
+            if self.slot_is_match_lazily(e.slot, value, &mut folded) {
+                Some(self.glob_match(e))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// What the first pattern in insertion order matching `value` says about it, for rule lists where
+    /// the earliest rule takes precedence like a firewall or ACL, e.g. `["!admin/*", "*"]` allows everything
+    /// but `admin`. Case sensitive and ignore case patterns are checked interleaved as they were added,
+    /// stopping at the first that matches. [`GlobList::matched`] is the gitignore way around instead.
+    pub fn first_match_ordered(&self, value: &str) -> MatchOutcome<'_> {
+        match self.first_match(value) {
+            Some(m) if m.negated => MatchOutcome::Excluded(m),
+            Some(m) => MatchOutcome::Included(m),
+            None => MatchOutcome::Unmatched,
+        }
+    }
+
+    /// What the last pattern in insertion order matching `value` says about it,
+    /// which with negated patterns is whether the value is included or excluded.
+    pub fn matched(&self, value: &str) -> MatchOutcome<'_> {
+        let mut folded = None;
+        match self.last_matching(|slot| self.slot_is_match_lazily(slot, value, &mut folded)) {
+            Some(e) if e.negated => MatchOutcome::Excluded(self.glob_match(e)),
+            Some(e) => MatchOutcome::Included(self.glob_match(e)),
+            None => MatchOutcome::Unmatched,
+        }
+    }
+
+    fn glob_match(&self, entry: &Entry) -> GlobMatch<'_> {
+        let glob = self.entry(entry.slot).as_glob();
+        GlobMatch {
+            id: entry.id,
+            pattern: glob.as_str(),
+            case_sensitive: !glob.is_case_insensitive(),
+            negated: entry.negated,
+        }
+    }
+
+    /// Whether the pattern in `slot` matches, `folded` is `value` case folded for ignore case patterns.
+    fn slot_is_match(&self, slot: Slot, value: &str, folded: &str) -> bool {
+        match slot {
+            Slot::CaseSensitive(i) => self.case_sensitive_patterns[i].0.matches_folded(value),
+            Slot::IgnoreCase(i) => self.ignore_case_patterns[i].is_match_folded(value, folded),
+        }
+    }
+
+    /// Same as [`GlobList::slot_is_match`], folding `value` into `folded` the first time an ignore case pattern
+    /// needs it.
+    fn slot_is_match_lazily(&self, slot: Slot, value: &str, folded: &mut Option<String>) -> bool {
+        match slot {
+            Slot::CaseSensitive(i) => self.case_sensitive_patterns[i].0.matches_folded(value),
+            Slot::IgnoreCase(i) => self.ignore_case_patterns[i].is_match_lazily(value, folded),
+        }
+    }
+
+    /// The last entry whose pattern matches, `is_match` tells whether the pattern in a slot does.
+    fn last_matching(&self, mut is_match: impl FnMut(Slot) -> bool) -> Option<&Entry> {
+        self.entries.iter().rev().find(|e| is_match(e.slot))
+    }
+
+    /// `all_match` with negated patterns, every normal pattern has to match and no negated one.
+    fn all_match_negated(&self, mut is_match: impl FnMut(Slot) -> bool) -> bool {
+        self.entries.iter().all(|e| is_match(e.slot) != e.negated)
+    }
+
     pub fn any_match(&self, value: &str) -> bool {
         if self.ignore_case_patterns.is_empty() &&
             self.case_sensitive_patterns.is_empty() {
             return false;
         }
+        if self.negated_count > 0 {
+            return self.matched(value).is_included();
+        }
+        if let Some(index) = self.usable_index() {
+            return index.is_match(value);
+        }
+        if !self.track_hits {
+            // literal patterns are looked up by hashing, unless the hits of each pattern are counted
+            return self.literals.get_or_init(|| LiteralMap::new(self)).any_match(self, value, &mut None);
+        }
 
-        let result_1 =
-            if !self.ignore_case_patterns.is_empty() {
-                // only allocate uppercase if have any ignore case patterns
-                let value = value.to_uppercase();
-                self.ignore_case_patterns
-                    .iter()
-                    .any(|p|glob_match_prebuilt(&p.0, &value))
-            } else {
-                false
-            };
+        // case sensitive patterns first, a match there saves folding the value
+        if self.case_sensitive_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| record_hit(&self.case_sensitive_hits, i, p.0.matches_folded(value))) {
+            return true;
+        }
+        let mut folded = None;
+        self.ignore_case_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| record_hit(&self.ignore_case_hits, i, p.is_match_lazily(value, &mut folded)))
+    }
 
-        let result_2 =
-            self.case_sensitive_patterns
-                .iter()
-                .any(|p|glob_match_prebuilt(&p.0, value));
+    /// Whether [`GlobList::any_match`] rejects `value`.
+    pub fn none_match(&self, value: &str) -> bool {
+        !self.any_match(value)
+    }
 
-        result_1 || result_2
+    /// Number of patterns matching `value`, negated ones included, e.g. how many rules fired.
+    pub fn count_matches(&self, value: &str) -> usize {
+        self.matching(value).count()
     }
 
     pub fn all_match(&self, value: &str) -> bool {
@@ -129,53 +1213,219 @@ impl GlobList {
             self.case_sensitive_patterns.is_empty() {
             return true;
         }
+        let mut folded = None;
+        if self.negated_count > 0 {
+            return self.all_match_negated(|slot| self.slot_is_match_lazily(slot, value, &mut folded));
+        }
 
-        let result_1 =
-            if !self.ignore_case_patterns.is_empty() {
-                // only allocate uppercase if have any ignore case patterns
-                let value = value.to_uppercase();
-                self.ignore_case_patterns
-                    .iter()
-                    .all(|p|glob_match_prebuilt(&p.0, &value))
-            } else {
-                true
-            };
-
-        let result_2 =
-            self.case_sensitive_patterns
+        // least likely to match first, see reorder_by_selectivity, and case sensitive patterns first
+        // so a mismatch there saves folding the value
+        self.case_sensitive_patterns
+            .iter()
+            .enumerate()
+            .rev()
+            .all(|(i, p)| record_hit(&self.case_sensitive_hits, i, p.0.matches_folded(value)))
+            && self.ignore_case_patterns
                 .iter()
-                .all(|p|glob_match_prebuilt(&p.0, value));
+                .enumerate()
+                .rev()
+                .all(|(i, p)| record_hit(&self.ignore_case_hits, i, p.is_match_lazily(value, &mut folded)))
+    }
+
+    /// Same as [`GlobList::any_match`], but case folds `value` into `ctx` instead of allocating.
+    pub fn any_match_with(&self, ctx: &mut MatchContext, value: &str) -> bool {
+        if self.negated_count > 0 {
+            let folded = ctx.fold(value);
+            return self.last_matching(|slot| self.slot_is_match(slot, value, folded)).is_some_and(|e| !e.negated);
+        }
+        if let Some(index) = self.usable_index() {
+            return index.is_match(value);
+        }
+        if !self.track_hits {
+            // case sensitive patterns first, a match there saves folding the value
+            let literals = self.literals.get_or_init(|| LiteralMap::new(self));
+            return literals.case_sensitive_match(self, value) ||
+                (!self.ignore_case_patterns.is_empty() && literals.ignore_case_match(self, value, ctx.fold(value)));
+        }
+        // case sensitive patterns first, a match there saves folding the value
+        if self.case_sensitive_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| record_hit(&self.case_sensitive_hits, i, p.0.matches_folded(value))) {
+            return true;
+        }
+        if self.ignore_case_patterns.is_empty() {
+            return false;
+        }
+        let folded = ctx.fold(value);
+        self.ignore_case_patterns
+            .iter()
+            .enumerate()
+            .any(|(i, p)| record_hit(&self.ignore_case_hits, i, p.is_match_folded(value, folded)))
+    }
 
-        result_1 && result_2
+    /// Same as [`GlobList::all_match`], but case folds `value` into `ctx` instead of allocating.
+    pub fn all_match_with(&self, ctx: &mut MatchContext, value: &str) -> bool {
+        if self.negated_count > 0 {
+            let folded = ctx.fold(value);
+            return self.all_match_negated(|slot| self.slot_is_match(slot, value, folded));
+        }
+        // least likely to match first, see reorder_by_selectivity, and case sensitive patterns first
+        // so a mismatch there saves folding the value
+        if !self.case_sensitive_patterns
+            .iter()
+            .enumerate()
+            .rev()
+            .all(|(i, p)| record_hit(&self.case_sensitive_hits, i, p.0.matches_folded(value))) {
+            return false;
+        }
+        if self.ignore_case_patterns.is_empty() {
+            return true;
+        }
+        let folded = ctx.fold(value);
+        self.ignore_case_patterns
+            .iter()
+            .enumerate()
+            .rev()
+            .all(|(i, p)| record_hit(&self.ignore_case_hits, i, p.is_match_folded(value, folded)))
     }
 
+    /// The values [`GlobList::any_match`] accepts, case folding them all into the same buffer.
+    pub fn filter<'a, 'v, I>(&'a self, values: I) -> impl Iterator<Item = &'v str> + 'a
+        where I: IntoIterator<Item = &'v str>, I::IntoIter: 'a {
+        let mut ctx = MatchContext::new();
+        values.into_iter().filter(move |value| self.any_match_with(&mut ctx, value))
+    }
+
+    /// Splits `values` into the ones [`GlobList::any_match`] accepts and the rest, keeping their order.
+    pub fn partition<'v>(&self, values: impl IntoIterator<Item = &'v str>) -> (Vec<&'v str>, Vec<&'v str>) {
+        let mut ctx = MatchContext::new();
+        values.into_iter().partition(|value| self.any_match_with(&mut ctx, value))
+    }
+
+    /// The case sensitive patterns are inserted before the ignore case patterns.
     pub fn from_patterns(case_sensitive: Vec<GlobCaseSensitive>, ignore_case: Vec<GlobIgnoreCase>) -> GlobList {
-        GlobList {
-            ignore_case_patterns: ignore_case,
-            case_sensitive_patterns: case_sensitive
+        let mut list = GlobList::new();
+        for p in case_sensitive {
+            list.add_case_sensitive(p);
         }
+        for p in ignore_case {
+            list.add_ignore_case(p);
+        }
+        list
     }
 
+    /// Keeps the insertion order of each list, one list after the other.
+    /// The entries get new ids in the combined list.
     pub fn combine(glob_lists: Vec<GlobList>) -> GlobList {
         glob_lists.into_iter().fold(GlobList::new(), |mut acc, item| {
+            let ignore_case_offset = acc.ignore_case_patterns.len();
+            let case_sensitive_offset = acc.case_sensitive_patterns.len();
             acc.ignore_case_patterns.extend(item.ignore_case_patterns);
             acc.case_sensitive_patterns.extend(item.case_sensitive_patterns);
+            for entry in item.entries {
+                acc.push_entry(match entry.slot {
+                    Slot::CaseSensitive(i) => Slot::CaseSensitive(i + case_sensitive_offset),
+                    Slot::IgnoreCase(i) => Slot::IgnoreCase(i + ignore_case_offset),
+                }, entry.negated);
+            }
             acc
         })
     }
 }
 
-
-#[derive(Debug,Clone)]
+/// A piece of a [`GlobPattern::Multipart`], a pattern of literals and `*` matched by searching for each
+/// literal in turn. [`GlobPattern::segments`] lists them as [`Segment`]s.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Multipart {
+    /// The literal the value starts with, only ever first
     ExactStart(String),
+    /// `*` followed by a literal
     AnyUntil(String),
+    /// `*` followed by the literal the value ends with, only ever last
     AnyUntilExactEnd(String),
+    /// `*` at the end
     AnyEnd,
 }
 
-pub fn build_glob_pattern(pattern: &str) -> Result<GlobPattern,()> {
+/// Options changing how a pattern is built, set through [`GlobBuilder`].
+#[derive(Debug,Clone,Default)]
+pub(crate) struct PatternOptions {
+    /// `*`, `?` and negated classes don't match `/`
+    pub(crate) literal_separator: bool,
+    /// matches anywhere in a value, as if the pattern started and ended with `*`
+    pub(crate) unanchored: bool,
+    /// the fewest chars a `*` matches, with more than 0 it's built as that many `?` followed by `*`
+    pub(crate) wildcard_min_len: usize,
+    /// the most literals and wildcards the built pattern can have, see `GlobPattern::segment_count`
+    pub(crate) max_segments: Option<usize>,
+    /// built segmented, which matching by grapheme clusters needs
+    pub(crate) graphemes: bool,
+    /// expanded with DOS wildcard rules first, see `dos::build`
+    pub(crate) dos: bool,
+    /// how the pattern is written
+    pub(crate) syntax: Syntax,
+    /// wildcards don't match a `.` at the start of the value, or of a component with a literal separator
+    pub(crate) literal_leading_dot: bool,
+}
+
+pub fn build_glob_pattern(pattern: &str) -> Result<GlobPattern,GlobError> {
+    build_glob_pattern_with(pattern, &PatternOptions::default())
+}
+
+pub(crate) fn build_glob_pattern_with(pattern: &str, options: &PatternOptions) -> Result<GlobPattern,GlobError> {
+    if pattern.is_empty() {
+        return Err(GlobError::EmptyPattern);
+    }
+    if options.dos {
+        return dos::build(pattern, options);
+    }
+    let built = build_glob_pattern_with_braces(pattern, options)?;
+    if let Some(max) = options.max_segments {
+        if built.segment_count() > max {
+            return Err(GlobError::TooManySegments { max });
+        }
+    }
+    Ok(if options.unanchored { unanchor(built) } else { built })
+}
+
+fn build_glob_pattern_with_braces(pattern: &str, options: &PatternOptions) -> Result<GlobPattern,GlobError> {
+    if !pattern.contains('{') || options.syntax != Syntax::Glob {
+        return build_glob_pattern_without_braces(pattern, options);
+    }
+    let mut alternatives = braces::expand_braces(pattern, options.max_segments)?
+        .iter()
+        .map(|p| build_glob_pattern_without_braces(p, options))
+        .collect::<Result<Vec<GlobPattern>, GlobError>>()
+        .map_err(|e| match build_glob_pattern_without_braces(pattern, options) {
+            // positions in the expansions don't match the ones in `pattern`, so find the error without expanding,
+            // if it only shows up after expanding (e.g. "*{*a,b}") blame the first brace
+            Err(e) => e,
+            Ok(_) => e.at(pattern.find('{').unwrap_or(0)),
+        })?;
+    if alternatives.len() == 1 {
+        return Ok(alternatives.remove(0));
+    }
+    Ok(GlobPattern::Alternatives(alternatives))
+}
+
+fn build_glob_pattern_without_braces(pattern: &str, options: &PatternOptions) -> Result<GlobPattern,GlobError> {
+    if options.syntax != Syntax::Glob || options.literal_leading_dot {
+        let segments = match options.syntax {
+            Syntax::Glob | Syntax::Gitignore => segments::build_segments(pattern, options)?,
+            Syntax::Fnmatch => fnmatch::build_segments(pattern, options)?,
+        };
+        if options.literal_leading_dot {
+            return Ok(leading_dot::restrict(&segments));
+        }
+        return Ok(GlobPattern::Segmented(segments));
+    }
     // TODO: rewrite cleaner
+    if options.literal_separator || options.graphemes || options.wildcard_min_len > 0 || pattern.contains(['?', '[']) || pattern.contains("**") {
+        return segments::build_segments(pattern, options).map(GlobPattern::Segmented);
+    }
+
     if pattern == "*" {
         return Ok(GlobPattern::MatchAny);
     }
@@ -195,13 +1445,16 @@ pub fn build_glob_pattern(pattern: &str) -> Result<GlobPattern,()> {
         }
     } else {
         // Multipart
-        let mut parts = Vec::<Multipart>::new();
+        let mut parts = MultipartVec::new();
         let mut pos;
         let end = pattern.len();
 
         if let Some(start_wildcard) = pattern.strip_prefix('*') {
             // + 1 because we're looking at the subset [1..] but we want the position in the original string
             let wildcard = start_wildcard.find('*').unwrap() + 1; // has to be at least 2 wildcards if we get here
+            if wildcard == 1 {
+                return Err(GlobError::EmptyWildcardSegment { position: wildcard });
+            }
             parts.push(Multipart::AnyUntil(pattern[1..wildcard].to_string()));
             pos = wildcard + 1;
         } else {
@@ -216,6 +1469,9 @@ pub fn build_glob_pattern(pattern: &str) -> Result<GlobPattern,()> {
         }
 
         while let Some(found) = pattern[pos..].find('*') {
+            if found == 0 {
+                return Err(GlobError::EmptyWildcardSegment { position: pos });
+            }
             parts.push(Multipart::AnyUntil(pattern[pos..pos + found].to_string()));
             pos += found + 1;
         }
@@ -226,28 +1482,15 @@ pub fn build_glob_pattern(pattern: &str) -> Result<GlobPattern,()> {
             parts.push(Multipart::AnyUntilExactEnd(pattern[pos..].to_string()));
         }
 
-        // validation (TODO: move validation earlier, rewrite the fn even)
-
-        for p in &parts {
-            if let Multipart::AnyUntil(s) = p {
-                if s.is_empty() {
-                    return Err(()); // return empty wildcard error
-                }
-            }
-        }
-
         Ok(GlobPattern::Multipart(parts))
     }
 }
 
-// TODO: create an even slightly usable error
-pub fn glob_match(pattern: &str, value: &str) -> Result<bool, ()> {
-    // TODO: move shared parts to a function, rewrite cleaner
-    let pattern = build_glob_pattern(&pattern.to_uppercase())?;
-    Ok(glob_match_prebuilt(&pattern, &value.to_uppercase()))
+pub fn glob_match(pattern: &str, value: &str) -> Result<bool, GlobError> {
+    Ok(GlobIgnoreCase::build(pattern)?.is_match(value))
 }
 
-pub fn glob_match_case_sensitive(pattern: &str, value: &str) -> Result<bool, ()> {
+pub fn glob_match_case_sensitive(pattern: &str, value: &str) -> Result<bool, GlobError> {
     // TODO: move shared parts to a function, rewrite cleaner
     let pattern = build_glob_pattern(pattern)?;
     Ok(glob_match_prebuilt(&pattern, value))
@@ -261,149 +1504,576 @@ pub fn glob_match_all_prebuilt(patterns: &[GlobPattern], value: &str) -> bool {
     patterns.iter().all(|p| glob_match_prebuilt(p, value))
 }
 
+/// Returns the byte ranges in `value` covered by the literal parts of `pattern`,
+/// or `None` if `value` doesn't match.
+///
+/// Each literal is placed as far left as possible, except for a literal end which is placed at the end.
+pub fn glob_match_spans_prebuilt(pattern: &GlobPattern, value: &str) -> Option<Vec<Range<usize>>> {
+    let mut spans = Vec::new();
+    if glob_match_spans_into(pattern, value, &mut spans) {
+        Some(spans)
+    } else {
+        None
+    }
+}
+
+/// Same as [`glob_match_spans_prebuilt`], but writes the spans into `spans` (cleared first) instead of allocating.
+/// Returns whether `value` matched, `spans` is unspecified if it didn't.
+pub fn glob_match_spans_into(pattern: &GlobPattern, value: &str, spans: &mut Vec<Range<usize>>) -> bool {
+    matched_spans(pattern, value, spans).is_some()
+}
+
+/// Same as [`glob_match_spans_into`], but returns the pattern that matched,
+/// which is one of the alternatives when `pattern` has any.
+pub(crate) fn matched_spans<'p>(pattern: &'p GlobPattern, value: &str, spans: &mut Vec<Range<usize>>) -> Option<&'p GlobPattern> {
+    if let GlobPattern::Alternatives(alternatives) = pattern {
+        return alternatives.iter().find_map(|alternative| matched_spans(alternative, value, spans));
+    }
+    if leaf_spans(pattern, value, spans) {
+        Some(pattern)
+    } else {
+        None
+    }
+}
+
+/// Leaves the byte range in `value` covered by a match in `range`, from the start of the first part of `pattern`
+/// that isn't `*` to the end of the last one. Returns the pattern that matched like [`matched_spans`].
+pub(crate) fn matched_range<'p>(pattern: &'p GlobPattern, value: &str, range: &mut Vec<Range<usize>>) -> Option<&'p GlobPattern> {
+    let (start, end) = match pattern {
+        GlobPattern::Alternatives(alternatives) => {
+            // the leftmost one, for finding matches in order within a value
+            let mut leftmost: Option<(&GlobPattern, Range<usize>)> = None;
+            for alternative in alternatives {
+                if let Some(matched) = matched_range(alternative, value, range) {
+                    if leftmost.as_ref().is_none_or(|(_, r)| range[0].start < r.start) {
+                        leftmost = Some((matched, range[0].clone()));
+                    }
+                }
+            }
+            let (matched, leftmost) = leftmost?;
+            range.clear();
+            range.push(leftmost);
+            return Some(matched);
+        },
+        GlobPattern::Segmented(segments) => {
+            if !segments::match_segments(segments, value.as_bytes(), Anchoring::Full, Some(range)) {
+                return None;
+            }
+            let mut covered = segments
+                .iter()
+                .zip(range.iter())
+                .filter(|(segment, _)| !segment.is_any_length())
+                .map(|(_, r)| r.clone());
+            let first = covered.next();
+            let last = covered.next_back().or_else(|| first.clone());
+            (first.map(|r| r.start), last.map(|r| r.end))
+        },
+        _ => {
+            if !leaf_spans(pattern, value, range) {
+                return None;
+            }
+            (range.first().map(|r| r.start), range.last().map(|r| r.end))
+        },
+    };
+    range.clear();
+    range.push(match (start, end) {
+        (Some(start), Some(end)) => start..end,
+        _ => 0..0, // only wildcards
+    });
+    Some(pattern)
+}
+
+fn leaf_spans(pattern: &GlobPattern, value: &str, spans: &mut Vec<Range<usize>>) -> bool {
+    spans.clear();
+    let len = value.len();
+    match pattern {
+        GlobPattern::MatchAny => true,
+        GlobPattern::MatchEnd(end) if value.ends_with(end.as_str()) => {
+            spans.push(len - end.len()..len);
+            true
+        },
+        GlobPattern::MatchStart(start) if value.starts_with(start.as_str()) => {
+            spans.push(0..start.len());
+            true
+        },
+        GlobPattern::MatchBothEnds(start,end) if match_both_ends(start, end, value) => {
+            spans.push(0..start.len());
+            spans.push(len - end.len()..len);
+            true
+        },
+        GlobPattern::MatchFull(full) if value == full => {
+            spans.push(0..len);
+            true
+        },
+        GlobPattern::Multipart(multi) if !multi.is_empty() => {
+            let mut pos = 0;
+            for part in multi {
+                match part {
+                    Multipart::ExactStart(start) => {
+                        if !value.starts_with(start.as_str()) {
+                            return false;
+                        }
+                        spans.push(0..start.len());
+                        pos = start.len();
+                    },
+                    Multipart::AnyUntil(until) => {
+                        let found = match find_literal(&value[pos..], until) {
+                            Some(found) => pos + found,
+                            None => return false,
+                        };
+                        spans.push(found..found + until.len());
+                        pos = found + until.len();
+                    },
+                    Multipart::AnyUntilExactEnd(end) => {
+                        if len < pos + end.len() || !value.ends_with(end.as_str()) {
+                            return false;
+                        }
+                        spans.push(len - end.len()..len);
+                    },
+                    Multipart::AnyEnd => (),
+                }
+            }
+            true
+        },
+        GlobPattern::Segmented(segments) => {
+            if !segments::match_segments(segments, value.as_bytes(), Anchoring::Full, Some(spans)) {
+                return false;
+            }
+            segments::literal_spans(segments, spans);
+            true
+        },
+        _ => false,
+    }
+}
+
+/// The literal start and end of `start*end` can't share chars, `a*a` doesn't match `a`.
+fn match_both_ends(start: &str, end: &str, value: &str) -> bool {
+    value.len() >= start.len() + end.len() && value.starts_with(start) && value.ends_with(end)
+}
+
 pub fn glob_match_prebuilt(pattern: &GlobPattern, value: &str) -> bool {
     match pattern {
         GlobPattern::MatchAny => true,
         GlobPattern::MatchEnd(end) => value.ends_with(end.as_str()),
         GlobPattern::MatchStart(start) => value.starts_with(start.as_str()),
-        GlobPattern::MatchBothEnds(start,end) => value.starts_with(start.as_str()) && value.ends_with(end.as_str()),
+        GlobPattern::MatchBothEnds(start,end) => match_both_ends(start, end, value),
         GlobPattern::MatchFull(full) => value == full,
+        GlobPattern::Multipart(multi) => glob_match_multipart_str(multi, value),
+        GlobPattern::Segmented(segments) => segments::match_segments(segments, value.as_bytes(), Anchoring::Full, None),
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(|p| glob_match_prebuilt(p, value)),
+    }
+}
+
+/// Matches a byte string, which doesn't have to be valid UTF-8. Wildcards match a UTF-8 char where there
+/// is one and a single byte where the bytes aren't valid UTF-8.
+pub fn glob_match_prebuilt_bytes(pattern: &GlobPattern, value: &[u8]) -> bool {
+    match pattern {
+        GlobPattern::Segmented(segments) => segments::match_segments(segments, value, Anchoring::Full, None),
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(|p| glob_match_prebuilt_bytes(p, value)),
+        _ => glob_match_units(pattern, || value.iter().copied(), || value.iter().rev().copied()),
+    }
+}
+
+/// [`glob_match_folded_prebuilt`] for byte strings, see [`glob_match_prebuilt_bytes`].
+pub(crate) fn glob_match_bytes_folded(pattern: &GlobPattern, value: &[u8], case: CaseMode) -> bool {
+    match (pattern, case) {
+        (_, CaseMode::Sensitive) => glob_match_prebuilt_bytes(pattern, value),
+        (GlobPattern::Segmented(segments), _) => segments::match_segments_folded(segments, value, Anchoring::Full, case),
+        (GlobPattern::Alternatives(alternatives), _) => alternatives.iter().any(|p| glob_match_bytes_folded(p, value, case)),
+        // only copies invalid UTF-8, which can only be matched by wildcards as U+FFFD
+        (_, CaseMode::Insensitive) | (_, CaseMode::TurkicInsensitive) =>
+            glob_match_folded_prebuilt(pattern, &String::from_utf8_lossy(value), case),
+        (_, CaseMode::AsciiInsensitive) => glob_match_units(
+            pattern,
+            || value.iter().map(u8::to_ascii_lowercase),
+            || value.iter().rev().map(u8::to_ascii_lowercase)),
+    }
+}
+
+/// Matches a pattern built from a pattern folded as `case` says, folding `value` one char at a time
+/// while matching instead of allocating a folded copy.
+pub(crate) fn glob_match_folded_prebuilt(pattern: &GlobPattern, value: &str, case: CaseMode) -> bool {
+    match (pattern, case) {
+        (_, CaseMode::Sensitive) => glob_match_prebuilt(pattern, value),
+        (GlobPattern::Segmented(segments), _) => segments::match_segments_folded(segments, value.as_bytes(), Anchoring::Full, case),
+        (GlobPattern::Alternatives(alternatives), _) => alternatives.iter().any(|p| glob_match_folded_prebuilt(p, value, case)),
+        (_, CaseMode::Insensitive) | (_, CaseMode::TurkicInsensitive) => glob_match_units(
+            pattern,
+            || value.chars().flat_map(|c| case.fold_char(c)),
+            || value.chars().rev().flat_map(|c| case.fold_char(c).rev())),
+        (_, CaseMode::AsciiInsensitive) => glob_match_units(
+            pattern,
+            || value.bytes().map(|b| b.to_ascii_lowercase()),
+            || value.bytes().rev().map(|b| b.to_ascii_lowercase())),
+    }
+}
+
+/// [`glob_match_folded_prebuilt`] taking steps out of `budget`, `None` if it ran out.
+/// Only segmented patterns backtrack, the others take a step per byte of `value` up front.
+pub(crate) fn glob_match_budgeted(pattern: &GlobPattern, value: &str, case: CaseMode, budget: &mut budget::Budget) -> Option<bool> {
+    match pattern {
+        GlobPattern::Segmented(segments) =>
+            segments::match_segments_budgeted(segments, value.as_bytes(), Anchoring::Full, case, budget),
+        GlobPattern::Alternatives(alternatives) => {
+            for alternative in alternatives {
+                if glob_match_budgeted(alternative, value, case, budget)? {
+                    return Some(true);
+                }
+            }
+            Some(false)
+        },
+        _ if budget.charge(value.len()) => Some(glob_match_folded_prebuilt(pattern, value, case)),
+        _ => None,
+    }
+}
+
+/// Which ends of the value a pattern has to line up with.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Anchoring {
+    /// The whole value, the way patterns normally match.
+    Full,
+    /// The start of the value, the rest can be anything.
+    Prefix,
+    /// The end of the value, anything can come before it.
+    Suffix,
+    /// Anywhere in the value.
+    Within,
+}
+
+/// The same pattern as if it started and ended with `*`, so it matches anywhere in a value.
+fn unanchor(pattern: GlobPattern) -> GlobPattern {
+    let within = |s: String| GlobPattern::Multipart(vec![Multipart::AnyUntil(s), Multipart::AnyEnd].into());
+    match pattern {
+        GlobPattern::MatchAny => GlobPattern::MatchAny,
+        GlobPattern::MatchFull(s) | GlobPattern::MatchStart(s) | GlobPattern::MatchEnd(s) => within(s),
+        GlobPattern::MatchBothEnds(start, end) =>
+            GlobPattern::Multipart(vec![Multipart::AnyUntil(start), Multipart::AnyUntil(end), Multipart::AnyEnd].into()),
+        GlobPattern::Multipart(mut multi) => {
+            if let Some(Multipart::ExactStart(start)) = multi.first_mut() {
+                multi[0] = Multipart::AnyUntil(std::mem::take(start));
+            }
+            if let Some(Multipart::AnyUntilExactEnd(end)) = multi.last_mut() {
+                *multi.last_mut().unwrap() = Multipart::AnyUntil(std::mem::take(end));
+                multi.push(Multipart::AnyEnd);
+            }
+            GlobPattern::Multipart(multi)
+        },
+        GlobPattern::Segmented(mut segments) => {
+            // `*` and not `*` with a literal separator, the match can start and end anywhere
+            if !matches!(segments.first(), Some(Segment::AnyChars) | Some(Segment::AnyPath)) {
+                segments.insert(0, Segment::AnyChars);
+            }
+            if !matches!(segments.last(), Some(Segment::AnyChars) | Some(Segment::AnyPath)) {
+                segments.push(Segment::AnyChars);
+            }
+            GlobPattern::Segmented(segments)
+        },
+        GlobPattern::Alternatives(alternatives) => GlobPattern::Alternatives(alternatives.into_iter().map(unanchor).collect()),
+    }
+}
+
+/// Matches `pattern` against `value` anchored as `anchoring` says,
+/// so one compiled pattern can do the job of `foo`, `foo*`, `*foo` and `*foo*`.
+pub fn glob_match_prebuilt_anchored(pattern: &GlobPattern, value: &str, anchoring: Anchoring) -> bool {
+    let (anchor_start, anchor_end) = match anchoring {
+        Anchoring::Full => return glob_match_prebuilt(pattern, value),
+        Anchoring::Prefix => (true, false),
+        Anchoring::Suffix => (false, true),
+        Anchoring::Within => (false, false),
+    };
+
+    let (start, middle, end) : (Option<&str>, &[Multipart], Option<&str>) = match pattern {
+        GlobPattern::MatchAny => return true,
+        GlobPattern::MatchFull(full) => return match anchoring {
+            Anchoring::Prefix => value.starts_with(full.as_str()),
+            Anchoring::Suffix => value.ends_with(full.as_str()),
+            _ => value.contains(full.as_str()),
+        },
+        GlobPattern::MatchEnd(end) => (None, &[], Some(end)),
+        GlobPattern::MatchStart(start) => (Some(start), &[], None),
+        GlobPattern::MatchBothEnds(start, end) => (Some(start), &[], Some(end)),
+        GlobPattern::Segmented(segments) => return segments::match_segments(segments, value.as_bytes(), anchoring, None),
+        GlobPattern::Alternatives(alternatives) =>
+            return alternatives.iter().any(|p| glob_match_prebuilt_anchored(p, value, anchoring)),
         GlobPattern::Multipart(multi) => {
-            if multi.is_empty() {
-                return false; // TODO: change this behavior
+            let (start, multi) = match multi.split_first() {
+                Some((Multipart::ExactStart(start), rest)) => (Some(start.as_str()), rest),
+                _ => (None, &multi[..]),
+            };
+            match multi.split_last() {
+                Some((Multipart::AnyUntilExactEnd(end), rest)) => (start, rest, Some(end.as_str())),
+                _ => (start, multi, None),
             }
+        },
+    };
+
+    // an unanchored start or end is just another literal that has to be found in order
+    let (start, first) = if anchor_start { (start, None) } else { (None, start) };
+    let (end, last) = if anchor_end { (end, None) } else { (None, end) };
+    let middle = middle
+        .iter()
+        .filter_map(|part| match part {
+            Multipart::AnyUntil(until) => Some(until.as_str()),
+            _ => None,
+        });
+
+    let mut pos = 0;
+    if let Some(start) = start {
+        if !value.starts_with(start) {
+            return false;
+        }
+        pos = start.len();
+    }
+    // the leftmost occurrence of each literal leaves the most room for the rest
+    for literal in first.into_iter().chain(middle).chain(last) {
+        match find_literal(&value[pos..], literal) {
+            Some(found) => pos += found + literal.len(),
+            None => return false,
+        }
+    }
+    match end {
+        Some(end) => value.len() >= pos + end.len() && value.ends_with(end),
+        None => true,
+    }
+}
 
-            let mut current_pos = 0;
-            let mut current = multi.get(current_pos).unwrap();
-            let mut ch_iter = value.chars();
-            'outer:
-            loop {
-                let mut ch = ch_iter.next();
-                if matches!(current, Multipart::AnyEnd) {
-                    return true;
-                }
+/// Matches `chunks` as if they were one string, e.g. the pieces of a rope or a ring buffer.
+///
+/// A [`GlobPattern::Segmented`] pattern (and alternatives that are) backtracks over the value, so the chars
+/// of the chunks are collected into a buffer for it. The other variants go over the chunks as they are.
+pub fn glob_match_prebuilt_chunks(pattern: &GlobPattern, chunks: &[&str]) -> bool {
+    glob_match_units(
+        pattern,
+        || chunks.iter().flat_map(|c| c.chars()),
+        || chunks.iter().rev().flat_map(|c| c.chars().rev()))
+}
+
+/// What the matcher compares one at a time, chars for strings and bytes for byte strings.
+trait Unit: Copy + PartialEq {
+    type Units<'a>: DoubleEndedIterator<Item = Self>;
+
+    fn units(literal: &str) -> Self::Units<'_>;
+
+    /// Number of units making up the first char of `value`, which isn't empty.
+    fn char_len(value: &[Self]) -> usize;
+
+    /// The first char of `value`, which isn't empty.
+    fn first_char(value: &[Self]) -> char;
+
+    fn ascii_lowercase(self) -> Self;
+}
+
+impl Unit for char {
+    type Units<'a> = std::str::Chars<'a>;
+
+    fn units(literal: &str) -> Self::Units<'_> {
+        literal.chars()
+    }
+
+    fn char_len(_value: &[char]) -> usize {
+        1
+    }
+
+    fn first_char(value: &[char]) -> char {
+        value[0]
+    }
+
+    fn ascii_lowercase(self) -> char {
+        self.to_ascii_lowercase()
+    }
+}
+
+impl Unit for u8 {
+    type Units<'a> = std::str::Bytes<'a>;
+
+    fn units(literal: &str) -> Self::Units<'_> {
+        literal.bytes()
+    }
+
+    /// Invalid UTF-8 counts as one char per byte.
+    fn char_len(value: &[u8]) -> usize {
+        let width = match value[0] {
+            0xF0..=0xFF => 4,
+            0xE0..=0xEF => 3,
+            0xC0..=0xDF => 2,
+            _ => return 1,
+        };
+        1 + value[1..width.min(value.len())]
+            .iter()
+            .take_while(|b| (0x80..0xC0).contains(*b))
+            .count()
+    }
 
-                if ch.is_none() {
-                    break;
-                }
-                match &current {
-                    Multipart::ExactStart(start) => {
-                        for ch_st in start.chars() {
-                            if ch.unwrap() != ch_st {
-                                return false;
-                            }
-                            ch = ch_iter.next();
-                        }
+    /// Invalid UTF-8 is U+FFFD, like it is for bstr's `chars`.
+    fn first_char(value: &[u8]) -> char {
+        std::str::from_utf8(&value[..u8::char_len(value)])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
 
-                        #[cfg(test)]
-                        println!("Matched exact start '{}'", start);
+    fn ascii_lowercase(self) -> u8 {
+        self.to_ascii_lowercase()
+    }
+}
 
-                        current_pos += 1;
-                        if current_pos > multi.len() - 1 {
-                            return true;
-                        }
-                        current = multi.get(current_pos).unwrap();
-                    },
-                    Multipart::AnyUntil(until) => {
-                        let mut ch_un_iter = until.chars();
-                        let mut ch_un = ch_un_iter.next();
-
-                        if ch.unwrap() != ch_un.unwrap() { // not yet at a possible start of next part
-                            loop {
-                                ch = ch_iter.next();
-                                if ch.is_none() {
-                                    return false; // out of chars before the first char of the part was found, couldn't possibly match (please don't be wrong about this)
-                                }
-                                if ch.unwrap() == ch_un.unwrap() {
-                                    break; // found possible start of part
-                                }
-                            }
-                        }
+/// Matches a value only available as an iterator, `units` iterates the value from the start
+/// and `units_rev` from the end.
+fn glob_match_units<U, F, I, R, J>(pattern: &GlobPattern, units: F, units_rev: R) -> bool
+    where U: Unit,
+          F: Fn() -> I, I: Iterator<Item = U> + Clone,
+          R: Fn() -> J, J: Iterator<Item = U> {
+    glob_match_units_ref(pattern, &units, &units_rev)
+}
 
-                        loop {
-                            ch_un = ch_un_iter.next();
-                            if ch_un.is_none() {
-                                break; // we matched everything
-                            }
+/// [`glob_match_units`] borrowing the closures, so alternatives can recurse with the same ones.
+fn glob_match_units_ref<U, F, I, R, J>(pattern: &GlobPattern, units: &F, units_rev: &R) -> bool
+    where U: Unit,
+          F: Fn() -> I, I: Iterator<Item = U> + Clone,
+          R: Fn() -> J, J: Iterator<Item = U> {
+    fn starts_with<U: Unit>(mut value: impl Iterator<Item = U>, mut start: impl Iterator<Item = U>) -> bool {
+        start.all(|ch| value.next() == Some(ch))
+    }
 
-                            ch = ch_iter.next();
-                            if ch.is_none() {
-                                return false; // ended before we could match everything
-                            }
+    match pattern {
+        GlobPattern::MatchAny => true,
+        GlobPattern::MatchEnd(end) => starts_with(units_rev(), U::units(end).rev()),
+        GlobPattern::MatchStart(start) => starts_with(units(), U::units(start)),
+        GlobPattern::MatchBothEnds(start,end) => starts_with(units(), U::units(start)) && starts_with(units_rev(), U::units(end).rev())
+            && units().count() >= U::units(start).count() + U::units(end).count(),
+        GlobPattern::MatchFull(full) => units().eq(U::units(full)),
+        GlobPattern::Multipart(multi) => glob_match_multipart(multi, units(), units().count(), units_rev()),
+        GlobPattern::Segmented(segments) => {
+            // segments backtrack over the value, which needs it in one place
+            let value : Vec<U> = units().collect();
+            segments::match_segments(segments, &value, Anchoring::Full, None)
+        },
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(|p| glob_match_units_ref(p, units, units_rev)),
+    }
+}
 
-                            if ch.unwrap() != ch_un.unwrap() {
-                                continue 'outer; // continue outer loop and try finding the start of the part again
-                            }
-                        }
+/// Runs the multipart matcher over `units`, one char (or byte) at a time, `len` being how many there are.
+/// Each literal is matched at its leftmost occurrence, which leaves the most room for the rest.
+///
+/// That never needs backtracking: with only `*` between the literals, whatever the rest of the pattern
+/// matches after a later occurrence of a literal it also matches after the leftmost one, the `*` following
+/// it takes up the difference. Only the literal end is anchored, so it's checked against the end of the value.
+/// The reference module tests this exhaustively against a matcher trying every split.
+fn glob_match_multipart<U, I, J>(multi: &[Multipart], mut units: I, len: usize, mut units_rev: J) -> bool
+    where U: Unit, I: Iterator<Item = U> + Clone, J: Iterator<Item = U> {
+    fn starts_with<U: Unit>(mut value: impl Iterator<Item = U>, start: &str) -> bool {
+        U::units(start).all(|ch| value.next() == Some(ch))
+    }
 
-                        #[cfg(test)]
-                        println!("Matched any until '{}'", until);
+    let mut pos = 0;
+    for part in multi {
+        match part {
+            Multipart::ExactStart(start) => {
+                if !starts_with(units.clone(), start) {
+                    return false;
+                }
+                let start_len = U::units(start).count();
+                units.by_ref().take(start_len).for_each(drop);
+                pos += start_len;
+            },
+            Multipart::AnyUntil(until) => {
+                let until_len = U::units(until).count();
+                loop {
+                    if len < pos + until_len {
+                        return false;
+                    }
+                    if starts_with(units.clone(), until) {
+                        break;
+                    }
+                    units.next();
+                    pos += 1;
+                }
+                units.by_ref().take(until_len).for_each(drop);
+                pos += until_len;
+            },
+            Multipart::AnyUntilExactEnd(end) => {
+                let end_len = U::units(end).count();
+                let mut end_rev = U::units(end).rev();
+                return len >= pos + end_len && end_rev.all(|ch| Some(ch) == units_rev.next());
+            },
+            Multipart::AnyEnd => return true,
+        }
+    }
+    pos == len
+}
 
-                        current_pos += 1;
-                        if current_pos > multi.len() - 1 {
-                            return true;
-                        }
-                        current = multi.get(current_pos).unwrap();
-                    },
-                    Multipart::AnyUntilExactEnd(until) => {
-                        loop { // TODO: maybe reduce the amount of loops :-)
-                            let mut ch_un_iter = until.chars();
-                            let mut ch_un = ch_un_iter.next();
-
-                            if ch.unwrap() != ch_un.unwrap() { // not yet at a possible start of next part
-                                loop {
-                                    ch = ch_iter.next();
-                                    if ch.is_none() {
-                                        return false; // out of chars before the first char of the part was found, couldn't possibly match (please don't be wrong about this)
-                                    }
-                                    if ch.unwrap() == ch_un.unwrap() {
-                                        break; // found possible start of part
-                                    }
-                                }
-                            }
-
-                            loop {
-                                ch_un = ch_un_iter.next();
-                                if ch_un.is_none() {
-                                    break; // we matched everything, break out and check if we're at the end
-                                }
-
-                                ch = ch_iter.next();
-                                if ch.is_none() {
-                                    return false; // ended before we could match everything
-                                }
-
-                                if ch.unwrap() != ch_un.unwrap() {
-                                    break; // continue outer loop and try finding the start of the part again
-                                } //^
-                            } //    |
-                            //      '--------------.
-                            ch = ch_iter.next(); //|
-                            //                     '--------------------------------<
-                            if ch.is_none() { // <- this should not be true if this ^ break happens
-                                              // unless I was a little too tired when reasoning about it
-                                #[cfg(test)]
-                                println!("Matched any until exact end '{}'", until);
-                                return true;
-                            }
-                        }
-                    },
-                    Multipart::AnyEnd => {
-                        #[cfg(test)]
-                        println!("Matched any end");
-                        return true;
-                    },
+/// Same as [`glob_match_multipart`] on a `&str`, finding each literal with a substring search
+/// instead of comparing one char at a time.
+fn glob_match_multipart_str(multi: &[Multipart], value: &str) -> bool {
+    let mut pos = 0;
+    for part in multi {
+        match part {
+            Multipart::ExactStart(start) => {
+                if !value.starts_with(start.as_str()) {
+                    return false;
                 }
-            }
-            false
+                pos = start.len();
+            },
+            Multipart::AnyUntil(until) => match find_literal(&value[pos..], until) {
+                Some(found) => pos += found + until.len(),
+                None => return false,
+            },
+            Multipart::AnyUntilExactEnd(end) => return value.len() >= pos + end.len() && value.ends_with(end.as_str()),
+            Multipart::AnyEnd => return true,
         }
     }
+    pos == value.len()
+}
+
+/// Byte position of the first occurrence of `literal` in `value`.
+#[cfg(feature = "memchr")]
+fn find_literal(value: &str, literal: &str) -> Option<usize> {
+    memchr::memmem::find(value.as_bytes(), literal.as_bytes())
+}
+
+/// Byte position of the first occurrence of `literal` in `value`.
+#[cfg(not(feature = "memchr"))]
+fn find_literal(value: &str, literal: &str) -> Option<usize> {
+    value.find(literal)
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::{GlobCaseSensitive, GlobIgnoreCase, GlobList};
+    use crate::{Glob, GlobCaseSensitive, GlobIgnoreCase, GlobList, GlobListEntry};
+
+    #[test]
+    fn ignore_case_captures_keep_original_case() {
+        let glob = GlobIgnoreCase::build("IMG_*_[0-9]?.jpg").unwrap();
+        let captures = glob.captures("img_Holiday_42.JPG").unwrap();
+        assert_eq!(captures.iter().collect::<Vec<_>>(), ["Holiday", "4", "2"]);
+        assert_eq!(glob.match_spans("img_Holiday_42.JPG"), Some(vec![0..4, 11..12, 13..13, 14..18]));
+        let glob = GlobIgnoreCase::build("{report,summary}-*.txt").unwrap();
+        assert_eq!(glob.captures("Summary-Q3.TXT").unwrap().get(0), Some("Q3"));
+        #[cfg(feature = "unicode")]
+        {
+            // the Kelvin sign folds to an ASCII `k`, one byte instead of three
+            let glob = GlobIgnoreCase::build("*k*").unwrap();
+            let captures = glob.captures("Ma\u{212A}er").unwrap();
+            assert_eq!(captures.iter().collect::<Vec<_>>(), ["Ma", "er"]);
+            let glob = Glob::builder("*I*").case_insensitive(true).case_fold(crate::CaseFold::Turkic).build().unwrap();
+            assert_eq!(glob.captures("Kıta").unwrap().iter().collect::<Vec<_>>(), ["K", "ta"]);
+        }
+    }
+
+    #[test]
+    fn public_segments() {
+        use crate::{build_glob_pattern, Segment};
+        let pattern = build_glob_pattern("src/*.rs").unwrap();
+        let segments: Vec<Segment> = pattern.segments().collect();
+        assert_eq!(segments, [Segment::Literal("src/".into()), Segment::AnyChars, Segment::Literal(".rs".into())]);
+        let pattern = build_glob_pattern("{a?,b{c,[d]}}").unwrap();
+        assert_eq!(pattern.segments().count(), 0);
+        let alternatives: Vec<Vec<Segment>> = pattern.alternatives().map(|p| p.segments().collect()).collect();
+        assert_eq!(alternatives.len(), 3);
+        assert_eq!(alternatives[0], [Segment::Literal("a".into()), Segment::AnyChar]);
+        assert_eq!(build_glob_pattern("*").unwrap().alternatives().count(), 1);
+    }
 
     #[test]
     fn empty_glob_list_any_match_never_matches() {
@@ -477,6 +2147,874 @@ mod tests {
         assert!(glob_list.all_match("hello world, you are nice, hELLO world"));
     }
 
+    #[test]
+    fn glob_list_iterates_in_insertion_order() {
+        let mut glob_list = GlobList::new();
+        let a = glob_list.add_case_sensitive(GlobCaseSensitive::build("a*").unwrap());
+        let b = glob_list.add_ignore_case(GlobIgnoreCase::build("b*").unwrap());
+        let c = glob_list.add_case_sensitive(GlobCaseSensitive::build("c*").unwrap());
+        let order : Vec<_> = glob_list.iter().map(|(id, e)| (id, e.is_case_sensitive())).collect();
+        assert_eq!(order, vec![(a, true), (b, false), (c, true)]);
+        assert!(matches!(glob_list.get(b), Some(GlobListEntry::IgnoreCase(p)) if p.is_match("BEE")));
+    }
+
+    #[test]
+    fn glob_list_reports_which_pattern_matched() {
+        let mut glob_list = GlobList::new();
+        glob_list.add_case_sensitive(GlobCaseSensitive::build("*.log").unwrap());
+        let target = glob_list.add_ignore_case(GlobIgnoreCase::build("TARGET/*").unwrap());
+        let logs = glob_list.add_case_sensitive(GlobCaseSensitive::build("target/*.log").unwrap());
+        let first = glob_list.first_match("target/build.txt").unwrap();
+        assert_eq!(first, crate::GlobMatch { id: target, pattern: "TARGET/*", case_sensitive: false, negated: false });
+        let all : Vec<_> = glob_list.matches("target/build.log").iter().map(|m| m.id.as_usize()).collect();
+        assert_eq!(all, vec![0, target.as_usize(), logs.as_usize()]);
+        assert!(glob_list.first_match("src/lib.rs").is_none());
+    }
+
+    #[test]
+    fn glob_list_optimize() {
+        let patterns: Vec<String> = ["src/*.rs", "*.rs", "*.toml", "*.rs", "Cargo.toml", "*.{md,txt}", "*.{txt,md}"]
+            .iter().map(|p| p.to_string()).collect();
+        let mut glob_list = GlobList::build(&patterns).unwrap();
+        glob_list.add(Glob::builder("*.RS").case_insensitive(true).build().unwrap());
+        let removed : Vec<_> = glob_list.optimize().iter().map(|id| id.as_usize()).collect();
+        assert_eq!(removed, vec![0, 1, 4, 6]);
+        let left : Vec<_> = glob_list.iter().map(|(_, e)| e.as_glob().as_str()).collect();
+        assert_eq!(left, vec!["*.toml", "*.rs", "*.{md,txt}", "*.RS"]);
+        for value in ["src/lib.rs", "Cargo.toml", "README.md", "MAIN.RS", "notes.txt"].iter() {
+            assert!(glob_list.any_match(value), "{}", value);
+        }
+        assert!(!glob_list.any_match("Cargo.lock"));
+        assert!(glob_list.any_match_with(&mut crate::MatchContext::new(), "Main.Rs"));
+
+        // with negated patterns only duplicates go
+        let patterns: Vec<String> = ["*.rs", "src/*.rs", "!target/*", "!target/*"].iter().map(|p| p.to_string()).collect();
+        let mut glob_list = GlobList::build(&patterns).unwrap();
+        assert_eq!(glob_list.optimize(), vec![crate::EntryId(2)]);
+        assert!(glob_list.any_match("src/lib.rs") && !glob_list.any_match("target/gen.rs"));
+        glob_list.add(Glob::new("target/keep.rs").unwrap());
+        assert!(glob_list.any_match("target/keep.rs"));
+    }
+
+    #[test]
+    fn glob_list_none_match_and_count_matches() {
+        let mut glob_list = GlobList::new();
+        glob_list.add(Glob::new("*spam*").unwrap());
+        glob_list.add(Glob::builder("*FREE*").case_insensitive(true).build().unwrap());
+        glob_list.add(Glob::new("*!!!").unwrap());
+        assert_eq!(glob_list.count_matches("free spam!!!"), 3);
+        assert_eq!(glob_list.count_matches("Free money"), 1);
+        assert_eq!(glob_list.count_matches("hello"), 0);
+        assert!(glob_list.none_match("hello") && !glob_list.none_match("spam"));
+        assert!(GlobList::new().none_match("anything"));
+    }
+
+    #[test]
+    fn glob_list_first_match_ordered() {
+        use crate::MatchOutcome;
+        let mut rules = GlobList::new();
+        rules.add_negated(Glob::builder("ADMIN/*").case_insensitive(true).build().unwrap());
+        let public = rules.add(Glob::new("admin/public/*").unwrap());
+        let everything = rules.add(Glob::new("*").unwrap());
+        assert!(matches!(rules.first_match_ordered("admin/public/index.html"), MatchOutcome::Excluded(m) if m.id.as_usize() == 0));
+        assert!(matches!(rules.first_match_ordered("docs/index.html"), MatchOutcome::Included(m) if m.id == everything));
+        // last match wins the other way around
+        assert!(matches!(rules.matched("admin/public/index.html"), MatchOutcome::Included(m) if m.id == everything));
+        assert!(rules.first_match("admin/public/a").is_some_and(|m| m.id != public));
+        assert_eq!(GlobList::new().first_match_ordered("a"), MatchOutcome::Unmatched);
+    }
+
+    #[test]
+    fn glob_list_negated_patterns() {
+        use crate::MatchOutcome;
+        let patterns : Vec<String> = vec!["*.rs", "!target/*", "target/keep/*"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let glob_list = GlobList::build(&patterns).unwrap();
+        assert!(glob_list.any_match("src/lib.rs"));
+        assert!(!glob_list.any_match("target/gen.rs"));
+        assert!(glob_list.any_match("target/keep/gen.rs"));
+        assert!(!glob_list.any_match("README.md"));
+        assert!(matches!(glob_list.matched("target/gen.rs"), MatchOutcome::Excluded(m) if m.pattern == "target/*" && m.negated));
+        assert!(matches!(glob_list.matched("src/lib.rs"), MatchOutcome::Included(m) if m.id.as_usize() == 0));
+        assert_eq!(glob_list.matched("README.md"), MatchOutcome::Unmatched);
+        assert!(glob_list.is_negated(glob_list.matches("target/gen.rs")[1].id));
+        let mut ctx = crate::MatchContext::new();
+        assert!(!glob_list.any_match_with(&mut ctx, "target/gen.rs"));
+
+        let patterns : Vec<String> = vec!["*.RS", "!*test*"].into_iter().map(String::from).collect();
+        let glob_list = GlobList::build_ignore_case(&patterns).unwrap();
+        assert!(glob_list.all_match("lib.rs") && !glob_list.all_match("lib_test.rs") && !glob_list.all_match("lib.c"));
+        let combined = GlobList::combine(vec![GlobList::build(&["*".to_string()]).unwrap(), glob_list]);
+        assert!(combined.any_match("lib.c") && !combined.any_match("TEST.rs"));
+        assert_eq!(GlobList::build(&["![".to_string()]).unwrap_err(), crate::GlobError::UnterminatedClass { position: 1 });
+    }
+
+    #[test]
+    fn glob_list_combine_keeps_order_and_renumbers() {
+        let mut first = GlobList::new();
+        first.add_ignore_case(GlobIgnoreCase::build("a*").unwrap());
+        first.add_case_sensitive(GlobCaseSensitive::build("b*").unwrap());
+        let mut second = GlobList::new();
+        second.add_ignore_case(GlobIgnoreCase::build("c*").unwrap());
+        let combined = GlobList::combine(vec![first, second]);
+        let ids : Vec<_> = combined.iter().map(|(id, _)| id.as_usize()).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+        let values = ["A", "b", "C"];
+        assert!(combined.iter().zip(values.iter()).all(|((_, e), v)| e.is_match(v)));
+    }
+
+    #[test]
+    fn glob_list_reorder_by_selectivity_keeps_insertion_order_visible() {
+        let patterns : Vec<String> = vec!["exact.txt", "*.longer_extension", "*", "*.rs"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut glob_list = GlobList::build(&patterns).unwrap();
+        glob_list.reorder_by_selectivity();
+        let internal : Vec<_> = glob_list.case_sensitive_patterns.iter().map(|p| format!("{:?}", p.0.pattern)).collect();
+        assert_eq!(internal, vec![
+            "MatchAny",
+            "MatchEnd(\".rs\")",
+            "MatchEnd(\".longer_extension\")",
+            "MatchFull(\"exact.txt\")"]);
+        let order : Vec<_> = glob_list.iter().map(|(id, e)| (id.as_usize(), e.is_match("exact.txt"))).collect();
+        assert_eq!(order, vec![(0, true), (1, false), (2, true), (3, false)]);
+        assert!(glob_list.any_match("main.rs"));
+        assert!(!glob_list.all_match("main.rs"));
+    }
+
+    #[test]
+    fn glob_list_reorder_by_selectivity_uses_hit_rate() {
+        let patterns : Vec<String> = vec!["*.a", "*.bb"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut glob_list = GlobList::build_ignore_case(&patterns).unwrap();
+        glob_list.set_hit_tracking(true);
+        for _ in 0..10 {
+            assert!(glob_list.any_match("x.bb"));
+        }
+        glob_list.reorder_by_selectivity();
+        assert!(glob_list.ignore_case_patterns[0].is_match("x.bb"));
+        assert!(matches!(glob_list.iter().next(), Some((_, e)) if e.is_match("x.a")));
+    }
+
+    #[test]
+    fn case_sensitive_patterns_checked_first() {
+        use std::sync::atomic::Ordering;
+        let mut list = GlobList::new();
+        list.add(Glob::new("*.rs").unwrap());
+        list.add(Glob::builder("*.MD").case_insensitive(true).build().unwrap());
+        list.set_hit_tracking(true);
+        assert!(list.any_match("lib.rs"));
+        assert!(!list.all_match("README.md"));
+        // decided by the case sensitive pattern alone, the ignore case one was never tried
+        assert_eq!(list.ignore_case_hits[0].checked.load(Ordering::Relaxed), 0);
+        assert!(list.any_match("README.md"));
+        assert_eq!(list.ignore_case_hits[0].checked.load(Ordering::Relaxed), 1);
+        assert_eq!(list.ignore_case_hits[0].hits.load(Ordering::Relaxed), 1);
+        assert!(GlobList::from_patterns(Vec::new(), vec![GlobIgnoreCase::build("*.MD").unwrap()]).all_match("notes.md"));
+    }
+
+    #[test]
+    fn can_match_len() {
+        let gp = crate::build_glob_pattern("*").unwrap();
+        assert!(gp.can_match_len(0) && gp.can_match_len(1000));
+        let gp = crate::build_glob_pattern("test").unwrap();
+        assert!(!gp.can_match_len(3) && gp.can_match_len(4) && !gp.can_match_len(5));
+        let gp = crate::build_glob_pattern("*.rs").unwrap();
+        assert!(!gp.can_match_len(2) && gp.can_match_len(3) && gp.can_match_len(300));
+        let gp = crate::build_glob_pattern("a*a").unwrap();
+        assert!(!gp.can_match_len(1) && gp.can_match_len(2));
+        let gp = crate::build_glob_pattern("ab*cd*ef").unwrap();
+        assert_eq!(gp.min_len(), 6);
+        assert_eq!(gp.max_len(), None);
+        assert!(!gp.can_match_len(5) && gp.can_match_len(6));
+    }
+
+    #[test]
+    fn anchoring_modes() {
+        let pattern = GlobCaseSensitive::build("foo").unwrap();
+        assert!(pattern.match_full("foo") && !pattern.match_full("foobar"));
+        assert!(pattern.match_prefix("foobar") && !pattern.match_prefix("barfoo"));
+        assert!(pattern.match_suffix("barfoo") && !pattern.match_suffix("foobar"));
+        assert!(pattern.match_within("a foo b") && !pattern.match_within("a fo o b"));
+
+        let pattern = GlobCaseSensitive::build("src/*.rs").unwrap();
+        assert!(pattern.match_prefix("src/lib.rs.bak"));
+        assert!(!pattern.match_full("src/lib.rs.bak"));
+        assert!(pattern.match_suffix("crate/src/lib.rs"));
+        assert!(!pattern.match_suffix("crate/src/lib.rs.bak"));
+        assert!(pattern.match_within("crate/src/lib.rs.bak"));
+        assert!(!pattern.match_within("crate/lib.rs/src"));
+
+        let pattern = GlobCaseSensitive::build("*.*.test.cs").unwrap();
+        assert!(pattern.match_prefix("startling.magic.test.cs.orig"));
+        assert!(pattern.match_within("x startling.magic.test.cs y"));
+        assert!(!pattern.match_suffix("startling.magic.test.cs y"));
+
+        let pattern = GlobIgnoreCase::build("ERROR*timeout").unwrap();
+        assert!(pattern.match_within("12:00 error: connection timeout after 30s"));
+        assert!(!pattern.match_prefix("12:00 error: connection timeout after 30s"));
+    }
+
+    #[test]
+    fn captures() {
+        let pattern = GlobCaseSensitive::build("hello*world").unwrap();
+        let captures = pattern.captures("hello nice world").unwrap();
+        assert_eq!(captures.get(0), Some(" nice "));
+        assert_eq!(captures.range(0), Some(5..11));
+        assert_eq!(captures.len(), 1);
+        assert!(pattern.captures("hello nice word").is_none());
+
+        let pattern = GlobCaseSensitive::build("*.*.test.cs").unwrap();
+        let captures = pattern.captures("startling.magic.test.cs").unwrap();
+        assert_eq!(captures.iter().collect::<Vec<_>>(), vec!["startling", "magic"]);
+
+        let pattern = GlobCaseSensitive::build("*").unwrap();
+        assert_eq!(pattern.captures("all of it").unwrap().get(0), Some("all of it"));
+        let pattern = GlobCaseSensitive::build("exact").unwrap();
+        assert!(pattern.captures("exact").unwrap().is_empty());
+    }
+
+    #[test]
+    fn glob_captures_star_and_question_mark() {
+        let glob = crate::Glob::new("hello*world").unwrap();
+        let captures = glob.captures("hello nice world").unwrap();
+        assert_eq!((captures.get(0), captures.range(0)), (Some(" nice "), Some(5..11)));
+        let glob = crate::Glob::builder("IMG_????*.JPG").case_insensitive(true).build().unwrap();
+        let captures = glob.captures("img_2024-summer.jpg").unwrap();
+        assert_eq!(captures.iter().collect::<Vec<_>>(), vec!["2", "0", "2", "4", "-summer"]);
+        assert_eq!(captures.range(4), Some(8..15));
+    }
+
+    #[test]
+    fn find_match_range() {
+        let glob = crate::Glob::new("*.rs").unwrap();
+        assert_eq!(glob.find("main.rs"), Some(4..7));
+        assert_eq!(glob.find("main.c"), None);
+        assert_eq!(crate::Glob::new("src/*").unwrap().find("src/lib.rs"), Some(0..4));
+        assert_eq!(crate::Glob::new("*.*.test.cs").unwrap().find("startling.magic.test.cs"), Some(9..23));
+        assert_eq!(crate::Glob::new("a*a").unwrap().find("a"), None);
+        assert_eq!(crate::Glob::new("a*a").unwrap().find("aa"), Some(0..2));
+        assert_eq!(crate::Glob::new("*").unwrap().find("anything"), Some(0..0));
+        assert_eq!(crate::Glob::new("*v?.*").unwrap().find("app-v2.1"), Some(4..7));
+        assert_eq!(crate::Glob::new("*.{rs,toml}").unwrap().find("Cargo.toml"), Some(5..10));
+        let glob = GlobIgnoreCase::build("*ERROR:*").unwrap();
+        assert_eq!(glob.find("12:00 error: timeout"), Some(6..12));
+    }
+
+    #[test]
+    fn captures_ignore_case_refer_to_original_value() {
+        let pattern = GlobIgnoreCase::build("HELLO*").unwrap();
+        assert_eq!(pattern.captures("hello World").unwrap().get(0), Some(" World"));
+    }
+
+    #[test]
+    fn captures_as() {
+        let pattern = GlobCaseSensitive::build("build-*.log").unwrap();
+        assert_eq!(pattern.captures_as::<u32>("build-42.log"), Some(Ok(vec![42])));
+        assert!(pattern.captures_as::<u32>("build-.log").unwrap().is_err());
+        assert!(pattern.captures_as::<u32>("build-42.txt").is_none());
+
+        let pattern = GlobCaseSensitive::build("*x*+*").unwrap();
+        let captures = pattern.captures("3x4+5").unwrap();
+        assert_eq!(captures.parse::<u8>(1), Ok(4));
+        assert_eq!(captures.parse_all::<u8>(), Ok(vec![3, 4, 5]));
+        assert_eq!(captures.parse::<u8>(3), Err(crate::CaptureParseError::NoSuchCapture(3)));
+        let err = pattern.captures("3xfour+5").unwrap().parse_all::<u8>().unwrap_err();
+        assert!(matches!(&err, crate::CaptureParseError::Invalid { index: 1, capture, .. } if capture == "four"));
+        assert_eq!(err.to_string(), "could not parse capture 1 ('four'): invalid digit found in string");
+    }
+
+    #[test]
+    fn match_context_reuses_buffers() {
+        let mut ctx = crate::MatchContext::new();
+        let pattern = GlobIgnoreCase::build("build-*.LOG").unwrap();
+        assert!(pattern.is_match_with(&mut ctx, "build-1234.log"));
+        let capacity = ctx.folded.capacity();
+        assert!(pattern.is_match_with(&mut ctx, "BUILD-7.log"));
+        assert!(!pattern.is_match_with(&mut ctx, "build-7.txt"));
+        assert_eq!(ctx.folded.capacity(), capacity);
+        assert_eq!(pattern.captures_with(&mut ctx, "Build-42.log").unwrap().parse::<u32>(0), Ok(42));
+        let pattern = GlobCaseSensitive::build("*.*.test.cs").unwrap();
+        let captures = pattern.captures_with(&mut ctx, "startling.magic.test.cs").unwrap();
+        assert_eq!(captures, pattern.captures("startling.magic.test.cs").unwrap());
+
+        let glob_list = GlobList::build_ignore_case(&["*.RS".to_string(), "src/*".to_string()]).unwrap();
+        assert!(glob_list.any_match_with(&mut ctx, "lib.rs"));
+        assert!(glob_list.all_match_with(&mut ctx, "src/lib.rs"));
+        assert!(!glob_list.all_match_with(&mut ctx, "src/lib.c"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut glob_list = GlobList::build(&["src/**/*.rs".to_string(), "!src/gen/*".to_string()]).unwrap();
+        glob_list.add(Glob::builder("*.{TOML,LOCK}").case_insensitive(true).build().unwrap());
+        let json = serde_json::to_string(&glob_list).unwrap();
+        let glob_list: GlobList = serde_json::from_str(&json).unwrap();
+        assert!(glob_list.any_match("src/a/lib.rs") && glob_list.any_match("Cargo.toml"));
+        assert!(!glob_list.any_match("src/gen/out.rs"));
+        assert_eq!(glob_list.first_match("Cargo.lock").unwrap().pattern, "*.{TOML,LOCK}");
+
+        let pattern = GlobIgnoreCase::build("log-??.[tT]xt").unwrap();
+        let pattern: GlobIgnoreCase = serde_json::from_str(&serde_json::to_string(&pattern).unwrap()).unwrap();
+        assert!(pattern.is_match("LOG-01.TXT"));
+    }
+
+    #[test]
+    fn ignore_case_matches_per_char() {
+        let patterns = ["*.TXT", "log-*", "a*b*c", "*mid*", "Data_??.[cC]sv", "{*.JPG,*.png}", "exact", "src/**/*.rS"];
+        let values = ["notes.txt", "LOG-1", "aXbYc", "the middle", "data_01.CSV", "photo.jpg", "EXACT", "src/a/lib.Rs", "nope"];
+        for pattern in patterns {
+            let glob = GlobIgnoreCase::build(pattern).unwrap();
+            for value in values {
+                assert_eq!(glob.is_match(value), crate::glob_match_prebuilt(&glob.0.pattern, &crate::fold_case(value)),
+                           "{} on {}", pattern, value);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "unicode", not(feature = "full-case-folding")))]
+    #[test]
+    fn ignore_case_simple_folding() {
+        assert!(GlobIgnoreCase::build("STRAẞE").unwrap().is_match("straße"));
+        assert!(!GlobIgnoreCase::build("strasse").unwrap().is_match("straße"));
+        assert!(GlobIgnoreCase::build("ΟΔΥΣΣΕΥΣ").unwrap().is_match("οδυσσευς"));
+        assert!(GlobIgnoreCase::build("*\u{212A}").unwrap().is_match("kk")); // Kelvin sign
+        // no Turkish dotless i matching a dotted one
+        assert!(!GlobIgnoreCase::build("ı*").unwrap().is_match("i"));
+        assert!(!GlobIgnoreCase::build("i*").unwrap().is_match("\u{130}"));
+    }
+
+    #[cfg(feature = "full-case-folding")]
+    #[test]
+    fn ignore_case_full_folding() {
+        // `ß` and `ẞ` fold to `ss`
+        assert!(GlobIgnoreCase::build("STRAẞE").unwrap().is_match("strasse"));
+        assert!(!GlobIgnoreCase::build("ı*").unwrap().is_match("i"));
+        assert!(GlobIgnoreCase::build("stra?e").unwrap().is_match("STRAßE"));
+        assert!(GlobIgnoreCase::build("*ss*").unwrap().is_match("Straße"));
+        assert!(GlobIgnoreCase::build("[a-z]tra?e").unwrap().is_match("straße"));
+    }
+
+    #[test]
+    fn ascii_case_insensitive() {
+        let glob = Glob::builder("*.Log.[a-c]?").ascii_case_insensitive(true).build().unwrap();
+        assert!(glob.is_case_insensitive() && glob.is_ascii_case_insensitive());
+        assert!(glob.is_match("APP.LOG.B1") && glob.is_match("app.log.c2"));
+        assert!(glob.is_match_chunks(&["APP.", "lOG.A", "9"]));
+        assert!(glob.is_match_with(&mut crate::MatchContext::new(), "app.LOG.a1"));
+        assert_eq!(glob.captures("Ärger.LOG.Bx").unwrap().iter().collect::<Vec<_>>(), vec!["Ärger", "B", "x"]);
+        assert_eq!(Glob::builder("LOG").ascii_case_insensitive(true).build().unwrap().find("a log"), None);
+
+        // only ASCII letters are folded
+        let glob = Glob::builder("ÄRGER*").ascii_case_insensitive(true).build().unwrap();
+        assert!(glob.is_match("ÄRGER.txt") && glob.is_match("ÄRger") && !glob.is_match("ärger"));
+
+        let mut glob_list = GlobList::new();
+        glob_list.add(glob);
+        glob_list.add(Glob::builder("Cargo.*").case_insensitive(true).build().unwrap());
+        assert!(glob_list.any_match("Ärger") && glob_list.any_match("CARGO.LOCK"));
+        assert!(!glob_list.any_match("ärger"));
+        assert!(glob_list.any_match_with(&mut crate::MatchContext::new(), "ÄRGERLICH"));
+    }
+
+    #[test]
+    fn parse_and_display() {
+        use std::convert::TryFrom;
+        let glob: crate::GlobIgnoreCase = "*.JPG".parse().unwrap();
+        assert!(glob.is_match("photo.jpg"));
+        assert_eq!(glob.to_string(), "*.JPG");
+        let glob = crate::GlobCaseSensitive::try_from("src/*.rs").unwrap();
+        assert_eq!(format!("{}", glob), "src/*.rs");
+        assert_eq!("a**".parse::<crate::Glob>().unwrap_err(), crate::GlobError::EmptyWildcardSegment { position: 2 });
+    }
+
+    #[test]
+    fn structural_equality() {
+        use std::collections::HashSet;
+        let a = crate::GlobIgnoreCase::build("*.JPG").unwrap();
+        let b = crate::GlobIgnoreCase::build("*.jpg").unwrap();
+        assert_eq!(a, b);
+        assert_ne!(crate::Glob::new("*.JPG").unwrap(), crate::Glob::new("*.jpg").unwrap());
+        assert_ne!(crate::Glob::new("*.jpg").unwrap(), b.as_glob().clone());
+        let set: HashSet<_> = vec![a, b, crate::GlobIgnoreCase::build("*.png").unwrap()].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert_eq!(crate::build_glob_pattern("{a,b}*").unwrap(), crate::build_glob_pattern("{a,b}*").unwrap());
+    }
+
+    #[test]
+    fn glob_list_dedup() {
+        let patterns: Vec<String> = ["*.rs", "!target/*", "*.rs", "*.toml", "!target/*", "*.toml"]
+            .iter().map(|p| p.to_string()).collect();
+        let mut list = crate::GlobList::build(&patterns).unwrap();
+        list.set_hit_tracking(true);
+        let removed = list.dedup();
+        assert_eq!(removed.iter().map(|id| id.as_usize()).collect::<Vec<_>>(), vec![0, 1, 3]);
+        let left: Vec<_> = list.iter().map(|(id, e)| (id.as_usize(), e.as_glob().as_str().to_string())).collect();
+        assert_eq!(left, vec![(2, "*.rs".to_string()), (4, "target/*".to_string()), (5, "*.toml".to_string())]);
+        assert!(list.any_match("src/lib.rs"));
+        assert!(!list.any_match("target/gen.rs"));
+        assert!(list.is_negated(crate::EntryId(4)));
+        assert!(list.dedup().is_empty());
+    }
+
+    #[test]
+    fn glob_list_remove_retain_clear() {
+        let patterns: Vec<String> = ["target/*", "*.rs", "!target/*", "*.toml"].iter().map(|p| p.to_string()).collect();
+        let mut list = crate::GlobList::build(&patterns).unwrap();
+        list.add(Glob::builder("*.MD").case_insensitive(true).build().unwrap());
+        assert_eq!(list.len(), 5);
+        assert!(!list.any_match("target/gen.rs"));
+
+        assert_eq!(list.remove("!target/*"), vec![crate::EntryId(2)]);
+        assert!(list.remove("*.txt").is_empty());
+        assert_eq!(list.len(), 4);
+        assert!(list.any_match("target/gen.rs") && list.any_match("README.md"));
+
+        let removed = list.retain(|_, e| e.is_case_sensitive());
+        assert_eq!(removed, vec![crate::EntryId(4)]);
+        assert!(!list.any_match("README.md") && list.any_match("Cargo.toml"));
+        let left: Vec<_> = list.iter().map(|(id, e)| (id.as_usize(), e.as_glob().as_str())).collect();
+        assert_eq!(left, vec![(0, "target/*"), (1, "*.rs"), (3, "*.toml")]);
+
+        list.clear();
+        assert!(list.is_empty() && list.iter().next().is_none() && !list.any_match("lib.rs"));
+        assert_eq!(list.add(Glob::new("*.rs").unwrap()).as_usize(), 5);
+    }
+
+    #[test]
+    fn unanchored_matches_anywhere() {
+        let unanchored = |p: &str| crate::Glob::builder(p).unanchored(true).build().unwrap();
+        let glob = unanchored("foo*bar");
+        assert!(glob.is_match("log: foo went to the bar today"));
+        assert!(glob.is_match("foobar"));
+        assert!(!glob.is_match("bar then foo"));
+        assert_eq!(glob.find("log: foo went to the bar today"), Some(5..24));
+        assert!(unanchored("ERROR").is_match("12:00 ERROR timeout"));
+        assert!(unanchored("a*a").is_match("xaax") && !unanchored("a*a").is_match("xax"));
+        assert!(unanchored("*.rs").is_match("src/lib.rs.bak"));
+        assert!(unanchored("id=[0-9]").is_match_bytes(b"GET /?id=4&x"));
+        assert!(unanchored("{cat,dog}s").is_match("raining cats and dogs"));
+        let glob = crate::Glob::builder("src/*.rs").unanchored(true).literal_separator(true).build().unwrap();
+        assert!(glob.is_match("see src/lib.rs:12"));
+        assert!(!glob.is_match("see src/a/lib.rs:12"));
+        assert!(crate::Glob::builder("ERROR").unanchored(true).case_insensitive(true).build().unwrap().is_match("an error"));
+    }
+
+    #[test]
+    fn find_iter_unanchored() {
+        let glob = crate::Glob::builder("ERROR*timeout").unanchored(true).build().unwrap();
+        let line = "ERROR db timeout, WARN slow, ERROR cache timeout!";
+        let found: Vec<_> = glob.find_iter(line).map(|r| &line[r]).collect();
+        assert_eq!(found, vec!["ERROR db timeout", "ERROR cache timeout"]);
+        let glob = crate::Glob::builder("{cat,dog}s").unanchored(true).case_insensitive(true).build().unwrap();
+        assert_eq!(glob.find_iter("Dogs, cats and DOGS").collect::<Vec<_>>(), vec![0..4, 6..10, 15..19]);
+        let glob = crate::Glob::builder("id=[0-9]").unanchored(true).build().unwrap();
+        assert_eq!(glob.find_iter("id=1 id=x id=2").collect::<Vec<_>>(), vec![0..4, 10..14]);
+        // anchored patterns match the whole value at most once
+        let glob = crate::Glob::new("*.rs").unwrap();
+        assert_eq!(glob.find_iter("a.rs b.rs").collect::<Vec<_>>(), vec![6..9]);
+        assert_eq!(crate::Glob::builder("*").unanchored(true).build().unwrap().find_iter("ab").count(), 3);
+    }
+
+    #[test]
+    fn glob_list_filter_and_partition() {
+        let patterns: Vec<String> = ["*.RS", "!target/*"].iter().map(|p| p.to_string()).collect();
+        let list = crate::GlobList::build_ignore_case(&patterns).unwrap();
+        let paths = ["src/lib.rs".to_string(), "target/gen.rs".to_string(), "Cargo.toml".to_string(), "main.Rs".to_string()];
+        let kept: Vec<&str> = list.filter(paths.iter().map(String::as_str)).collect();
+        assert_eq!(kept, vec!["src/lib.rs", "main.Rs"]);
+        let (matched, rest) = list.partition(paths.iter().map(String::as_str));
+        assert_eq!(matched, vec!["src/lib.rs", "main.Rs"]);
+        assert_eq!(rest, vec!["target/gen.rs", "Cargo.toml"]);
+    }
+
+    #[test]
+    fn multipart_wildcards_can_match_nothing() {
+        for pattern in ["a*b*c", "*a*b*", "a*b*c*", "*a*b*c"].iter() {
+            assert!(crate::glob_match_case_sensitive(pattern, "abc").unwrap(), "{}", pattern);
+            assert!(crate::glob_match(pattern, "ABC").unwrap(), "{}", pattern);
+            assert!(crate::Glob::new(pattern).unwrap().is_match_bytes(b"abc"), "{}", pattern);
+        }
+        assert!(crate::glob_match_case_sensitive("*ab*ab*", "aabab").unwrap());
+        assert!(!crate::glob_match_case_sensitive("*ab*ab*", "abba").unwrap());
+        assert!(!crate::glob_match_case_sensitive("a*bc*bc", "abc").unwrap());
+        assert!(crate::glob_match_case_sensitive("a*bc*bc", "abcbc").unwrap());
+    }
+
+    #[test]
+    fn literal_prefix_and_suffix() {
+        let cases = [
+            ("src/foo*", "src/foo", ""),
+            ("*.rs", "", ".rs"),
+            ("Cargo.toml", "Cargo.toml", "Cargo.toml"),
+            ("a*b*c", "a", "c"),
+            ("*a*", "", ""),
+            ("src/**/*.rs", "src/", ".rs"),
+            ("log-??.txt", "log-", ".txt"),
+            ("{src/lib,src/main}.rs", "src/", ".rs"),
+            ("{a,b}", "", ""),
+            ("{åx,åy}*", "å", ""),
+            ("*", "", ""),
+        ];
+        for &(pattern, prefix, suffix) in cases.iter() {
+            let built = crate::build_glob_pattern(pattern).unwrap();
+            assert_eq!((built.literal_prefix(), built.literal_suffix()), (prefix, suffix), "{}", pattern);
+        }
+        assert_eq!(GlobIgnoreCase::build("SRC/*").unwrap().as_glob().pattern().literal_prefix(), "src/");
+        assert_eq!(Glob::new("(?i:a)b*").unwrap().pattern().literal_prefix(), "");
+        assert_eq!(Glob::builder("src/*").unanchored(true).build().unwrap().pattern().literal_prefix(), "");
+    }
+
+    #[test]
+    fn literal_start_and_end_do_not_overlap() {
+        for pattern in ["a*a", "xx*x", "ab*ba"].iter() {
+            assert_eq!(crate::build_glob_pattern(pattern).unwrap().min_len(), pattern.len() - 1, "{}", pattern);
+        }
+        assert!(!crate::glob_match_case_sensitive("a*a", "a").unwrap());
+        assert!(crate::glob_match_case_sensitive("a*a", "aa").unwrap());
+        assert!(!crate::glob_match_case_sensitive("xx*x", "xx").unwrap());
+        assert!(crate::glob_match_case_sensitive("xx*x", "xxx").unwrap());
+        assert!(!crate::glob_match_case_sensitive("ab*ba", "aba").unwrap());
+        assert!(!crate::glob_match("A*A", "a").unwrap());
+        assert!(!crate::Glob::new("a*a").unwrap().is_match_bytes(b"a"));
+        assert!(!crate::glob_match_prebuilt_chunks(&crate::build_glob_pattern("a*a").unwrap(), &["a"]));
+        assert!(crate::glob_match_prebuilt_chunks(&crate::build_glob_pattern("a*a").unwrap(), &["a", "a"]));
+        let captures = crate::Glob::new("a*a").unwrap().captures("aa").unwrap();
+        assert_eq!(captures.get(0), Some(""));
+    }
+
+    #[test]
+    fn match_bytes() {
+        let pattern = crate::build_glob_pattern("GET /*?id=[0-9]*").unwrap();
+        assert!(crate::glob_match_prebuilt_bytes(&pattern, b"GET /users?id=42"));
+        assert!(crate::glob_match_prebuilt_bytes(&pattern, b"GET /\xFF\xFE?id=1"));
+        assert!(!crate::glob_match_prebuilt_bytes(&pattern, b"GET /users?id=x"));
+
+        let glob = Glob::new("*.log").unwrap();
+        assert!(glob.is_match_bytes(b"\xC3(.log") && !glob.is_match_bytes(b"a.LOG"));
+        assert!(GlobCaseSensitive::build("a?b").unwrap().is_match_bytes(b"a\xFFb"));
+        assert!(GlobCaseSensitive::build("a?b").unwrap().is_match_bytes("aåb".as_bytes()));
+        assert!(GlobIgnoreCase::build("*.LOG").unwrap().is_match_bytes(b"\xFF.log"));
+        assert!(GlobIgnoreCase::build("?.LOG").unwrap().is_match_bytes(b"\xFF.log"));
+        assert!(Glob::builder("*.LOG").ascii_case_insensitive(true).build().unwrap().is_match_bytes(b"\xFF.log"));
+    }
+
+    #[test]
+    fn match_path() {
+        use std::path::Path;
+        let pattern = Glob::builder("src/**/*.rs").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match_path(Path::new("src/a/lib.rs")));
+        assert!(!pattern.is_match_path(Path::new("tests/lib.rs")));
+        let pattern = GlobIgnoreCase::build("*.RS").unwrap();
+        assert!(pattern.is_match_path(Path::new("src/lib.rs")));
+        #[cfg(windows)]
+        assert!(GlobCaseSensitive::build("src/*.rs").unwrap().is_match_path(Path::new(r"src\lib.rs")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn match_path_not_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        use std::path::Path;
+        let path = Path::new(OsStr::from_bytes(b"logs/\xFFold.log"));
+        assert!(GlobCaseSensitive::build("logs/*.log").unwrap().is_match_path(path));
+        assert!(GlobCaseSensitive::build("logs/?old.log").unwrap().is_match_path(path));
+        assert!(GlobIgnoreCase::build("LOGS/*.LOG").unwrap().is_match_path(path));
+    }
+
+    #[test]
+    fn double_star_matches_directories() {
+        let pattern = Glob::builder("src/**/*.rs").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("src/lib.rs"));
+        assert!(pattern.is_match("src/a/b/lib.rs"));
+        assert!(!pattern.is_match("src/a/lib.c"));
+        assert!(!pattern.is_match("srcx/lib.rs"));
+        assert!(!pattern.is_match("lib.rs"));
+        assert_eq!(pattern.captures("src/a/b/lib.rs").unwrap().get(1), Some("lib"));
+
+        let pattern = Glob::builder("**/test_*").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("test_a") && pattern.is_match("a/b/test_a"));
+        assert!(!pattern.is_match("a/test_b/c") && !pattern.is_match("atest_a"));
+
+        let pattern = Glob::builder("target/**").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("target/debug/globber") && !pattern.is_match("src/target"));
+
+        // without a literal separator `*` crosses directories, `**` still only matches whole components
+        let pattern = Glob::new("src/**/*.rs").unwrap();
+        assert!(pattern.is_match("src/a/b/lib.rs"));
+        assert!(Glob::new("**").unwrap().is_match("a/b"));
+        assert!(!Glob::new("**/b").unwrap().is_match("ab"));
+    }
+
+    #[test]
+    fn double_star_can_follow_double_star() {
+        for literal_separator in [false, true] {
+            let build = |pattern| Glob::builder(pattern).literal_separator(literal_separator).build().unwrap();
+            let pattern = build("**/**");
+            assert!(pattern.is_match("a") && pattern.is_match("a/b/c"));
+            let pattern = build("**/**/x");
+            assert!(pattern.is_match("x") && pattern.is_match("a/b/x"));
+            assert!(!pattern.is_match("a/bx"));
+            let pattern = build("a/**/**/b");
+            assert!(pattern.is_match("a/b") && pattern.is_match("a/c/b") && pattern.is_match("a/c/d/b"));
+            assert!(!pattern.is_match("a/cb") && !pattern.is_match("b"));
+        }
+        assert_eq!(crate::build_glob_pattern("**/***").unwrap_err(), crate::GlobError::EmptyWildcardSegment { position: 5 });
+    }
+
+    #[test]
+    fn literal_separator_stops_wildcards_at_slash() {
+        let pattern = Glob::builder("*.rs").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("lib.rs"));
+        assert!(!pattern.is_match("src/lib.rs"));
+        assert!(Glob::new("*.rs").unwrap().is_match("src/lib.rs"));
+
+        let pattern = Glob::builder("src?[!x]*").literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("src_ab"));
+        assert!(!pattern.is_match("src/ab") && !pattern.is_match("src_/b"));
+
+        let pattern = Glob::builder("*").literal_separator(true).case_insensitive(true).build().unwrap();
+        assert!(pattern.is_match("Cargo.toml") && !pattern.is_match("src/lib.rs"));
+        assert!(pattern.match_within("src/lib.rs"));
+    }
+
+    #[test]
+    fn wildcard_min_len() {
+        let pattern = Glob::builder("*.rs").wildcard_min_len(1).build().unwrap();
+        assert!(pattern.is_match("a.rs") && !pattern.is_match(".rs"));
+        let pattern = Glob::builder("a*b*c").wildcard_min_len(1).build().unwrap();
+        assert!(!pattern.is_match("abc") && !pattern.is_match("axbc") && pattern.is_match("axbxc"));
+        // each of the `?` a `*` is built with is a wildcard of its own
+        assert_eq!(pattern.captures("axxbyc").unwrap().iter().collect::<Vec<_>>(), vec!["x", "x", "y", ""]);
+        let pattern = Glob::builder("src/**/*").wildcard_min_len(2).literal_separator(true).build().unwrap();
+        assert!(pattern.is_match("src/ab") && pattern.is_match("src/a/bc") && !pattern.is_match("src/a") && !pattern.is_match("src/a/b"));
+        assert!(Glob::builder("*").wildcard_min_len(1).build().unwrap().is_match_bytes(b"x"));
+        assert!(!Glob::builder("*").wildcard_min_len(1).build().unwrap().is_match(""));
+    }
+
+    #[test]
+    fn complexity_limits() {
+        use crate::GlobError;
+        assert_eq!(Glob::builder("src/*.rs").max_pattern_len(7).build().unwrap_err(), GlobError::PatternTooLong { max: 7 });
+        assert!(Glob::builder("src/*.rs").max_pattern_len(8).build().is_ok());
+        assert_eq!(crate::build_glob_pattern("src/*.rs").unwrap().segment_count(), 3);
+        assert_eq!(crate::build_glob_pattern("a*b?c[de]").unwrap().segment_count(), 6);
+        assert_eq!(crate::build_glob_pattern("*a*b*").unwrap().segment_count(), 5);
+        assert_eq!(crate::build_glob_pattern("{a,b}{c,d}").unwrap().segment_count(), 4);
+        assert_eq!(Glob::builder("a*b*c").max_segments(4).build().unwrap_err(), GlobError::TooManySegments { max: 4 });
+        assert!(Glob::builder("a*b*c").max_segments(5).build().is_ok());
+        assert_eq!(Glob::builder("{a,b}{c,d}").max_segments(3).build().unwrap_err(), GlobError::TooManySegments { max: 3 });
+        // rejected while expanding, the 2^40 alternatives are never built
+        let braces = "{a,b}".repeat(40);
+        assert_eq!(Glob::builder(&braces).max_segments(1000).build().unwrap_err(), GlobError::TooManySegments { max: 1000 });
+    }
+
+    #[test]
+    fn match_with_budget() {
+        use crate::BudgetExceeded;
+        let pattern = Glob::new("*.rs").unwrap();
+        assert_eq!(pattern.is_match_with_budget("lib.rs", 6), Ok(true));
+        assert_eq!(pattern.is_match_with_budget("lib.rs", 5), Err(BudgetExceeded { max_steps: 5 }));
+
+        let value = "a".repeat(5000);
+        for pattern in &["*a*a*a*a*b?", "{x,*a*a*a*?b}"] {
+            for &literal_separator in &[false, true] {
+                let pattern = Glob::builder(pattern).literal_separator(literal_separator).build().unwrap();
+                assert_eq!(pattern.is_match_with_budget(&value, 1000), Err(BudgetExceeded { max_steps: 1000 }));
+                assert_eq!(pattern.is_match_with_budget(&value, usize::MAX), Ok(pattern.is_match(&value)));
+            }
+        }
+        let pattern = Glob::builder("src/**/[a-z]?.rs").case_insensitive(true).build().unwrap();
+        for value in &["src/AB.rs", "src/x/y/ab.RS", "src/abc.rs", "lib/ab.rs"] {
+            assert_eq!(pattern.is_match_with_budget(value, 1000), Ok(pattern.is_match(value)), "{}", value);
+        }
+        let pattern = Glob::builder("*a*b").dfa(true).build().unwrap();
+        assert_eq!(pattern.is_match_with_budget("xaxb", 4), Ok(true));
+        assert!(pattern.is_match_with_budget("xaxb", 3).is_err());
+    }
+
+    #[test]
+    fn ignore_case_keeps_the_pattern_as_written() {
+        let pattern = GlobIgnoreCase::build("Photos/*.Jpg").unwrap();
+        assert_eq!(pattern.as_str(), "Photos/*.Jpg");
+        assert_eq!(pattern.to_string(), "Photos/*.Jpg");
+        // the value's own casing comes back in the captures, not the folded one
+        assert_eq!(pattern.captures("PHOTOS/Holiday.JPG").unwrap().iter().collect::<Vec<_>>(), vec!["Holiday"]);
+        let list = GlobList::build_ignore_case(&["*.RS".to_string()]).unwrap();
+        assert_eq!(list.iter().map(|(_, entry)| entry.as_str()).collect::<Vec<_>>(), vec!["*.RS"]);
+        assert_eq!(list.first_match("lib.rs").unwrap().pattern, "*.RS");
+    }
+
+    #[test]
+    fn turkic_case_fold() {
+        use crate::{CaseFold, GlobSet};
+        for &dfa in &[false, true] {
+            let pattern = Glob::builder("DOSYA-I*.txt").case_insensitive(true).case_fold(CaseFold::Turkic).dfa(dfa).build().unwrap();
+            assert!(pattern.is_match("dosya-ı1.txt") && pattern.is_match("DOSYA-I2.TXT"));
+            assert!(!pattern.is_match("dosya-i1.txt") && !pattern.is_match("DOSYA-İ1.txt"));
+            let pattern = Glob::builder("?stanbul").case_insensitive(true).case_fold(CaseFold::Turkic).dfa(dfa).build().unwrap();
+            assert!(pattern.is_match("İstanbul") && pattern.is_match("istanbul"));
+            let pattern = Glob::builder("[İ]ZM[I]R").case_insensitive(true).case_fold(CaseFold::Turkic).dfa(dfa).build().unwrap();
+            assert!(pattern.is_match("izmır") && pattern.is_match("İZMIR") && !pattern.is_match("IZMIR") && !pattern.is_match("izmir"));
+        }
+        let pattern = GlobIgnoreCase::build_with_fold("*İSTANBUL*", CaseFold::Turkic).unwrap();
+        assert!(pattern.is_match("istanbul.jpg") && !pattern.is_match("ıstanbul.jpg"));
+        assert_eq!(pattern.as_glob().case_fold(), CaseFold::Turkic);
+        assert_eq!(GlobIgnoreCase::build("*").unwrap().as_glob().case_fold(), CaseFold::Default);
+        assert_eq!(pattern.captures("İSTANBUL.jpg").unwrap().iter().collect::<Vec<_>>(), vec!["", ".jpg"]);
+        assert!(pattern.is_match_bytes("İstanbul".as_bytes()) && pattern.is_match_chunks(&["İst", "anbul"]));
+
+        // lists and sets fold each value the way each pattern does
+        let mut list = GlobList::new();
+        list.add_ignore_case(GlobIgnoreCase::build_with_fold("ı*", CaseFold::Turkic).unwrap());
+        list.add_ignore_case(GlobIgnoreCase::build("x*").unwrap());
+        assert!(list.any_match("Ilık") && !list.any_match("ilik") && list.any_match("X"));
+        let set = GlobSet::new(vec![Glob::builder("ı*").case_insensitive(true).case_fold(CaseFold::Turkic).build().unwrap()]);
+        assert!(set.is_match("Ilık") && !set.is_match("ilik"));
+
+        // without ignoring case it changes nothing
+        assert!(!Glob::builder("I").case_fold(CaseFold::Turkic).build().unwrap().is_match("ı"));
+    }
+
+    #[test]
+    fn case_insensitive_group() {
+        use crate::GlobError;
+        for &dfa in &[false, true] {
+            let pattern = Glob::builder("Assets/(?i:*.jpg)").dfa(dfa).build().unwrap();
+            assert!(pattern.is_match("Assets/a.JPG") && pattern.is_match("Assets/b.jpg") && pattern.is_match("Assets/c.JpG"));
+            assert!(!pattern.is_match("assets/a.jpg") && !pattern.is_match("Assets/a.png"));
+            let pattern = Glob::builder("(?i:[a-c]x)y").dfa(dfa).build().unwrap();
+            assert!(pattern.is_match("BXy") && pattern.is_match("axy") && !pattern.is_match("BXY") && !pattern.is_match("dxy"));
+            let pattern = Glob::builder("(?i:[!a])").dfa(dfa).build().unwrap();
+            assert!(!pattern.is_match("A") && pattern.is_match("b"));
+        }
+        let pattern = Glob::new("Assets/(?i:*.jpg)").unwrap();
+        assert_eq!(pattern.captures("Assets/Photo.JpG").unwrap().iter().collect::<Vec<_>>(), vec!["Photo"]);
+        assert_eq!(crate::build_glob_pattern(&pattern.pattern().to_string()).unwrap(), *pattern.pattern());
+        assert_eq!(pattern.to_regex_string(), r"(?s)^Assets/.*(?i:\.jpg)$");
+        // outside a group parentheses are literal
+        assert!(Glob::new("a)b").unwrap().is_match("a)b"));
+        assert!(Glob::new("(a)").unwrap().is_match("(a)"));
+        assert_eq!(Glob::new("a(?i:b").unwrap_err(), GlobError::UnterminatedGroup { position: 1 });
+        #[cfg(feature = "unicode")]
+        {
+            // the Kelvin sign folds to `k` too
+            assert!(Glob::new("(?i:k)").unwrap().is_match("\u{212A}"));
+            assert!(Glob::builder("(?i:k)").dfa(true).build().unwrap().is_match("\u{212A}"));
+        }
+    }
+
+    #[test]
+    fn question_mark_matches_one_char() {
+        let pattern = GlobCaseSensitive::build("file?.txt").unwrap();
+        assert!(pattern.is_match("file1.txt"));
+        assert!(pattern.is_match("fileå.txt"));
+        assert!(!pattern.is_match("file10.txt"));
+        assert!(!pattern.is_match("file.txt"));
+        let pattern = GlobCaseSensitive::build("*.?s").unwrap();
+        assert!(pattern.is_match("lib.rs") && pattern.is_match("a.b.ts") && !pattern.is_match("lib.rsx"));
+        let pattern = GlobIgnoreCase::build("LOG-??*.TXT").unwrap();
+        assert!(pattern.is_match("log-01.txt") && pattern.is_match("log-01-old.txt") && !pattern.is_match("log-1.txt"));
+        assert!(pattern.is_match_chunks(&["log-0", "1-old.t", "xt"]));
+        assert!(crate::build_glob_pattern("a?**").is_err());
+    }
+
+    #[test]
+    fn question_mark_spans_and_captures() {
+        let pattern = GlobCaseSensitive::build("v?.*.?").unwrap();
+        assert_eq!(pattern.match_spans("v1.22.3"), Some(vec![0..1, 2..3, 5..6]));
+        assert_eq!(pattern.captures("v1.22.3").unwrap().iter().collect::<Vec<_>>(), vec!["1", "22", "3"]);
+        let pattern = GlobCaseSensitive::build("??*").unwrap();
+        assert_eq!(pattern.captures("abcd").unwrap().iter().collect::<Vec<_>>(), vec!["a", "b", "cd"]);
+        let gp = crate::build_glob_pattern("a?c").unwrap();
+        assert_eq!((gp.min_len(), gp.max_len()), (3, Some(6)));
+        let pattern = GlobCaseSensitive::build("?oo").unwrap();
+        assert!(pattern.match_within("a food") && pattern.match_prefix("foo bar") && !pattern.match_suffix("food"));
+    }
+
+    #[test]
+    fn char_classes() {
+        let pattern = GlobCaseSensitive::build("log[0-9].txt").unwrap();
+        assert!(pattern.is_match("log7.txt") && !pattern.is_match("logA.txt") && !pattern.is_match("log10.txt"));
+        assert_eq!(pattern.captures_as::<u8>("log7.txt"), Some(Ok(vec![7])));
+        let pattern = GlobCaseSensitive::build("*.[ch]").unwrap();
+        assert!(pattern.is_match("main.c") && pattern.is_match("main.h") && !pattern.is_match("main.rs"));
+        let pattern = GlobCaseSensitive::build("[!.]*").unwrap();
+        assert!(pattern.is_match("visible") && !pattern.is_match(".hidden"));
+        let pattern = GlobCaseSensitive::build("[]!-]").unwrap();
+        assert!(pattern.is_match("]") && pattern.is_match("!") && pattern.is_match("-") && !pattern.is_match("a"));
+        let pattern = GlobCaseSensitive::build("[a-cx-zå]?").unwrap();
+        assert!(pattern.is_match("bb") && pattern.is_match("yy") && pattern.is_match("åå") && !pattern.is_match("dd"));
+        let pattern = GlobIgnoreCase::build("[a-f]*.LOG").unwrap();
+        assert!(pattern.is_match("Debug.log") && !pattern.is_match("info.log"));
+        assert!(crate::build_glob_pattern("log[0-9.txt").is_err());
+        assert!(crate::build_glob_pattern("[!]").is_err());
+        assert!(crate::build_glob_pattern("[z-a]").is_err());
+    }
+
+    #[test]
+    fn brace_alternatives() {
+        let pattern = GlobCaseSensitive::build("*.{jpg,png,gif}").unwrap();
+        assert!(pattern.is_match("cat.jpg") && pattern.is_match("cat.gif") && !pattern.is_match("cat.bmp"));
+        let pattern = GlobCaseSensitive::build("{src,tests}/*.rs{,.bak}").unwrap();
+        assert!(pattern.is_match("src/lib.rs") && pattern.is_match("tests/a.rs.bak") && !pattern.is_match("benches/a.rs"));
+        let pattern = GlobCaseSensitive::build("a{b,{c,d}e}f").unwrap();
+        assert!(pattern.is_match("abf") && pattern.is_match("adef") && !pattern.is_match("adf"));
+        let pattern = GlobCaseSensitive::build("[{]{x,y}").unwrap();
+        assert!(pattern.is_match("{x") && !pattern.is_match("{{"));
+        let pattern = GlobIgnoreCase::build("*.{JPG,png}").unwrap();
+        assert!(pattern.is_match("CAT.jpg") && pattern.is_match("cat.PNG"));
+        assert!(pattern.is_match_chunks(&["cat.", "pn", "g"]));
+        let pattern = GlobCaseSensitive::build("{*-v*,v*}.tar").unwrap();
+        assert_eq!(pattern.captures("v1.tar").unwrap().iter().collect::<Vec<_>>(), vec!["1"]);
+        assert_eq!(pattern.captures("app-v2.tar").unwrap().iter().collect::<Vec<_>>(), vec!["app", "2"]);
+        let gp = crate::build_glob_pattern("{a,bcd}").unwrap();
+        assert_eq!((gp.min_len(), gp.max_len()), (1, Some(3)));
+        assert!(crate::build_glob_pattern("*.{jpg,png").is_err());
+        assert!(matches!(crate::build_glob_pattern("{single}").unwrap(), crate::GlobPattern::MatchFull(s) if s == "single"));
+    }
+
+    #[test]
+    fn build_errors() {
+        use crate::GlobError;
+        assert_eq!(crate::build_glob_pattern("").unwrap_err(), GlobError::EmptyPattern);
+        assert_eq!(crate::build_glob_pattern("a**").unwrap_err(), GlobError::EmptyWildcardSegment { position: 2 });
+        assert_eq!(crate::build_glob_pattern("***").unwrap_err(), GlobError::EmptyWildcardSegment { position: 2 });
+        assert_eq!(crate::build_glob_pattern("a*b**").unwrap_err(), GlobError::EmptyWildcardSegment { position: 4 });
+        assert_eq!(crate::build_glob_pattern("?**").unwrap_err(), GlobError::EmptyWildcardSegment { position: 2 });
+        assert_eq!(crate::build_glob_pattern("log[0-9.txt").unwrap_err(), GlobError::UnterminatedClass { position: 3 });
+        assert_eq!(crate::build_glob_pattern("[a-cz-x]").unwrap_err(), GlobError::ReversedRange { position: 4 });
+        assert_eq!(crate::build_glob_pattern("a{b,c}{d").unwrap_err(), GlobError::UnterminatedBraces { position: 6 });
+        assert_eq!(crate::build_glob_pattern("{a,b}[x").unwrap_err(), GlobError::UnterminatedClass { position: 5 });
+        assert_eq!(crate::build_glob_pattern("*{*a,b}").unwrap_err(), GlobError::EmptyWildcardSegment { position: 1 });
+        assert_eq!(GlobIgnoreCase::build("ß[x").unwrap_err(), GlobError::UnterminatedClass { position: 2 });
+        let err = GlobList::build(&["*.rs".to_string(), "[".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "unterminated character class starting at position 0");
+    }
+
+    #[test]
+    fn glob_builder() {
+        let glob = Glob::builder("*.TXT").case_insensitive(true).build().unwrap();
+        assert!(glob.is_case_insensitive());
+        assert!(glob.is_match("notes.txt") && glob.match_prefix("notes.txt.bak"));
+        assert_eq!(glob.captures("Notes.txt").unwrap().get(0), Some("Notes"));
+        let glob = Glob::new("*.TXT").unwrap();
+        assert!(!glob.is_case_insensitive() && !glob.is_match("notes.txt"));
+
+        let mut glob_list = GlobList::new();
+        glob_list.add(Glob::builder("*.rs").case_insensitive(true).build().unwrap());
+        glob_list.add(GlobCaseSensitive::build("*.toml").unwrap().into());
+        assert!(glob_list.any_match("MAIN.RS") && !glob_list.any_match("CARGO.TOML"));
+        assert!(matches!(glob_list.iter().next(), Some((_, GlobListEntry::IgnoreCase(_)))));
+    }
+
     #[test]
     fn build_glob_pattern_match_any() {
         let gp = crate::build_glob_pattern("*").unwrap();
@@ -512,7 +3050,7 @@ mod tests {
         let gp = crate::build_glob_pattern("*val*").unwrap();
         let part = match gp {
             crate::GlobPattern::Multipart(m) => m,
-            _ => {assert!(false); Vec::new()},
+            other => panic!("unexpected pattern {:?}", other),
         };
         assert!(matches!(&part[0], crate::Multipart::AnyUntil(v) if v == "val"));
         assert!(matches!(&part[1], crate::Multipart::AnyEnd));
@@ -523,7 +3061,7 @@ mod tests {
         let gp = crate::build_glob_pattern("val*whale*value").unwrap();
         let part = match gp {
             crate::GlobPattern::Multipart(m) => m,
-            _ => {assert!(false); Vec::new()},
+            other => panic!("unexpected pattern {:?}", other),
         };
         assert!(matches!(&part[0], crate::Multipart::ExactStart(v) if v == "val"));
         assert!(matches!(&part[1], crate::Multipart::AnyUntil(v) if v == "whale"));
@@ -535,7 +3073,7 @@ mod tests {
         let gp = crate::build_glob_pattern("*val*brawl*").unwrap();
         let part = match gp {
             crate::GlobPattern::Multipart(m) => m,
-            _ => {assert!(false); Vec::new()},
+            other => panic!("unexpected pattern {:?}", other),
         };
         assert!(matches!(&part[0], crate::Multipart::AnyUntil(v) if v == "val"));
         assert!(matches!(&part[1], crate::Multipart::AnyUntil(v) if v == "brawl"));
@@ -547,7 +3085,7 @@ mod tests {
         let gp = crate::build_glob_pattern("*val*brawl*crawl").unwrap();
         let part = match gp {
             crate::GlobPattern::Multipart(m) => m,
-            _ => {assert!(false); Vec::new()},
+            other => panic!("unexpected pattern {:?}", other),
         };
         assert!(matches!(&part[0], crate::Multipart::AnyUntil(v) if v == "val"));
         assert!(matches!(&part[1], crate::Multipart::AnyUntil(v) if v == "brawl"));
@@ -578,8 +3116,7 @@ mod tests {
 
     #[test]
     fn glob_match_multipart_multiple_dots() {
-        // TODO: not sure if asterisk should match "0 or more" or "1 or more"
-        // could have * for 0 or more, + for 1 or more, ? for exactly 1
+        // `*` matches zero or more, see `wildcard_min_len` for one or more
         assert!(crate::glob_match("*.*~", "test.dots.~multiple.~").unwrap());
     }
 
@@ -615,6 +3152,78 @@ mod tests {
         assert!(pattern.is_match("startling.MAGIC.test.cs"));
     }
 
+    #[test]
+    fn match_spans_multipart() {
+        let pattern = GlobCaseSensitive::build("*.*.test.cs").unwrap();
+        assert_eq!(pattern.match_spans("startling.magic.test.cs"), Some(vec![9..10, 15..23]));
+        assert_eq!(pattern.match_spans("startling.magic.test.rs"), None);
+    }
+
+    #[test]
+    fn match_spans_both_ends() {
+        let pattern = GlobCaseSensitive::build("hello*world").unwrap();
+        assert_eq!(pattern.match_spans("hello nice world"), Some(vec![0..5, 11..16]));
+    }
+
+    #[test]
+    #[cfg(feature = "full-case-folding")]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn match_spans_ignore_case_refer_to_original_value() {
+        let pattern = GlobIgnoreCase::build("*strasse*").unwrap();
+        assert_eq!(pattern.match_spans("Große Straße 1"), Some(vec![7..14]));
+        let pattern = GlobIgnoreCase::build("*.rs").unwrap();
+        assert_eq!(pattern.match_spans("ßlib.RS"), Some(vec![5..8]));
+        let pattern = GlobIgnoreCase::build("??SS").unwrap();
+        assert_eq!(pattern.captures("abß").unwrap().iter().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn is_match_chunks_across_chunk_boundaries() {
+        let pattern = GlobCaseSensitive::build("hello*world").unwrap();
+        assert!(pattern.is_match_chunks(&["hel", "lo nice wor", "ld"]));
+        assert!(pattern.is_match_chunks(&["", "hello", "", "world"]));
+        assert!(!pattern.is_match_chunks(&["hello", "worl"]));
+        let pattern = GlobCaseSensitive::build("*.*.test.cs").unwrap();
+        assert!(pattern.is_match_chunks(&["startling.ma", "gic.te", "st.cs"]));
+        assert!(!pattern.is_match_chunks(&["startling.ma", "gic.te", "st.rs"]));
+        let pattern = GlobCaseSensitive::build("exact").unwrap();
+        assert!(pattern.is_match_chunks(&["ex", "act"]));
+        assert!(!pattern.is_match_chunks(&["ex", "actly"]));
+    }
+
+    #[test]
+    fn is_match_chunks_ignore_case() {
+        let pattern = GlobIgnoreCase::build("*.TXT").unwrap();
+        assert!(pattern.is_match_chunks(&["notes", ".t", "xt"]));
+        let pattern = GlobIgnoreCase::build("da*da*da*").unwrap();
+        assert!(pattern.is_match_chunks(&["DAaa", "dAba", "dmanda"]));
+    }
+
+    #[test]
+    fn is_match_chunks_with_dfa() {
+        let pattern = Glob::builder("src/**/[a-z]?.RS").case_insensitive(true).dfa(true).build().unwrap();
+        assert!(pattern.is_match_chunks(&["sr", "c/a/b", "/ab.", "rs"]));
+        assert!(!pattern.is_match_chunks(&["src/a/", "1b.rs"]));
+        assert!(pattern.is_match_chunks(&["SRC/", "", "xy.rs"]));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn ignore_case_unicode() {
+        let pattern = GlobIgnoreCase::build("*.ÅÄÖ").unwrap();
+        assert!(pattern.is_match("räksmörgås.åäö"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "unicode"))]
+    fn ignore_case_ascii_only() {
+        let pattern = GlobIgnoreCase::build("*.ÅÄÖ").unwrap();
+        assert!(!pattern.is_match("räksmörgås.åäö"));
+        assert!(pattern.is_match("RÄKSMÖRGÅS.ÅÄÖ"));
+        let pattern = GlobIgnoreCase::build("*.txt").unwrap();
+        assert!(pattern.is_match("notes.TXT"));
+    }
+
     #[test]
     fn dadada() {
         assert!(crate::glob_match("da*da*da*", "daaadabadmanda").unwrap());
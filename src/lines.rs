@@ -0,0 +1,102 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::io::{self, BufRead};
+
+use crate::{GlobList, MatchContext};
+
+impl GlobList {
+    /// The lines of `reader` matching any of the patterns, see [`GlobList::any_match`].
+    /// Lines that aren't valid UTF-8 are an [`io::ErrorKind::InvalidData`] error like for [`BufRead::lines`].
+    pub fn filter_lines<R: BufRead>(&self, reader: R) -> FilterLines<'_, R> {
+        FilterLines {
+            glob_list: self,
+            reader,
+            invert: false,
+            ctx: MatchContext::new(),
+        }
+    }
+}
+
+/// Iterator over the lines of a reader that match a [`GlobList`], created by [`GlobList::filter_lines`].
+///
+/// Line terminators (`\n` or `\r\n`) are stripped from the yielded lines.
+#[derive(Debug)]
+pub struct FilterLines<'a, R> {
+    glob_list: &'a GlobList,
+    reader: R,
+    invert: bool,
+    ctx: MatchContext,
+}
+
+impl<R> FilterLines<'_, R> {
+    /// Yield the lines that don't match any pattern instead.
+    pub fn invert(mut self) -> Self {
+        self.invert = !self.invert;
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for FilterLines<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) => (),
+                Err(e) => return Some(Err(e)),
+            }
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            if self.glob_list.any_match_with(&mut self.ctx, &line) != self.invert {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::GlobList;
+
+    #[test]
+    fn filter_lines() {
+        let glob_list = GlobList::build_ignore_case(&["*error*".to_string(), "!*ignored*".to_string()]).unwrap();
+        let input = &b"12:00 ERROR disk full\r\n12:01 info started\n12:02 error ignored\n12:03 Error again"[..];
+        let lines : Vec<String> = glob_list.filter_lines(input).map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["12:00 ERROR disk full", "12:03 Error again"]);
+        let lines : Vec<String> = glob_list.filter_lines(input).invert().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["12:01 info started", "12:02 error ignored"]);
+    }
+
+    #[test]
+    fn filter_lines_invalid_utf8() {
+        let glob_list = GlobList::build(&["*".to_string()]).unwrap();
+        let mut lines = glob_list.filter_lines(&b"ok\n\xff\n"[..]);
+        assert_eq!(lines.next().unwrap().unwrap(), "ok");
+        assert_eq!(lines.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
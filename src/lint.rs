@@ -0,0 +1,144 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Warnings about patterns that build but are likely mistakes, see [`lint`].
+
+use std::fmt;
+
+use crate::{CharClass, Glob, GlobError, GlobPattern, MatchOptions, Segment};
+
+/// Something suspicious about a pattern, found by [`lint`].
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Lint {
+    /// The pattern doesn't build at all.
+    Invalid(GlobError),
+    /// A `**` where `*` does the same, as without a literal separator `*` already matches `/`,
+    /// at the position of the `**`.
+    RedundantRecursive { position: usize },
+    /// The pattern matches every value, like `*`.
+    MatchesEverything,
+    /// No value can match, e.g. a class of every char negated.
+    NeverMatches,
+    /// Spaces or tabs at the end, usually left over from a config file, at the position of the first one.
+    TrailingWhitespace { position: usize },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::Invalid(e) => write!(f, "invalid pattern: {}", e),
+            Lint::RedundantRecursive { position } =>
+                write!(f, "`**` at position {} does the same as `*` without a literal separator", position),
+            Lint::MatchesEverything => write!(f, "pattern matches every value"),
+            Lint::NeverMatches => write!(f, "pattern can't match any value"),
+            Lint::TrailingWhitespace { position } => write!(f, "trailing whitespace starting at position {}", position),
+        }
+    }
+}
+
+/// The suspicious things about `pattern` built with the default options, see [`lint_with`].
+pub fn lint(pattern: &str) -> Vec<Lint> {
+    lint_with(pattern, MatchOptions::new())
+}
+
+/// The suspicious things about `pattern` built with `options`, empty if there are none.
+pub fn lint_with(pattern: &str, options: MatchOptions) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let trimmed = pattern.trim_end_matches([' ', '\t']);
+    if trimmed.len() < pattern.len() {
+        lints.push(Lint::TrailingWhitespace { position: trimmed.len() });
+    }
+    let glob = match Glob::builder(pattern).match_options(options).build() {
+        Ok(glob) => glob,
+        Err(e) => {
+            lints.push(Lint::Invalid(e));
+            return lints;
+        },
+    };
+    if !options.require_literal_separator {
+        let mut recursive = pattern.match_indices("**").map(|(i, _)| i).peekable();
+        while let Some(position) = recursive.next() {
+            lints.push(Lint::RedundantRecursive { position });
+            // `***` is one run, not two
+            recursive.next_if(|&next| next == position + 1);
+        }
+    }
+    if matches_everything(glob.pattern()) {
+        lints.push(Lint::MatchesEverything);
+    } else if never_matches(glob.pattern()) {
+        lints.push(Lint::NeverMatches);
+    }
+    lints
+}
+
+fn matches_everything(pattern: &GlobPattern) -> bool {
+    match pattern {
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(matches_everything),
+        _ => pattern.is_match_all(),
+    }
+}
+
+fn never_matches(pattern: &GlobPattern) -> bool {
+    match pattern {
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().all(never_matches),
+        GlobPattern::Segmented(segments) => segments.iter().any(|segment| matches!(segment, Segment::Class(class) if is_empty(class))),
+        _ => false,
+    }
+}
+
+/// Whether no char is in `class`, a negated class has to leave out every char but the surrogates, which
+/// aren't chars.
+fn is_empty(class: &CharClass) -> bool {
+    if !class.is_negated() {
+        return class.ranges().is_empty();
+    }
+    let mut ranges = class.ranges().to_vec();
+    ranges.sort_unstable();
+    let mut next = 0;
+    for (first, last) in ranges {
+        let first = first as u32;
+        if first > next && !(next == 0xD800 && first == 0xE000) {
+            return false;
+        }
+        next = next.max(last as u32 + 1);
+    }
+    next > char::MAX as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, lint_with, Lint};
+    use crate::{GlobError, MatchOptions};
+
+    #[test]
+    fn lints() {
+        assert_eq!(lint("src/*.rs"), vec![]);
+        assert_eq!(lint("src/**/*.rs"), vec![Lint::RedundantRecursive { position: 4 }]);
+        let separator = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+        assert_eq!(lint_with("src/**/*.rs", separator), vec![]);
+        assert_eq!(lint("*"), vec![Lint::MatchesEverything]);
+        assert_eq!(lint("{*.rs,*}"), vec![Lint::MatchesEverything]);
+        assert_eq!(lint_with("*", separator), vec![]);
+        assert_eq!(lint("a[!\u{0}-\u{10FFFF}]"), vec![Lint::NeverMatches]);
+        assert_eq!(lint("a[!\u{0}-\u{D7FF}\u{E000}-\u{10FFFF}]"), vec![Lint::NeverMatches]);
+        assert_eq!(lint("a[!\u{0}-\u{D7FF}]"), vec![]);
+        assert_eq!(lint("*.log \t"), vec![Lint::TrailingWhitespace { position: 5 }]);
+        assert_eq!(lint("a[b"), vec![Lint::Invalid(GlobError::UnterminatedClass { position: 1 })]);
+        assert_eq!(Lint::MatchesEverything.to_string(), "pattern matches every value");
+    }
+}
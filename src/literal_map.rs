@@ -0,0 +1,250 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Looking up the literal patterns of a [`GlobList`] by hashing, and the `*` followed by a literal ones
+//! (e.g. `*.rs`) in a trie of their reversed literals, instead of matching them one by one.
+
+use std::collections::HashSet;
+
+use crate::{fold_case, CaseMode, Glob, GlobList, GlobPattern};
+
+/// The patterns of a list without wildcards as sets of the values they match, the `*.rs` like ones
+/// as tries of their ends, the ignore case ones case folded, and the indexes of the rest in their
+/// buckets, which still take the matcher.
+#[derive(Debug,Clone,Default)]
+pub(crate) struct LiteralMap {
+    case_sensitive: HashSet<String>,
+    ignore_case: HashSet<String>,
+    case_sensitive_ends: SuffixTrie,
+    ignore_case_ends: SuffixTrie,
+    case_sensitive_wildcards: Vec<usize>,
+    ignore_case_wildcards: Vec<usize>,
+}
+
+impl LiteralMap {
+    pub(crate) fn new(list: &GlobList) -> LiteralMap {
+        let mut map = LiteralMap::default();
+        for (i, p) in list.case_sensitive_patterns.iter().enumerate() {
+            match literal(&p.0) {
+                Some(Literal::Exact(literal)) => { map.case_sensitive.insert(literal.to_string()); },
+                Some(Literal::End(end)) => map.case_sensitive_ends.insert(end),
+                None => map.case_sensitive_wildcards.push(i),
+            }
+        }
+        for (i, p) in list.ignore_case_patterns.iter().enumerate() {
+            match literal(&p.0) {
+                Some(Literal::Exact(literal)) => { map.ignore_case.insert(literal.to_string()); },
+                Some(Literal::End(end)) => map.ignore_case_ends.insert(end),
+                None => map.ignore_case_wildcards.push(i),
+            }
+        }
+        map
+    }
+
+    /// [`GlobList::any_match`] for a list without negated patterns, folding `value` into `folded` the first
+    /// time an ignore case pattern needs it. Case sensitive patterns are tried first, a match there saves
+    /// folding the value.
+    pub(crate) fn any_match(&self, list: &GlobList, value: &str, folded: &mut Option<String>) -> bool {
+        if self.case_sensitive_match(list, value) {
+            return true;
+        }
+        if !self.ignore_case.is_empty() || !self.ignore_case_ends.is_empty() {
+            let folded = folded.get_or_insert_with(|| fold_case(value));
+            if self.ignore_case.contains(folded.as_str()) || self.ignore_case_ends.ends(folded) {
+                return true;
+            }
+        }
+        self.ignore_case_wildcards.iter().any(|&i| list.ignore_case_patterns[i].is_match_lazily(value, folded))
+    }
+
+    /// Whether a case sensitive pattern of the list matches `value`.
+    pub(crate) fn case_sensitive_match(&self, list: &GlobList, value: &str) -> bool {
+        self.case_sensitive.contains(value) ||
+            self.case_sensitive_ends.ends(value) ||
+            self.case_sensitive_wildcards.iter().any(|&i| list.case_sensitive_patterns[i].0.matches_folded(value))
+    }
+
+    /// Whether an ignore case pattern of the list matches `value`, `folded` is `value` case folded.
+    pub(crate) fn ignore_case_match(&self, list: &GlobList, value: &str, folded: &str) -> bool {
+        self.ignore_case.contains(folded) ||
+            self.ignore_case_ends.ends(folded) ||
+            self.ignore_case_wildcards.iter().any(|&i| list.ignore_case_patterns[i].is_match_folded(value, folded))
+    }
+}
+
+/// What a pattern a [`LiteralMap`] looks up matches.
+enum Literal<'a> {
+    /// only this value
+    Exact(&'a str),
+    /// values ending with this
+    End(&'a str),
+}
+
+/// What `glob` matches as values look once folded the way [`GlobList`] folds them, unless it has
+/// other wildcards or compares values some other way (e.g. only folding ASCII letters).
+fn literal(glob: &Glob) -> Option<Literal<'_>> {
+    if glob.normalized || !matches!(glob.case, CaseMode::Sensitive | CaseMode::Insensitive) {
+        return None;
+    }
+    match &glob.pattern {
+        GlobPattern::MatchFull(literal) => Some(Literal::Exact(literal)),
+        GlobPattern::MatchEnd(end) => Some(Literal::End(end)),
+        _ => None,
+    }
+}
+
+/// Literals by their bytes from the last one, so the ends of a value are all looked up in one pass
+/// over it from the back.
+#[derive(Debug,Clone,Default)]
+struct SuffixTrie {
+    /// the root first, none when empty
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug,Clone,Default)]
+struct Node {
+    /// the byte before the ones leading here, and the node it leads to
+    children: Vec<(u8, usize)>,
+    /// whether the bytes leading here are a whole literal
+    end: bool,
+}
+
+impl SuffixTrie {
+    fn insert(&mut self, literal: &str) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::default());
+        }
+        let mut node = 0;
+        for byte in literal.bytes().rev() {
+            node = match self.nodes[node].children.iter().find(|&&(b, _)| b == byte) {
+                Some(&(_, child)) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.push((byte, child));
+                    child
+                },
+            };
+        }
+        self.nodes[node].end = true;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Whether `value` ends with any of the literals.
+    fn ends(&self, value: &str) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let mut node = 0;
+        for byte in value.bytes().rev() {
+            node = match self.nodes[node].children.iter().find(|&&(b, _)| b == byte) {
+                Some(&(_, child)) => child,
+                None => return false,
+            };
+            if self.nodes[node].end {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LiteralMap;
+    use crate::{Glob, GlobIgnoreCase, GlobList, MatchContext};
+
+    #[test]
+    fn literals_and_wildcards() {
+        let mut list = GlobList::new();
+        for i in 0..100 {
+            list.add(Glob::new(&format!("file-{}.txt", i)).unwrap());
+        }
+        list.add_ignore_case(GlobIgnoreCase::build("README.MD").unwrap());
+        list.add(Glob::builder("LICENSE").ascii_case_insensitive(true).build().unwrap());
+        list.add(Glob::new("*.rs").unwrap());
+        let mut ctx = MatchContext::new();
+        for value in ["file-42.txt", "readme.md", "ReadMe.md", "license", "lib.rs"].iter() {
+            assert!(list.any_match(value) && list.any_match_with(&mut ctx, value), "{}", value);
+        }
+        for value in ["file-100.txt", "File-42.txt", "readme", "lib.rs.bak", ""].iter() {
+            assert!(!list.any_match(value) && !list.any_match_with(&mut ctx, value), "{}", value);
+        }
+
+        // changes drop the lookup built before them
+        list.remove("file-42.txt");
+        assert!(!list.any_match("file-42.txt"));
+        list.add(Glob::new("file-42.txt").unwrap());
+        list.reorder_by_selectivity();
+        assert!(list.any_match("file-42.txt") && list.any_match("x.rs"));
+    }
+
+    #[test]
+    fn ends_in_one_pass() {
+        let mut list = GlobList::new();
+        for extension in ["rs", "toml", "lock", "md", "tar.gz", "gz", "é"].iter() {
+            list.add(Glob::new(&format!("*.{}", extension)).unwrap());
+        }
+        list.add_ignore_case(GlobIgnoreCase::build("*.PNG").unwrap());
+        list.add(Glob::new("*.c*").unwrap());
+        for value in ["lib.rs", "Cargo.toml", "a.tar.gz", "b.gz", ".md", "caf.é", "LOGO.png", "x.cpp"].iter() {
+            assert!(list.any_match(value), "{}", value);
+        }
+        for value in ["lib.r", "rs", "a.tar", "README", "caf.e", "x.h", "", "toml.bak"].iter() {
+            assert!(!list.any_match(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn folds_only_for_ignore_case_patterns() {
+        let mut list = GlobList::new();
+        list.add(Glob::new("*.rs").unwrap());
+        list.add(Glob::new("Cargo.toml").unwrap());
+        list.add_ignore_case(GlobIgnoreCase::build("*.MD").unwrap());
+        list.add_ignore_case(GlobIgnoreCase::build("LICENSE-*").unwrap());
+        let map = LiteralMap::new(&list);
+        // decided by the case sensitive patterns alone, the value is never folded
+        for value in ["lib.rs", "Cargo.toml"].iter() {
+            let mut folded = None;
+            assert!(map.any_match(&list, value, &mut folded), "{}", value);
+            assert_eq!(folded, None, "{}", value);
+        }
+        let mut folded = None;
+        assert!(map.any_match(&list, "README.md", &mut folded));
+        assert_eq!(folded.as_deref(), Some("readme.md"));
+        assert!(map.any_match(&list, "License-MIT", &mut None));
+        assert!(!map.any_match(&list, "notes.txt", &mut None));
+        let mut ctx = MatchContext::new();
+        assert!(list.any_match("LICENSE-mit") && list.any_match_with(&mut ctx, "LICENSE-mit"));
+        assert!(!list.any_match("cargo.toml") && !list.any_match_with(&mut ctx, "cargo.toml"));
+    }
+
+    #[test]
+    fn hits_are_still_counted() {
+        let mut list = GlobList::new();
+        list.set_hit_tracking(true);
+        list.add(Glob::new("b").unwrap());
+        list.add(Glob::new("a").unwrap());
+        assert!(list.any_match("a") && list.any_match("a") && !list.any_match("c"));
+        list.reorder_by_selectivity();
+        assert_eq!(list.case_sensitive_patterns[0].as_str(), "a");
+    }
+}
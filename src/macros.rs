@@ -0,0 +1,68 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! The `glob_pattern!` proc macro, building a [`GlobPattern`](crate::GlobPattern) at compile time.
+//!
+//! `globber::macros::glob_pattern!("src/*.rs")` fails to compile for an invalid pattern like `a**`,
+//! a valid one expands to the built pattern so nothing is parsed at runtime. Unlike [`glob!`](crate::glob!),
+//! which checks the pattern and builds a [`Glob`](crate::Glob) on first use, there are no options.
+
+use crate::case::fold_case;
+use crate::CharClass;
+
+pub use globber_macros::glob_pattern;
+
+// what the macro expands to calls these, so a group ignoring case is folded the way the crate was built to fold
+
+#[doc(hidden)]
+pub fn __fold_case(literal: &str) -> String {
+    fold_case(literal)
+}
+
+#[doc(hidden)]
+pub fn __class(negated: bool, ranges: Vec<(char, char)>, ignore_case: bool) -> CharClass {
+    let class = CharClass { negated, ranges };
+    if ignore_case { class.ignoring_case() } else { class }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_pattern;
+    use crate::{build_glob_pattern, glob_match_prebuilt, GlobPattern};
+
+    #[test]
+    fn glob_pattern_macro_builds_like_build_glob_pattern() {
+        let built = [glob_pattern!("*"), glob_pattern!("Cargo.toml"), glob_pattern!("*.rs"), glob_pattern!("src/*"),
+                     glob_pattern!("a*b"), glob_pattern!("*.*.test.cs"), glob_pattern!("a*b*c"), glob_pattern!("*a*"),
+                     glob_pattern!(r"src/**/*.rs"), glob_pattern!("file?.{txt,md}"), glob_pattern!("tab\there*"),
+                     glob_pattern!("**"), glob_pattern!("**/**/x"), glob_pattern!("[]a-c]?[!0-9]"), glob_pattern!("[{}]"),
+                     glob_pattern!("(?i:Straße)/[a-c]*"), glob_pattern!("x/(?i:[k])"), glob_pattern!("{a,{b,c}}*{x,}"),
+                     glob_pattern!("{only}"), glob_pattern!("{,a}"), glob_pattern!("é?\"")];
+        let patterns = ["*", "Cargo.toml", "*.rs", "src/*", "a*b", "*.*.test.cs", "a*b*c", "*a*", "src/**/*.rs",
+                        "file?.{txt,md}", "tab\there*", "**", "**/**/x", "[]a-c]?[!0-9]", "[{}]", "(?i:Straße)/[a-c]*",
+                        "x/(?i:[k])", "{a,{b,c}}*{x,}", "{only}", "{,a}", "é?\""];
+        for (built, pattern) in built.iter().zip(patterns.iter()) {
+            assert_eq!(built, &build_glob_pattern(pattern).unwrap(), "{}", pattern);
+        }
+        assert!(glob_match_prebuilt(&glob_pattern!("*.rs"), "main.rs"));
+        assert!(matches!(glob_pattern!("*.rs"), GlobPattern::MatchEnd(_)));
+        assert!(matches!(glob_pattern!("a?"), GlobPattern::Segmented(_)));
+        assert!(matches!(glob_pattern!("*.{rs,toml}"), GlobPattern::Alternatives(_)));
+        assert!(glob_match_prebuilt(&glob_pattern!("(?i:readme)*"), "README.md"));
+    }
+}
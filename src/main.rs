@@ -0,0 +1,347 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use globber::{GlobCaseSensitive, GlobError, GlobIgnoreCase};
+
+const USAGE: &str = "\
+usage:
+    globber [-i] [-v] [-c | -q] [--color[=<when>]] [--walk <dir>] [--] <pattern>...
+    globber bench <pattern file> <input file> [--ignore-case] [--runs <n>]
+
+filter:
+    prints every line read from stdin that matches any of the patterns
+
+    -i, --ignore-case   match case insensitively
+    -v, --invert-match  select the lines that don't match any pattern,
+                        --invert does the same
+    -c, --count         only print the number of selected lines
+    -q, --quiet         print nothing, stop at the first selected line
+    --color[=<when>]    highlight the literal parts of the pattern within the
+                        line, <when> is 'auto' (the default), 'always' or 'never'
+    --walk <dir>        match the paths of the files below <dir> instead of
+                        lines from stdin, relative to <dir> and separated by '/'
+
+    exits with 0 if any line was selected, 1 if none was and 2 on errors
+
+bench:
+    compiles every pattern in <pattern file> (one per line, blank lines and
+    lines starting with '#' are skipped), matches each of them against every
+    line in <input file> and reports timing and hit statistics per pattern,
+    slowest pattern first";
+
+// grep compatible exit codes
+const EXIT_MATCH: i32 = 0;
+const EXIT_NO_MATCH: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("bench") => bench(&args[1..]).map(|_| true),
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(true)
+        },
+        Some(_) => filter(&args),
+        None => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(true) => process::exit(EXIT_MATCH),
+        Ok(false) => process::exit(EXIT_NO_MATCH),
+        Err(e) => {
+            eprintln!("globber: {}", e);
+            process::exit(EXIT_ERROR);
+        },
+    }
+}
+
+enum Compiled {
+    CaseSensitive(GlobCaseSensitive),
+    IgnoreCase(GlobIgnoreCase),
+}
+
+impl Compiled {
+    fn build(pattern: &str, ignore_case: bool) -> Result<Compiled, GlobError> {
+        if ignore_case {
+            GlobIgnoreCase::build(pattern).map(Compiled::IgnoreCase)
+        } else {
+            GlobCaseSensitive::build(pattern).map(Compiled::CaseSensitive)
+        }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Compiled::CaseSensitive(p) => p.is_match(value),
+            Compiled::IgnoreCase(p) => p.is_match(value),
+        }
+    }
+
+    fn match_spans(&self, value: &str) -> Option<Vec<Range<usize>>> {
+        match self {
+            Compiled::CaseSensitive(p) => p.match_spans(value),
+            Compiled::IgnoreCase(p) => p.match_spans(value),
+        }
+    }
+}
+
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+/// Returns whether any line was selected.
+fn filter(args: &[String]) -> Result<bool, String> {
+    let mut patterns = Vec::new();
+    let mut ignore_case = false;
+    let mut invert = false;
+    let mut count = false;
+    let mut quiet = false;
+    let mut color = None;
+    let mut walk = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                patterns.extend(args.by_ref());
+                break;
+            },
+            "--ignore-case" => ignore_case = true,
+            "--invert-match" | "--invert" => invert = true,
+            "--count" => count = true,
+            "--quiet" | "--silent" => quiet = true,
+            "--color" | "--color=auto" => color = Some(io::stdout().is_terminal()),
+            "--color=always" => color = Some(true),
+            "--color=never" => color = Some(false),
+            "--walk" => walk = Some(args.next().ok_or("--walk needs a directory")?.clone()),
+            _ if arg.starts_with("--walk=") => walk = Some(arg["--walk=".len()..].to_string()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg)),
+            // short flags can be combined like grep's, e.g. -vc
+            _ if arg.len() > 1 && arg.starts_with('-') => {
+                for flag in arg[1..].chars() {
+                    match flag {
+                        'i' => ignore_case = true,
+                        'v' => invert = true,
+                        'c' => count = true,
+                        'q' => quiet = true,
+                        _ => return Err(format!("unknown option '-{}'", flag)),
+                    }
+                }
+            },
+            _ => patterns.push(arg),
+        }
+    }
+    // nothing to highlight in lines that didn't match
+    let color = color.unwrap_or(false) && !invert && !count;
+
+    if patterns.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let patterns = patterns
+        .into_iter()
+        .map(|p| Compiled::build(p, ignore_case).map_err(|e| format!("invalid pattern '{}': {}", p, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lines: Box<dyn Iterator<Item = Result<String, String>>> = match walk {
+        Some(dir) => Box::new(walk_files(dir)?),
+        None => Box::new(io::stdin().lock().lines().map(|line| line.map_err(|e| format!("could not read stdin: {}", e)))),
+    };
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut selected = 0usize;
+    for line in lines {
+        let line = line?;
+        let spans = if color {
+            patterns.iter().find_map(|p| p.match_spans(&line))
+        } else {
+            None
+        };
+        let is_match = spans.is_some() || (!color && patterns.iter().any(|p| p.is_match(&line)));
+        if is_match == invert {
+            continue;
+        }
+
+        selected += 1;
+        if quiet {
+            return Ok(true);
+        }
+        if count {
+            continue;
+        }
+
+        match spans {
+            Some(spans) => write_highlighted(&mut out, &line, spans),
+            None => writeln!(out, "{}", line),
+        }.map_err(|e| format!("could not write to stdout: {}", e))?;
+    }
+
+    if count && !quiet {
+        writeln!(out, "{}", selected).map_err(|e| format!("could not write to stdout: {}", e))?;
+    }
+    out.flush().map_err(|e| format!("could not write to stdout: {}", e))?;
+    Ok(selected > 0)
+}
+
+/// The paths of the files below `dir`, relative to it and separated by `/`, in sorted order.
+/// Symlinks to directories are skipped rather than followed.
+fn walk_files(dir: String) -> Result<impl Iterator<Item = Result<String, String>>, String> {
+    // the walk itself finds nothing in a missing directory rather than failing
+    fs::read_dir(&dir).map_err(|e| format!("could not walk '{}': {}", dir, e))?;
+    let root = PathBuf::from(dir.trim_end_matches('/'));
+    let paths = globber::fs::glob(&format!("{}/**", escape(dir.trim_end_matches('/'))))
+        .map_err(|e| format!("could not walk '{}': {}", dir, e))?;
+    Ok(paths.filter_map(move |path| match path {
+        Ok(path) if path.is_dir() => None,
+        Ok(path) => {
+            let relative = path.strip_prefix(&root).unwrap_or(&path);
+            Some(Ok(relative.to_string_lossy().replace('\\', "/")))
+        },
+        Err(e) => Some(Err(format!("could not walk '{}': {}", dir, e))),
+    }))
+}
+
+/// `path` as a pattern matching only itself.
+fn escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for ch in path.chars() {
+        match ch {
+            '*' | '?' | '[' | '{' | '(' => {
+                escaped.push('[');
+                escaped.push(ch);
+                escaped.push(']');
+            },
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn write_highlighted(out: &mut impl Write, line: &str, mut spans: Vec<Range<usize>>) -> io::Result<()> {
+    // spans can overlap when case folding turned one char into several (`ß` matching `s*s`),
+    // merge them so nothing gets highlighted twice
+    spans.sort_by_key(|s| s.start);
+    let mut pos = 0;
+    for span in spans {
+        let start = span.start.max(pos);
+        if span.end <= start {
+            continue;
+        }
+        write!(out, "{}{}{}{}", &line[pos..start], HIGHLIGHT_START, &line[start..span.end], HIGHLIGHT_END)?;
+        pos = span.end;
+    }
+    writeln!(out, "{}", &line[pos..])
+}
+
+struct BenchResult<'a> {
+    pattern: &'a str,
+    line: usize,
+    build: Duration,
+    matching: Duration,
+    hits: usize,
+}
+
+fn bench(args: &[String]) -> Result<(), String> {
+    let mut files = Vec::new();
+    let mut ignore_case = false;
+    let mut runs = 1usize;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--ignore-case" => ignore_case = true,
+            "--runs" => {
+                runs = args.next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| "--runs expects a number greater than 0".to_string())?;
+            },
+            _ => files.push(arg),
+        }
+    }
+
+    if files.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+
+    let patterns = fs::read_to_string(files[0])
+        .map_err(|e| format!("could not read pattern file '{}': {}", files[0], e))?;
+    let input = fs::read_to_string(files[1])
+        .map_err(|e| format!("could not read input file '{}': {}", files[1], e))?;
+    let values: Vec<&str> = input.lines().collect();
+
+    let mut results = Vec::new();
+    for (i, pattern) in patterns.lines().enumerate() {
+        if pattern.is_empty() || pattern.starts_with('#') {
+            continue;
+        }
+
+        let start = Instant::now();
+        let compiled = Compiled::build(pattern, ignore_case)
+            .map_err(|e| format!("invalid pattern '{}' on line {}: {}", pattern, i + 1, e))?;
+        let build = start.elapsed();
+
+        let mut hits = 0;
+        let start = Instant::now();
+        for _ in 0..runs {
+            hits = values.iter().filter(|v| compiled.is_match(v)).count();
+        }
+        let matching = start.elapsed().div_f64(runs as f64);
+
+        results.push(BenchResult {
+            pattern,
+            line: i + 1,
+            build,
+            matching,
+            hits,
+        });
+    }
+
+    // slowest first, that's the one the user is looking for
+    results.sort_by_key(|r| Reverse(r.matching));
+
+    let total: Duration = results.iter().map(|r| r.matching).sum();
+    println!("{} patterns, {} input lines, {} run(s), {:.3} ms matching in total",
+             results.len(), values.len(), runs, total.as_secs_f64() * 1000.0);
+    println!("{:>12} {:>10} {:>10} {:>10} {:>6}  pattern", "match (ms)", "ns/value", "build (us)", "hits", "line");
+    for r in &results {
+        let per_value = if values.is_empty() {
+            0.0
+        } else {
+            r.matching.as_nanos() as f64 / values.len() as f64
+        };
+        println!("{:>12.3} {:>10.1} {:>10.1} {:>10} {:>6}  {}",
+                 r.matching.as_secs_f64() * 1000.0,
+                 per_value,
+                 r.build.as_secs_f64() * 1_000_000.0,
+                 r.hits,
+                 r.line,
+                 r.pattern);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Options for building a pattern in one value, like the `glob` crate's `MatchOptions`.
+
+/// The options the `glob` crate and shells match by, for [`GlobBuilder::match_options`](crate::GlobBuilder::match_options)
+/// [`GlobList::build_with_options`](crate::GlobList::build_with_options) and [`GlobSet::build_with_options`](crate::GlobSet::build_with_options).
+/// The default is case sensitive with wildcards matching `/`, same as [`Glob::new`](crate::Glob::new).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchOptions {
+    /// whether `a` and `A` are different, see [`GlobBuilder::case_insensitive`](crate::GlobBuilder::case_insensitive)
+    pub case_sensitive: bool,
+    /// whether `*`, `?` and negated classes stop at `/`, so `*.rs` doesn't match `src/lib.rs`,
+    /// see [`GlobBuilder::literal_separator`](crate::GlobBuilder::literal_separator)
+    pub require_literal_separator: bool,
+    /// whether wildcards don't match a `.` at the start of a path component, so `*` doesn't match `.git`,
+    /// see [`GlobBuilder::literal_leading_dot`](crate::GlobBuilder::literal_leading_dot)
+    pub require_literal_leading_dot: bool,
+}
+
+impl MatchOptions {
+    pub fn new() -> MatchOptions {
+        MatchOptions::default()
+    }
+}
+
+impl Default for MatchOptions {
+    fn default() -> MatchOptions {
+        MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: false,
+            require_literal_leading_dot: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, GlobList, GlobSet, MatchOptions};
+
+    #[test]
+    fn literal_separator() {
+        let options = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+        let glob = Glob::builder("*.rs").match_options(options).build().unwrap();
+        assert!(glob.is_match("lib.rs") && !glob.is_match("src/lib.rs"));
+        assert!(Glob::builder("*.rs").match_options(MatchOptions::new()).build().unwrap().is_match("src/lib.rs"));
+
+        let options = MatchOptions { case_sensitive: false, require_literal_separator: true, ..MatchOptions::new() };
+        let list = GlobList::build_with_options(&["*.RS".to_string(), "!main.rs".to_string()], options).unwrap();
+        assert!(list.any_match("lib.rs") && !list.any_match("src/lib.rs"));
+        assert!(list.iter().all(|(_, entry)| !entry.is_case_sensitive()));
+        let set = GlobSet::build_with_options(&["src/*".to_string()], options).unwrap();
+        assert!(set.is_match("SRC/lib.rs") && !set.is_match("src/a/lib.rs"));
+    }
+
+    #[test]
+    fn literal_leading_dot() {
+        let options = MatchOptions { require_literal_leading_dot: true, ..MatchOptions::new() };
+        let list = GlobList::build_with_options(&["**/*".to_string()], options).unwrap();
+        assert!(list.any_match("src/lib.rs") && list.any_match("Cargo.toml"));
+        assert!(!list.any_match(".git") && !list.any_match(".git/config") && !list.any_match("src/.git/config"));
+    }
+}
@@ -0,0 +1,76 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching media types the way `Accept` headers do, `image/*` matches `image/png`.
+
+use crate::Glob;
+
+/// Whether the media type `mime` is in `range`, e.g. `image/*` for `image/png; charset=binary`.
+///
+/// Both are taken without their parameters and compared ignoring ASCII case, the type and subtype are
+/// matched separately so a wildcard never matches the `/`. Each half of `range` is a pattern, `*/*`
+/// (or just `*`) matches every media type and `application/*+json` matches `application/ld+json`.
+/// A `mime` without a subtype or a `range` that isn't a valid pattern doesn't match.
+pub fn matches(range: &str, mime: &str) -> bool {
+    let range = match essence(range) {
+        "*" => "*/*",
+        range => range,
+    };
+    match (range.split_once('/'), essence(mime).split_once('/')) {
+        (Some((range_type, range_subtype)), Some((mime_type, mime_subtype))) =>
+            !mime_type.is_empty() && !mime_subtype.is_empty() && !mime_subtype.contains('/')
+                && half_matches(range_type, mime_type) && half_matches(range_subtype, mime_subtype),
+        _ => false,
+    }
+}
+
+/// `mime` without its parameters, `text/html` for `text/html; charset=utf-8`.
+pub fn essence(mime: &str) -> &str {
+    mime.split(';').next().unwrap_or(mime).trim()
+}
+
+fn half_matches(pattern: &str, value: &str) -> bool {
+    Glob::builder(pattern).ascii_case_insensitive(true).build().is_ok_and(|glob| glob.is_match(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{essence, matches};
+
+    #[test]
+    fn ranges() {
+        assert!(matches("image/*", "image/png"));
+        assert!(matches("image/*", "Image/PNG; charset=binary"));
+        assert!(matches("*/*", "text/html"));
+        assert!(matches("*", "text/html"));
+        assert!(matches("text/html;level=1", "text/html"));
+        assert!(matches("application/*+json", "application/ld+json"));
+        assert!(!matches("image/*", "text/plain"));
+        assert!(!matches("image/*", "image"));
+        assert!(!matches("*/*", "image/"));
+        assert!(!matches("image*", "image/png"));
+        assert!(!matches("*/*", "a/b/c"));
+        assert!(!matches("image/[png", "image/png"));
+    }
+
+    #[test]
+    fn parameters() {
+        assert_eq!(essence(" text/html ; charset=utf-8"), "text/html");
+        assert_eq!(essence("text/plain"), "text/plain");
+    }
+}
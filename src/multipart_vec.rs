@@ -0,0 +1,200 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Inline storage for the parts of a multipart pattern, see [`MultipartVec`].
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use crate::Multipart;
+
+/// Parts stored inline before spilling to the heap, most patterns have 2 to 4.
+const INLINE: usize = 4;
+
+/// The parts of a [`GlobPattern::Multipart`](crate::GlobPattern::Multipart), a `Vec` keeping up to four
+/// of them inline so building a typical pattern doesn't allocate for the list itself.
+///
+/// Derefs to a slice and converts from and to a `Vec`, serialized as a plain sequence.
+#[derive(Clone)]
+pub struct MultipartVec(Storage);
+
+#[derive(Clone)]
+enum Storage {
+    /// unused slots hold `AnyEnd`, which doesn't allocate
+    Inline { len: usize, parts: [Multipart; INLINE] },
+    Heap(Vec<Multipart>),
+}
+
+impl MultipartVec {
+    pub fn new() -> MultipartVec {
+        MultipartVec(Storage::Inline { len: 0, parts: std::array::from_fn(|_| Multipart::AnyEnd) })
+    }
+
+    pub fn push(&mut self, part: Multipart) {
+        match &mut self.0 {
+            Storage::Inline { len, parts } if *len < INLINE => {
+                parts[*len] = part;
+                *len += 1;
+            },
+            Storage::Inline { parts, .. } => {
+                let mut heap = Vec::with_capacity(INLINE * 2);
+                heap.extend(parts.iter_mut().map(|part| std::mem::replace(part, Multipart::AnyEnd)));
+                heap.push(part);
+                self.0 = Storage::Heap(heap);
+            },
+            Storage::Heap(heap) => heap.push(part),
+        }
+    }
+
+    /// Whether the parts spilled to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.0, Storage::Heap(_))
+    }
+
+    pub fn into_vec(self) -> Vec<Multipart> {
+        match self.0 {
+            Storage::Inline { len, parts } => IntoIterator::into_iter(parts).take(len).collect(),
+            Storage::Heap(heap) => heap,
+        }
+    }
+}
+
+impl Default for MultipartVec {
+    fn default() -> MultipartVec {
+        MultipartVec::new()
+    }
+}
+
+impl Deref for MultipartVec {
+    type Target = [Multipart];
+
+    fn deref(&self) -> &[Multipart] {
+        match &self.0 {
+            Storage::Inline { len, parts } => &parts[..*len],
+            Storage::Heap(heap) => heap,
+        }
+    }
+}
+
+impl DerefMut for MultipartVec {
+    fn deref_mut(&mut self) -> &mut [Multipart] {
+        match &mut self.0 {
+            Storage::Inline { len, parts } => &mut parts[..*len],
+            Storage::Heap(heap) => heap,
+        }
+    }
+}
+
+impl fmt::Debug for MultipartVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MultipartVec {
+    fn eq(&self, other: &MultipartVec) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for MultipartVec {}
+
+impl Hash for MultipartVec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl From<Vec<Multipart>> for MultipartVec {
+    fn from(parts: Vec<Multipart>) -> MultipartVec {
+        if parts.len() > INLINE {
+            return MultipartVec(Storage::Heap(parts));
+        }
+        parts.into_iter().collect()
+    }
+}
+
+impl From<MultipartVec> for Vec<Multipart> {
+    fn from(parts: MultipartVec) -> Vec<Multipart> {
+        parts.into_vec()
+    }
+}
+
+impl FromIterator<Multipart> for MultipartVec {
+    fn from_iter<I: IntoIterator<Item = Multipart>>(iter: I) -> MultipartVec {
+        let mut parts = MultipartVec::new();
+        parts.extend(iter);
+        parts
+    }
+}
+
+impl Extend<Multipart> for MultipartVec {
+    fn extend<I: IntoIterator<Item = Multipart>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|part| self.push(part));
+    }
+}
+
+impl<'a> IntoIterator for &'a MultipartVec {
+    type Item = &'a Multipart;
+    type IntoIter = std::slice::Iter<'a, Multipart>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MultipartVec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MultipartVec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<MultipartVec, D::Error> {
+        Vec::<Multipart>::deserialize(deserializer).map(MultipartVec::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Multipart, MultipartVec};
+
+    fn part(i: usize) -> Multipart {
+        Multipart::AnyUntil(i.to_string())
+    }
+
+    #[test]
+    fn spills_after_four_parts() {
+        let mut parts = MultipartVec::new();
+        for i in 0..4 {
+            parts.push(part(i));
+        }
+        assert!(!parts.spilled());
+        parts.push(part(4));
+        assert!(parts.spilled());
+        assert_eq!(parts.into_vec(), (0..5).map(part).collect::<Vec<_>>());
+        let inline = MultipartVec::from(vec![part(0), Multipart::AnyEnd]);
+        assert!(!inline.spilled());
+        assert_eq!(&*inline, &[part(0), Multipart::AnyEnd][..]);
+        assert_eq!(inline, (0..1).map(part).chain(Some(Multipart::AnyEnd)).collect());
+    }
+}
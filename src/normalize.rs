@@ -0,0 +1,68 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Unicode normalization of patterns and values, see [`GlobBuilder::normalize`](crate::GlobBuilder::normalize).
+
+use std::borrow::Cow;
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// `value` in NFC, composing a letter and its combining accents into one char where there is one,
+/// only allocating when it isn't already.
+pub(crate) fn nfc(value: &str) -> Cow<'_, str> {
+    if is_nfc(value) {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(value.nfc().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, GlobIgnoreCase, GlobList, MatchContext};
+
+    const NFC: &str = "caf\u{e9}.txt";
+    const NFD: &str = "cafe\u{301}.txt";
+
+    #[test]
+    fn matches_either_encoding() {
+        for pattern in &["caf\u{e9}*", "cafe\u{301}*", "caf?.txt", "*\u{e9}.txt"] {
+            let glob = Glob::builder(pattern).normalize(true).build().unwrap();
+            assert!(glob.is_match(NFC) && glob.is_match(NFD), "{}", pattern);
+            assert!(glob.match_prefix(NFD) && glob.match_within(NFD));
+        }
+        assert!(!Glob::new("caf\u{e9}*").unwrap().is_match(NFD));
+        assert_eq!(Glob::builder("cafe\u{301}*").normalize(true).build().unwrap().as_str(), "cafe\u{301}*");
+
+        let glob = Glob::builder("CAF\u{c9}*").normalize(true).case_insensitive(true).dfa(true).build().unwrap();
+        assert!(glob.is_match(NFD) && glob.is_match_with(&mut MatchContext::new(), NFD));
+        // a combining accent in the next chunk still composes with the char before it
+        let glob = Glob::builder("caf\u{e9}.txt").normalize(true).build().unwrap();
+        assert!(glob.is_match_chunks(&["cafe", "\u{301}.txt"]));
+    }
+
+    #[test]
+    fn lists_normalize_values() {
+        let mut list = GlobList::new();
+        list.add(Glob::builder("caf\u{e9}*").normalize(true).build().unwrap());
+        list.add(Glob::builder("CAF\u{e9}*").normalize(true).case_insensitive(true).build().unwrap());
+        assert!(list.all_match(NFD) && list.count_matches(NFD) == 2);
+        assert!(GlobList::from_bytes(&list.to_bytes()).unwrap().all_match(NFD));
+        assert!(!GlobIgnoreCase::build("CAF\u{e9}*").unwrap().is_match(NFD));
+    }
+}
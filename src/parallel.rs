@@ -0,0 +1,64 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching many values across threads, enabled by the `rayon` feature.
+
+use rayon::prelude::*;
+
+use crate::{GlobList, MatchContext};
+
+impl GlobList {
+    /// Same as [`GlobList::filter`], but matches the values on the rayon thread pool, keeping their order.
+    pub fn par_filter<'v>(&self, values: &[&'v str]) -> Vec<&'v str> {
+        values
+            .par_iter()
+            .map_init(MatchContext::new, |ctx, &value| (self.any_match_with(ctx, value), value))
+            .filter_map(|(matched, value)| if matched { Some(value) } else { None })
+            .collect()
+    }
+
+    /// [`GlobList::any_match`] for each of `values`, matched on the rayon thread pool.
+    pub fn par_any_match_many(&self, values: &[&str]) -> Vec<bool> {
+        values
+            .par_iter()
+            .map_init(MatchContext::new, |ctx, value| self.any_match_with(ctx, value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GlobList;
+
+    #[test]
+    fn par_filter_keeps_order() {
+        let patterns: Vec<String> = ["*.rs", "!target/*"].iter().map(|p| p.to_string()).collect();
+        let list = GlobList::build(&patterns).unwrap();
+        let paths: Vec<String> = (0..1000)
+            .map(|i| match i % 3 {
+                0 => format!("src/{}.rs", i),
+                1 => format!("target/{}.rs", i),
+                _ => format!("doc/{}.md", i),
+            })
+            .collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let expected: Vec<&str> = list.filter(paths.iter().copied()).collect();
+        assert_eq!(list.par_filter(&paths), expected);
+        assert_eq!(list.par_any_match_many(&paths), paths.iter().map(|p| list.any_match(p)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,140 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Building a pattern a segment at a time, without writing and parsing a pattern string.
+
+use crate::combine::{from_segments, push_segment};
+use crate::segments::{CharClass, SEPARATOR};
+use crate::{GlobPattern, Segment};
+
+/// Builds a [`GlobPattern`] out of literals and wildcards, e.g.
+/// `PatternBuilder::new().literal("foo").any().literal(".rs").build()` for `foo*.rs`.
+/// Literals are taken as they are, so `*`, `[` or `{` in them need no escaping.
+#[derive(Debug,Clone,Default)]
+pub struct PatternBuilder {
+    segments: Vec<Segment>,
+}
+
+impl PatternBuilder {
+    pub fn new() -> PatternBuilder {
+        PatternBuilder::default()
+    }
+
+    /// Exactly `value`.
+    pub fn literal(self, value: &str) -> Self {
+        self.push(Segment::Literal(value.to_string()))
+    }
+
+    /// `value` in any case, like a literal in a `(?i:...)` group.
+    pub fn literal_ignore_case(self, value: &str) -> Self {
+        self.push(Segment::LiteralIgnoreCase(value.to_lowercase()))
+    }
+
+    /// Any number of chars, `*`.
+    pub fn any(self) -> Self {
+        self.push(Segment::AnyChars)
+    }
+
+    /// Exactly one char, `?`.
+    pub fn any_char(self) -> Self {
+        self.push(Segment::AnyChar)
+    }
+
+    /// Any number of chars that aren't `/`, `*` with a literal separator.
+    pub fn any_in_component(self) -> Self {
+        self.push(Segment::AnyCharsExceptSeparator)
+    }
+
+    /// One char that isn't `/`, `?` with a literal separator.
+    pub fn any_char_in_component(self) -> Self {
+        self.push(Segment::AnyCharExceptSeparator)
+    }
+
+    /// Any number of whole directories, `**/`. A `/` is added first unless it's at the start or after one.
+    pub fn any_dirs(self) -> Self {
+        let after_separator = match self.segments.last() {
+            None => true,
+            Some(Segment::Literal(s)) => s.ends_with(SEPARATOR),
+            Some(segment) => matches!(segment, Segment::AnyDirs | Segment::AnyVisibleDirs),
+        };
+        let builder = if after_separator { self } else { self.literal("/") };
+        builder.push(Segment::AnyDirs)
+    }
+
+    /// One char in one of the inclusive `ranges`, `[a-z0-9]` for `&[('a', 'z'), ('0', '9')]`.
+    /// A reversed range is read the right way round.
+    pub fn one_of(self, ranges: &[(char, char)]) -> Self {
+        self.push(Segment::Class(class(false, ranges)))
+    }
+
+    /// One char in none of the inclusive `ranges`, `[!a-z]` for `&[('a', 'z')]`.
+    pub fn none_of(self, ranges: &[(char, char)]) -> Self {
+        self.push(Segment::Class(class(true, ranges)))
+    }
+
+    /// The pattern, as the simplest variant matching what the segments do.
+    pub fn build(self) -> GlobPattern {
+        from_segments(self.segments)
+    }
+
+    fn push(mut self, segment: Segment) -> Self {
+        push_segment(&mut self.segments, segment);
+        self
+    }
+}
+
+fn class(negated: bool, ranges: &[(char, char)]) -> CharClass {
+    let ranges = ranges.iter().map(|&(first, last)| (first.min(last), first.max(last))).collect();
+    CharClass { negated, ranges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternBuilder;
+    use crate::{build_glob_pattern, glob_match_prebuilt, GlobPattern};
+
+    #[test]
+    fn builds_like_parsed() {
+        let pattern = PatternBuilder::new().literal("foo").any().literal(".rs").build();
+        assert_eq!(pattern, build_glob_pattern("foo*.rs").unwrap());
+        assert_eq!(PatternBuilder::new().any().build(), GlobPattern::MatchAny);
+        assert_eq!(PatternBuilder::new().literal("a").literal("b").any().any().build(), build_glob_pattern("ab*").unwrap());
+        let class = PatternBuilder::new().literal("v").one_of(&[('9', '0')]).any_char().build();
+        assert_eq!(class, build_glob_pattern("v[0-9]?").unwrap());
+    }
+
+    #[test]
+    fn no_escaping() {
+        let pattern = PatternBuilder::new().literal("[draft] {1}*").any().literal("?").build();
+        assert!(glob_match_prebuilt(&pattern, "[draft] {1}* notes?"));
+        assert!(!glob_match_prebuilt(&pattern, "d notes?"));
+        assert_eq!(pattern.to_string(), "[[]draft] [{]1[}][*]*[?]");
+        assert!(glob_match_prebuilt(&build_glob_pattern(&pattern.to_string()).unwrap(), "[draft] {1}* notes?"));
+    }
+
+    #[test]
+    fn paths() {
+        let pattern = PatternBuilder::new().literal("src").any_dirs().any_in_component().literal(".RS").build();
+        for &(value, expected) in [("src/a.RS", true), ("src/a/b.RS", true), ("src.RS", false), ("src/a/b", false)].iter() {
+            assert_eq!(glob_match_prebuilt(&pattern, value), expected, "{}", value);
+        }
+        let pattern = PatternBuilder::new().any_dirs().literal_ignore_case("README").none_of(&[('/', '/')]).any().build();
+        assert!(glob_match_prebuilt(&pattern, "docs/readme.md"));
+        assert!(!glob_match_prebuilt(&pattern, "docs/readme/x"));
+    }
+}
@@ -0,0 +1,103 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Reading patterns from `.globignore` style files, one per line.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::{GlobError, GlobList, MatchOptions};
+
+/// Why [`GlobList::from_reader`] failed, `line` counts from 1 and the error's position refers to the
+/// line as written, leading spaces included.
+#[derive(Debug)]
+pub enum PatternFileError {
+    Io(io::Error),
+    Pattern { line: usize, error: GlobError },
+}
+
+impl fmt::Display for PatternFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternFileError::Io(e) => write!(f, "reading patterns: {}", e),
+            PatternFileError::Pattern { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl Error for PatternFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatternFileError::Io(e) => Some(e),
+            PatternFileError::Pattern { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for PatternFileError {
+    fn from(e: io::Error) -> PatternFileError {
+        PatternFileError::Io(e)
+    }
+}
+
+impl GlobList {
+    /// Reads one pattern per line of `reader`, each built with `options`. Spaces around a pattern are
+    /// dropped, blank lines and lines starting with `#` are skipped and patterns starting with `!`
+    /// are negated, see [`GlobList::add_negated`].
+    pub fn from_reader<R: BufRead>(reader: R, options: MatchOptions) -> Result<GlobList, PatternFileError> {
+        let mut list = GlobList::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let pattern = line.trim();
+            if pattern.is_empty() || pattern.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+            list.add_pattern(pattern, options).map_err(|error| PatternFileError::Pattern {
+                line: i + 1,
+                error: error.position().map_or(error, |p| error.at(p + indent)),
+            })?;
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternFileError;
+    use crate::{GlobError, GlobList, MatchOptions, MatchOutcome};
+
+    #[test]
+    fn one_pattern_per_line() {
+        let file = "# build output\ntarget/**\n\n  *.log  \r\n!keep.log\n";
+        let list = GlobList::from_reader(file.as_bytes(), MatchOptions::new()).unwrap();
+        assert_eq!(list.len(), 3);
+        assert!(list.matched("target/debug/app").is_included() && list.matched("a.log").is_included());
+        assert!(matches!(list.matched("keep.log"), MatchOutcome::Excluded(_)) && !list.any_match("# build output"));
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let err = GlobList::from_reader("*.rs\n\n  !a[b\n".as_bytes(), MatchOptions::new()).unwrap_err();
+        assert!(matches!(err, PatternFileError::Pattern { line: 3, error: GlobError::UnterminatedClass { position: 4 } }));
+        assert_eq!(err.to_string(), "line 3: unterminated character class starting at position 4");
+        let invalid: &[u8] = b"*.rs\n\xff\n";
+        assert!(matches!(GlobList::from_reader(invalid, MatchOptions::new()), Err(PatternFileError::Io(_))));
+    }
+}
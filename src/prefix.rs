@@ -0,0 +1,75 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Telling whether a value could still grow into a match, see [`GlobPattern::is_match_prefix`].
+
+use crate::segments::{as_segments, match_segments_partial};
+use crate::{CaseMode, GlobPattern};
+
+impl GlobPattern {
+    /// Whether `value` is the start of some value that matches, e.g. for autocompleting or for not descending
+    /// into directories nothing in can match. With a literal separator `src/**/*.rs` is matched by the start
+    /// `src/a/` but not by `target/`, and every value is the start of a match of `*`.
+    pub fn is_match_prefix(&self, value: &str) -> bool {
+        is_match_prefix(self, value, CaseMode::Sensitive)
+    }
+}
+
+/// [`GlobPattern::is_match_prefix`] folding `value` as `case` says while comparing.
+pub(crate) fn is_match_prefix(pattern: &GlobPattern, value: &str, case: CaseMode) -> bool {
+    match pattern {
+        GlobPattern::Alternatives(alternatives) => alternatives.iter().any(|p| is_match_prefix(p, value, case)),
+        GlobPattern::Segmented(segments) => match_segments_partial(segments, value.as_bytes(), case),
+        _ => match_segments_partial(&as_segments(pattern), value.as_bytes(), case),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, Glob};
+
+    #[test]
+    fn prefixes() {
+        let rs = Glob::builder("src/**/*.rs").literal_separator(true).build().unwrap();
+        assert!(rs.is_match_prefix("") && rs.is_match_prefix("sr") && rs.is_match_prefix("src/a/b/"));
+        assert!(rs.is_match_prefix("src/lib.r") && rs.is_match_prefix("src/lib.rs"));
+        assert!(!rs.is_match_prefix("target/") && !rs.is_match_prefix("srcx"));
+        // the value can end in the middle of a directory `**/` matches
+        let dirs = Glob::builder("**/").literal_separator(true).build().unwrap();
+        assert!(dirs.is_match_prefix("aaab") && dirs.is_match("aaab/"));
+        assert!(rs.is_match_prefix("src/a/bc"));
+        let hidden = Glob::builder("**/*.rs").literal_leading_dot(true).literal_separator(true).build().unwrap();
+        assert!(hidden.is_match_prefix("a/b") && !hidden.is_match_prefix("a/.b"));
+        let cases = [("*", "anything", true), ("a*b", "a", true), ("a*b", "b", false), ("log-??.txt", "log-1", true),
+                     ("log-??.txt", "log-123", false), ("{foo,bar}*", "ba", true), ("{foo,bar}*", "baz", false),
+                     ("*.rs", "x/y", true), ("a*b*c", "axxbyy", true), ("[a-c]x", "d", false), ("exact", "exact!", false),
+                     ("/**/[!a]", "/a", true), ("/**/[!a]", "/", true), ("/**/x", "a", false)];
+        for &(pattern, value, expected) in cases.iter() {
+            assert_eq!(build_glob_pattern(pattern).unwrap().is_match_prefix(value), expected, "{} on {}", pattern, value);
+        }
+    }
+
+    #[test]
+    fn prefixes_ignoring_case() {
+        let glob = Glob::builder("Assets/*.PNG").case_insensitive(true).build().unwrap();
+        assert!(glob.is_match_prefix("ASSETS/") && glob.is_match_prefix("assets/x.p") && !glob.is_match_prefix("src/"));
+        let group = Glob::new("(?i:abc)/*").unwrap();
+        assert!(group.is_match_prefix("AB") && !group.is_match_prefix("abd"));
+        assert!(Glob::builder("ABC*").ascii_case_insensitive(true).build().unwrap().is_match_prefix("ab"));
+    }
+}
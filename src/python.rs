@@ -0,0 +1,128 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! PyO3 bindings, so Python scripts match with the same semantics as Rust.
+//!
+//! Exported as the `globber` module with `Glob` and `GlobList` classes, invalid patterns raise `ValueError`.
+//! Build the extension with `maturin build --features python,pyo3/extension-module`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{Glob, GlobError, GlobList};
+
+fn to_py(error: GlobError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+#[pyclass(name = "Glob", module = "globber", frozen)]
+pub struct PyGlob(Glob);
+
+#[pymethods]
+impl PyGlob {
+    #[new]
+    #[pyo3(signature = (pattern, ignore_case = false))]
+    pub fn new(pattern: &str, ignore_case: bool) -> PyResult<PyGlob> {
+        Glob::builder(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(PyGlob)
+            .map_err(to_py)
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    /// The captured wildcards, or None if it doesn't match.
+    pub fn captures(&self, value: &str) -> Option<Vec<String>> {
+        self.0
+            .captures(value)
+            .map(|captures| captures.iter().map(|capture| capture.to_string()).collect())
+    }
+
+    #[getter]
+    pub fn pattern(&self) -> &str {
+        self.0.as_str()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Glob({:?})", self.0.as_str())
+    }
+}
+
+#[pyclass(name = "GlobList", module = "globber", frozen)]
+pub struct PyGlobList(GlobList);
+
+#[pymethods]
+impl PyGlobList {
+    /// Patterns starting with `!` are negated, like for [`GlobList::build`].
+    #[new]
+    #[pyo3(signature = (patterns, ignore_case = false))]
+    pub fn new(patterns: Vec<String>, ignore_case: bool) -> PyResult<PyGlobList> {
+        let list = if ignore_case {
+            GlobList::build_ignore_case(&patterns)
+        } else {
+            GlobList::build(&patterns)
+        };
+        list.map(PyGlobList).map_err(to_py)
+    }
+
+    pub fn any_match(&self, value: &str) -> bool {
+        self.0.any_match(value)
+    }
+
+    pub fn all_match(&self, value: &str) -> bool {
+        self.0.all_match(value)
+    }
+
+    /// The values any pattern matches, in order.
+    pub fn filter(&self, values: Vec<String>) -> Vec<String> {
+        values.into_iter().filter(|value| self.0.any_match(value)).collect()
+    }
+}
+
+#[pymodule]
+fn globber(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGlob>()?;
+    module.add_class::<PyGlobList>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_match_like_rust() {
+        let glob = PyGlob::new("src/*.RS", true).unwrap();
+        assert!(glob.is_match("src/lib.rs"));
+        assert_eq!(glob.captures("src/lib.rs"), Some(vec!["lib".to_string()]));
+        assert_eq!(glob.captures("lib.rs"), None);
+        assert_eq!(glob.pattern(), "src/*.RS");
+        assert!(PyGlob::new("a**", false).is_err());
+
+        let list = PyGlobList::new(vec!["*.rs".into(), "!build.rs".into()], false).unwrap();
+        assert!(list.any_match("lib.rs"));
+        assert!(!list.any_match("build.rs"));
+        assert_eq!(
+            list.filter(vec!["a.rs".into(), "b.c".into(), "build.rs".into()]),
+            vec!["a.rs".to_string()]
+        );
+    }
+}
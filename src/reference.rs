@@ -0,0 +1,149 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! A slow but obviously correct matcher to check the real one against, behind the `reference` feature.
+
+/// Whether `value` matches `pattern`, trying every way of splitting the value between the wildcards.
+///
+/// Supports literals, `*`, `?` and `[...]` classes (negated with `!` or `^`, a `]` right at the start is
+/// part of the class), matched case sensitively. Braces and `**` aren't supported, an unterminated class
+/// doesn't match anything. Takes time exponential in the number of `*` in the worst case.
+pub fn reference_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    match_from(&pattern, &value)
+}
+
+fn match_from(pattern: &[char], value: &[char]) -> bool {
+    match pattern.first() {
+        None => value.is_empty(),
+        Some('*') => (0..=value.len()).any(|skip| match_from(&pattern[1..], &value[skip..])),
+        Some('?') => !value.is_empty() && match_from(&pattern[1..], &value[1..]),
+        Some('[') => match (class(&pattern[1..]), value.first()) {
+            (Some((matches, len)), Some(&ch)) => matches(ch) && match_from(&pattern[1 + len..], &value[1..]),
+            _ => false,
+        },
+        Some(&ch) => value.first() == Some(&ch) && match_from(&pattern[1..], &value[1..]),
+    }
+}
+
+/// The class starting right after its `[`, as whether it matches a char and its length up to and including the `]`.
+fn class(class: &[char]) -> Option<(impl Fn(char) -> bool + '_, usize)> {
+    let negated = matches!(class.first(), Some('!') | Some('^'));
+    let start = negated as usize;
+    // the first char is never the closing `]`
+    let end = start + 1 + class.get(start + 1..)?.iter().position(|&ch| ch == ']')?;
+    let members = &class[start..end];
+    let matches = move |ch: char| {
+        let mut i = 0;
+        let mut found = false;
+        while i < members.len() {
+            if i + 2 < members.len() && members[i + 1] == '-' {
+                found |= members[i] <= ch && ch <= members[i + 2];
+                i += 3;
+            } else {
+                found |= members[i] == ch;
+                i += 1;
+            }
+        }
+        found != negated
+    };
+    Some((matches, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::reference_match;
+    use crate::{fold_case, Glob};
+
+    #[test]
+    fn reference_matches() {
+        assert!(reference_match("a*b?c", "aXXbYc"));
+        assert!(reference_match("*[!a-c]", "abd") && !reference_match("*[!a-c]", "abc"));
+        assert!(reference_match("[]]", "]") && reference_match("[a-]", "-"));
+        assert!(!reference_match("a*a", "a") && !reference_match("[ab", "a"));
+    }
+
+    /// Every string of up to `max_len` chars from `alphabet`.
+    fn all_strings(alphabet: &[char], max_len: usize) -> Vec<String> {
+        let mut strings = vec![String::new()];
+        let mut last = vec![String::new()];
+        for _ in 0..max_len {
+            last = last.iter().flat_map(|s| alphabet.iter().map(move |&ch| format!("{}{}", s, ch))).collect();
+            strings.extend(last.iter().cloned());
+        }
+        strings
+    }
+
+    #[test]
+    fn multipart_matches_like_reference_exhaustively() {
+        assert!(Glob::new("*aba*").unwrap().is_match("aabba aba"));
+        assert!(Glob::new("*aab*").unwrap().is_match("aaab"));
+        let values = all_strings(&['a', 'b'], 7);
+        for pattern in all_strings(&['a', 'b', '*'], 6) {
+            let glob = match Glob::new(&pattern) {
+                Ok(glob) => glob,
+                Err(_) => continue, // empty or adjacent `*`
+            };
+            let ignore_case = Glob::builder(&pattern.to_uppercase()).case_insensitive(true).build().unwrap();
+            for value in &values {
+                let expected = reference_match(&pattern, value);
+                assert_eq!(glob.is_match(value), expected, "{} {}", pattern, value);
+                assert_eq!(glob.is_match_bytes(value.as_bytes()), expected, "{} {}", pattern, value);
+                assert_eq!(ignore_case.is_match(value), expected, "{} {}", pattern, value);
+                let (head, tail) = value.split_at(value.len() / 2);
+                assert_eq!(glob.is_match_chunks(&[head, tail]), expected, "{} {}", pattern, value);
+                // the captures put back together with the literals between them give the value again
+                if let Some(captures) = glob.captures(value) {
+                    let mut literals = pattern.split('*');
+                    let mut rebuilt = literals.next().unwrap().to_string();
+                    for (capture, literal) in captures.iter().zip(literals) {
+                        rebuilt.push_str(capture);
+                        rebuilt.push_str(literal);
+                    }
+                    assert_eq!(&rebuilt, value, "{}", pattern);
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn matches_like_reference(pattern in "([ab*?]|\\[!?[ab]-?[b-d]\\]){1,8}", value in "[abcd]{0,12}") {
+            // adjacent `*` are an error instead
+            let glob = Glob::new(&pattern);
+            prop_assume!(glob.is_ok());
+            let glob = glob.unwrap();
+            let expected = reference_match(&pattern, &value);
+            prop_assert_eq!(glob.is_match(&value), expected);
+            prop_assert_eq!(glob.is_match_bytes(value.as_bytes()), expected);
+            prop_assert_eq!(glob.find(&value).is_some(), expected);
+            prop_assert_eq!(glob.pattern().compile_dfa().unwrap().is_match(&value), expected);
+        }
+
+        #[test]
+        fn matches_ignoring_case_like_reference(pattern in "[aAbB*?]{1,8}", value in "[aAbB]{0,12}") {
+            let glob = Glob::builder(&pattern).case_insensitive(true).build();
+            prop_assume!(glob.is_ok());
+            let expected = reference_match(&fold_case(&pattern), &fold_case(&value));
+            prop_assert_eq!(glob.unwrap().is_match(&value), expected);
+        }
+    }
+}
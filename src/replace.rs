@@ -0,0 +1,142 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Replacing matches with text referring to what the wildcards matched, e.g. renaming `*.jpeg` to `$1.jpg`.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{Captures, Glob};
+
+impl Glob {
+    /// `value` with the first match replaced by `replacement`, or `value` itself if it doesn't match.
+    ///
+    /// In `replacement` `$1`, `$2`, ... are the text matched by each wildcard, `$0` the whole match and `$$` a `$`.
+    /// `${1}` is the same as `$1` but can be followed by a digit. A wildcard the pattern doesn't have is empty.
+    /// `*.jpeg` with `$1.jpg` turns `holiday.jpeg` into `holiday.jpg`.
+    ///
+    /// For an [unanchored](crate::GlobBuilder::unanchored) pattern only the match itself is replaced,
+    /// the text before and after it isn't a wildcard there.
+    pub fn replace<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.replace_n(value, replacement, 1)
+    }
+
+    /// Same as [`Glob::replace`], but replaces every match of an unanchored pattern, see [`Glob::find_iter`].
+    pub fn replace_all<'a>(&self, value: &'a str, replacement: &str) -> Cow<'a, str> {
+        self.replace_n(value, replacement, usize::MAX)
+    }
+
+    fn replace_n<'a>(&self, value: &'a str, replacement: &str, limit: usize) -> Cow<'a, str> {
+        if !self.is_unanchored() {
+            return match self.captures(value) {
+                Some(captures) => Cow::Owned(expand(replacement, value, &captures, 0..captures.len())),
+                None => Cow::Borrowed(value),
+            };
+        }
+        let mut replaced = None;
+        let mut pos = 0;
+        for found in self.find_iter(value).take(limit) {
+            let matched = &value[found.clone()];
+            // the first and last wildcards are what makes it unanchored, around the match
+            let wildcards = match self.captures(matched) {
+                Some(captures) if captures.len() >= 2 => {
+                    let len = captures.len();
+                    (captures, 1..len - 1)
+                },
+                Some(captures) => (captures, 0..0),
+                None => continue,
+            };
+            let replaced = replaced.get_or_insert_with(String::new);
+            replaced.push_str(&value[pos..found.start]);
+            replaced.push_str(&expand(replacement, matched, &wildcards.0, wildcards.1));
+            pos = found.end;
+        }
+        match replaced {
+            Some(mut replaced) => {
+                replaced.push_str(&value[pos..]);
+                Cow::Owned(replaced)
+            },
+            None => Cow::Borrowed(value),
+        }
+    }
+}
+
+/// `replacement` with `$n` replaced by the `n`th capture in `wildcards` and `$0` by `matched`.
+fn expand(replacement: &str, matched: &str, captures: &Captures<'_>, wildcards: Range<usize>) -> String {
+    let mut expanded = String::with_capacity(replacement.len());
+    let mut rest = replacement;
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        rest = &rest[dollar + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        let (digits, after) = match rest.strip_prefix('{').and_then(|braced| braced.split_once('}')) {
+            Some((digits, after)) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => (digits, after),
+            _ => {
+                let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+                (&rest[..len], &rest[len..])
+            },
+        };
+        match digits.parse::<usize>() {
+            Ok(0) => expanded.push_str(matched),
+            Ok(n) => {
+                if n <= wildcards.len() {
+                    expanded.push_str(captures.get(wildcards.start + n - 1).unwrap_or(""));
+                }
+            },
+            // not a reference, e.g. "$x" or a number too long to be one
+            Err(_) if digits.is_empty() => {
+                expanded.push('$');
+                continue;
+            },
+            Err(_) => (),
+        }
+        rest = after;
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Glob;
+
+    #[test]
+    fn replace_with_captures() {
+        let glob = Glob::new("*.jpeg").unwrap();
+        assert_eq!(glob.replace("holiday.jpeg", "$1.jpg"), "holiday.jpg");
+        assert_eq!(glob.replace("holiday.png", "$1.jpg"), "holiday.png");
+        let glob = Glob::new("IMG_*_*.*").unwrap();
+        assert_eq!(glob.replace("IMG_2021_07.png", "${2}1-$1.$3 ($0) $$5 $x $9"), "071-2021.png (IMG_2021_07.png) $5 $x ");
+        let glob = Glob::builder("*.JPEG").case_insensitive(true).build().unwrap();
+        assert_eq!(glob.replace("Beach.jpeg", "$1.jpg"), "Beach.jpg");
+    }
+
+    #[test]
+    fn replace_unanchored() {
+        let glob = Glob::builder("ERROR*timeout").unanchored(true).build().unwrap();
+        let line = "ERROR db timeout, ERROR cache timeout";
+        assert_eq!(glob.replace(line, "[$1]"), "[ db ], ERROR cache timeout");
+        assert_eq!(glob.replace_all(line, "[$1]"), "[ db ], [ cache ]");
+        assert_eq!(glob.replace_all(line, "<$0>"), "<ERROR db timeout>, <ERROR cache timeout>");
+        assert!(matches!(glob.replace_all("all good", "x"), std::borrow::Cow::Borrowed("all good")));
+    }
+}
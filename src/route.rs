@@ -0,0 +1,185 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching URL paths a segment at a time with named parameters, a small router.
+
+use crate::{Glob, GlobError};
+
+/// A pattern for URL paths matched segment by segment, `/users/:id/posts/*` matches `/users/42/posts/7`
+/// with `id` being `42`.
+///
+/// The pattern is split into segments at every `/`. A segment that is `:` followed by a name matches any
+/// one segment and captures it under that name, `**` matches any number of segments and every other
+/// segment is matched like a [`Glob`] against one segment, so `*` never matches a `/`.
+#[derive(Debug,Clone)]
+pub struct RouteGlob {
+    source: String,
+    segments: Vec<RouteSegment>,
+}
+
+#[derive(Debug,Clone)]
+enum RouteSegment {
+    /// before a leading `/`, after a trailing one or between two
+    Empty,
+    Param(String),
+    AnySegments,
+    Glob(Box<Glob>),
+}
+
+/// The named parameters of a matched route, borrowed from the path.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct RouteParams<'a> {
+    params: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> RouteParams<'a> {
+    /// The path segment captured as `name`.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.params.iter().find(|&&(n, _)| n == name).map(|&(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// The names and captured segments in pattern order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.params.iter().copied()
+    }
+}
+
+impl RouteGlob {
+    pub fn new(pattern: &str) -> Result<RouteGlob, GlobError> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for segment in pattern.split('/') {
+            segments.push(match segment {
+                "" => RouteSegment::Empty,
+                "**" => RouteSegment::AnySegments,
+                _ if is_param(segment) => RouteSegment::Param(segment[1..].to_string()),
+                _ => RouteSegment::Glob(Box::new(Glob::new(segment).map_err(|e| e.position().map_or(e, |p| e.at(start + p)))?)),
+            });
+            start += segment.len() + 1;
+        }
+        Ok(RouteGlob { source: pattern.to_string(), segments })
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The names of the parameters in pattern order.
+    pub fn param_names(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|s| match s {
+            RouteSegment::Param(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.captures(path).is_some()
+    }
+
+    /// The parameters captured from `path`, `None` if it doesn't match.
+    pub fn captures<'a>(&'a self, path: &'a str) -> Option<RouteParams<'a>> {
+        let path: Vec<&str> = path.split('/').collect();
+        let mut params = Vec::new();
+        if match_route(&self.segments, &path, &mut params) {
+            Some(RouteParams { params })
+        } else {
+            None
+        }
+    }
+}
+
+/// `:` and a name of letters, digits and `_`.
+fn is_param(segment: &str) -> bool {
+    segment.strip_prefix(':').is_some_and(|name| !name.is_empty() && name.chars().all(|ch| ch.is_alphanumeric() || ch == '_'))
+}
+
+fn match_route<'a>(segments: &'a [RouteSegment], path: &[&'a str], params: &mut Vec<(&'a str, &'a str)>) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(first) => first,
+        None => return path.is_empty(),
+    };
+    match segment {
+        // shortest first, like `*`
+        RouteSegment::AnySegments => (0..=path.len()).any(|skip| {
+            let captured = params.len();
+            match_route(rest, &path[skip..], params) || {
+                params.truncate(captured);
+                false
+            }
+        }),
+        RouteSegment::Param(name) => match path.split_first() {
+            Some((value, path)) if !value.is_empty() => {
+                params.push((name, value));
+                match_route(rest, path, params) || {
+                    params.pop();
+                    false
+                }
+            },
+            _ => false,
+        },
+        RouteSegment::Empty => match path.split_first() {
+            Some((value, path)) => value.is_empty() && match_route(rest, path, params),
+            None => false,
+        },
+        RouteSegment::Glob(glob) => match path.split_first() {
+            Some((value, path)) => glob.is_match(value) && match_route(rest, path, params),
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GlobError, RouteGlob};
+
+    #[test]
+    fn named_params() {
+        let route = RouteGlob::new("/users/*/posts/:id").unwrap();
+        let params = route.captures("/users/ann/posts/42").unwrap();
+        assert_eq!(params.get("id"), Some("42"));
+        assert_eq!(params.len(), 1);
+        assert!(!route.is_match("/users/ann/bob/posts/42"));
+        assert!(!route.is_match("/users/ann/posts/"));
+        assert!(!route.is_match("/users/ann/posts/42/comments"));
+        let route = RouteGlob::new("/:org/:repo/blob/**/*.rs").unwrap();
+        assert_eq!(route.param_names().collect::<Vec<_>>(), ["org", "repo"]);
+        let params = route.captures("/rust-lang/rust/blob/master/src/lib.rs").unwrap();
+        assert_eq!(params.iter().collect::<Vec<_>>(), [("org", "rust-lang"), ("repo", "rust")]);
+        assert!(route.is_match("/a/b/blob/lib.rs"));
+        assert!(!route.is_match("/a/b/blob/lib.md"));
+    }
+
+    #[test]
+    fn plain_segments() {
+        let route = RouteGlob::new("/static/{css,js}/:file").unwrap();
+        assert!(route.is_match("/static/css/site.css"));
+        assert!(!route.is_match("/static/img/a.png"));
+        // `:` only starts a parameter followed by a name
+        assert!(RouteGlob::new("/a/:").unwrap().is_match("/a/:"));
+        assert!(matches!(RouteGlob::new("/files/[ab"), Err(GlobError::UnterminatedClass { position: 7 })));
+    }
+}
@@ -0,0 +1,153 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Allow and deny rules, the access-control use of patterns.
+
+use std::iter::FromIterator;
+
+use crate::{Glob, GlobSet};
+
+/// What a [`Rule`] does with the values it matches.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+/// A pattern with the action to take on the values it matches, rules with a higher priority win over
+/// lower ones.
+#[derive(Debug,Clone)]
+pub struct Rule {
+    glob: Glob,
+    action: Action,
+    priority: i32,
+}
+
+impl Rule {
+    pub fn new(glob: Glob, action: Action) -> Rule {
+        Rule { glob, action, priority: 0 }
+    }
+
+    pub fn allow(glob: Glob) -> Rule {
+        Rule::new(glob, Action::Allow)
+    }
+
+    pub fn deny(glob: Glob) -> Rule {
+        Rule::new(glob, Action::Deny)
+    }
+
+    /// 0 by default.
+    pub fn priority(mut self, priority: i32) -> Rule {
+        self.priority = priority;
+        self
+    }
+
+    pub fn glob(&self) -> &Glob {
+        &self.glob
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+}
+
+/// Rules matched all at once through a [`GlobSet`]. The matching rule with the highest priority decides
+/// what happens to a value, the first one given out of those with the same priority, and the default
+/// action ([`Action::Deny`] unless set) when no rule matches.
+#[derive(Debug,Clone)]
+pub struct RuleSet {
+    set: GlobSet,
+    /// action and priority of each pattern in `set`
+    rules: Vec<(Action, i32)>,
+    default: Action,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Rule>) -> RuleSet {
+        let (globs, rules) = rules.into_iter().map(|rule| (rule.glob, (rule.action, rule.priority))).unzip();
+        RuleSet { set: GlobSet::new(globs), rules, default: Action::Deny }
+    }
+
+    /// The action for values no rule matches.
+    pub fn default_action(mut self, action: Action) -> RuleSet {
+        self.default = action;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// What to do with `value`.
+    pub fn evaluate(&self, value: &str) -> Action {
+        self.deciding_rule(value).map_or(self.default, |i| self.rules[i].0)
+    }
+
+    /// The index of the rule deciding what to do with `value`, `None` if no rule matches it.
+    pub fn deciding_rule(&self, value: &str) -> Option<usize> {
+        // matches come in ascending order, so the first with the highest priority is kept
+        self.set.matches(value).into_iter().rev().max_by_key(|&i| self.rules[i].1)
+    }
+}
+
+impl FromIterator<Rule> for RuleSet {
+    fn from_iter<I: IntoIterator<Item = Rule>>(iter: I) -> RuleSet {
+        RuleSet::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Action, Glob, Rule, RuleSet};
+
+    fn glob(pattern: &str) -> Glob {
+        Glob::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn priority_then_order() {
+        let rules = RuleSet::new(vec![
+            Rule::allow(glob("/public/*")),
+            Rule::deny(glob("*.secret")),
+            Rule::deny(glob("/public/drafts/*")).priority(1),
+            Rule::allow(glob("/public/drafts/shared-*")).priority(2),
+        ]);
+        assert_eq!(rules.evaluate("/public/index.html"), Action::Allow);
+        // same priority, the earlier rule wins
+        assert_eq!(rules.evaluate("/public/key.secret"), Action::Allow);
+        assert_eq!(rules.evaluate("/public/drafts/post"), Action::Deny);
+        assert_eq!(rules.evaluate("/public/drafts/shared-post"), Action::Allow);
+        assert_eq!(rules.deciding_rule("/public/drafts/post"), Some(2));
+        assert_eq!(rules.evaluate("/private/a"), Action::Deny);
+        assert_eq!(rules.deciding_rule("/private/a"), None);
+    }
+
+    #[test]
+    fn default_action() {
+        let rules: RuleSet = vec![Rule::deny(glob("*.exe"))].into_iter().collect();
+        let rules = rules.default_action(Action::Allow);
+        assert_eq!(rules.evaluate("setup.exe"), Action::Deny);
+        assert_eq!(rules.evaluate("notes.txt"), Action::Allow);
+        assert_eq!(RuleSet::new(Vec::new()).evaluate("a"), Action::Deny);
+    }
+}
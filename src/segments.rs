@@ -0,0 +1,603 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+use crate::budget::Budget;
+use crate::case::{fold_case, folded_from};
+use crate::{Anchoring, CaseMode, GlobError, GlobPattern, Multipart, PatternOptions, Syntax, Unit};
+
+/// A piece of a pattern using wildcards beyond `*` (`?`, `[...]` and `**`), matched in order.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Segment {
+    /// Chars matched as they are
+    Literal(String),
+    /// `?`, exactly one char
+    AnyChar,
+    /// `*`, any number of chars
+    AnyChars,
+    /// `[...]`, one char in (or not in) the class
+    Class(CharClass),
+    /// `?` with a literal separator, one char that isn't `/`
+    AnyCharExceptSeparator,
+    /// `*` with a literal separator, any number of chars that aren't `/`
+    AnyCharsExceptSeparator,
+    /// `**/` at the start or after a `/`, any number of whole path components (each with its `/`)
+    AnyDirs,
+    /// `**` at the end after a `/` (or on its own), any number of chars including `/`
+    AnyPath,
+    /// A literal in a `(?i:...)` group, case folded, matching the value in any case
+    /// even when the rest of the pattern is case sensitive.
+    LiteralIgnoreCase(String),
+    /// `*` or `**` that doesn't match a `.` at the start of a path component, for
+    /// [`GlobBuilder::literal_leading_dot`](crate::GlobBuilder::literal_leading_dot)
+    AnyVisibleChars,
+    /// `**/` that doesn't match path components starting with a `.`, for
+    /// [`GlobBuilder::literal_leading_dot`](crate::GlobBuilder::literal_leading_dot)
+    AnyVisibleDirs,
+}
+
+impl Segment {
+    /// Whether the segment can match any number of chars, like `*`.
+    pub(crate) fn is_any_length(&self) -> bool {
+        matches!(self, Segment::AnyChars | Segment::AnyCharsExceptSeparator | Segment::AnyDirs | Segment::AnyPath |
+                       Segment::AnyVisibleChars | Segment::AnyVisibleDirs)
+    }
+
+    /// Whether the segment can't skip over every char, which the plain `*` backtracking relies on.
+    fn is_restricted(&self) -> bool {
+        matches!(self, Segment::AnyCharsExceptSeparator | Segment::AnyDirs | Segment::AnyVisibleChars | Segment::AnyVisibleDirs)
+    }
+}
+
+pub(crate) const SEPARATOR: char = '/';
+
+/// A bracket expression like `[abc]`, `[a-z]` or `[!0-9]`, matching one char.
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CharClass {
+    pub(crate) negated: bool,
+    /// inclusive, a single char is a range of one
+    pub(crate) ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// The class with every char in it in any case, for a class in a `(?i:...)` group.
+    pub(crate) fn ignoring_case(mut self) -> CharClass {
+        let contains = |ranges: &[(char, char)], ch: char| ranges.iter().any(|&(first, last)| first <= ch && ch <= last);
+        let mut variants = Vec::new();
+        for (&folded, others) in folded_from() {
+            if contains(&self.ranges, folded) || others.iter().any(|&other| contains(&self.ranges, other)) {
+                variants.push(folded);
+                variants.extend(others);
+            }
+        }
+        variants.sort_unstable();
+        self.ranges.extend(variants.into_iter().map(|ch| (ch, ch)));
+        self
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        self.ranges.iter().any(|&(first, last)| first <= ch && ch <= last) != self.negated
+    }
+
+    /// The inclusive ranges in the class, before negating it.
+    pub(crate) fn ranges(&self) -> &[(char, char)] {
+        &self.ranges
+    }
+}
+
+/// Parses a class after its opening `[` at `open`, a `]` right at the start is part of the class
+/// and `!` or `^` there negates it.
+/// With a literal separator a negated class doesn't match `/` either.
+fn parse_class(chars: &mut Peekable<CharIndices<'_>>, open: usize, options: &PatternOptions) -> Result<CharClass, GlobError> {
+    let negated = chars.next_if(|&(_, ch)| ch == '!' || ch == '^').is_some();
+    let mut ranges = Vec::new();
+    loop {
+        let (position, first) = chars.next().ok_or(GlobError::UnterminatedClass { position: open })?;
+        if first == ']' && !ranges.is_empty() {
+            if negated && options.literal_separator {
+                ranges.push((SEPARATOR, SEPARATOR));
+            }
+            return Ok(CharClass { negated, ranges });
+        }
+        let mut ahead = chars.clone();
+        let last = match (ahead.next(), ahead.next()) {
+            (Some((_, '-')), Some((_, last))) if last != ']' => {
+                chars.next();
+                chars.next();
+                last
+            },
+            _ => first,
+        };
+        if last < first {
+            return Err(GlobError::ReversedRange { position });
+        }
+        ranges.push((first, last));
+    }
+}
+
+/// Splits `pattern` into segments. `**` has to be a whole path component (`**/`, `/**` at the end, or on its own),
+/// elsewhere adjacent `*` are an error like they are for [`Multipart`](crate::Multipart).
+/// A `*` that has to match at least `wildcard_min_len` chars is built as that many `?` followed by `*`.
+///
+/// Literals and classes in a `(?i:...)` group ignore case, the first `)` ends the group and groups don't nest.
+/// With [`Syntax::Gitignore`] there are no groups and `\` makes the next char a literal instead.
+pub(crate) fn build_segments(pattern: &str, options: &PatternOptions) -> Result<Vec<Segment>, GlobError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.char_indices().peekable();
+    let mut previous = None;
+    // where the `(?i:` group the chars are in started
+    let mut group = None;
+    let flush = |segments: &mut Vec<Segment>, literal: &mut String, group: Option<usize>| {
+        if literal.is_empty() {
+            return;
+        }
+        let literal = std::mem::take(literal);
+        segments.push(match group {
+            Some(_) => Segment::LiteralIgnoreCase(fold_case(&literal)),
+            None => Segment::Literal(literal),
+        });
+    };
+    while let Some((position, ch)) = chars.next() {
+        match ch {
+            '(' if options.syntax == Syntax::Glob && group.is_none() && pattern[position..].starts_with("(?i:") => {
+                flush(&mut segments, &mut literal, group);
+                group = Some(position);
+                chars.nth(2);
+                continue;
+            },
+            ')' if group.is_some() => {
+                flush(&mut segments, &mut literal, group);
+                group = None;
+                continue;
+            },
+            _ => (),
+        }
+        let wildcard = match ch {
+            '\\' if options.syntax == Syntax::Gitignore => {
+                let escaped = chars.next().map_or('\\', |(_, escaped)| escaped);
+                literal.push(escaped);
+                previous = Some(escaped);
+                continue;
+            },
+            '?' if options.literal_separator => Segment::AnyCharExceptSeparator,
+            '?' => Segment::AnyChar,
+            '*' => match chars.next_if(|&(_, ch)| ch == '*') {
+                Some((second, _)) => {
+                    let component_start = matches!(previous, None | Some(SEPARATOR));
+                    match chars.peek() {
+                        Some(&(_, SEPARATOR)) if component_start => {
+                            chars.next();
+                            Segment::AnyDirs
+                        },
+                        None if component_start => Segment::AnyPath,
+                        // `**` itself is fine there, the third star is the one too many
+                        Some(&(third, '*')) if component_start =>
+                            return Err(GlobError::EmptyWildcardSegment { position: third }),
+                        _ => return Err(GlobError::EmptyWildcardSegment { position: second }),
+                    }
+                },
+                None => {
+                    let (one, any) = if options.literal_separator {
+                        (Segment::AnyCharExceptSeparator, Segment::AnyCharsExceptSeparator)
+                    } else {
+                        (Segment::AnyChar, Segment::AnyChars)
+                    };
+                    flush(&mut segments, &mut literal, group);
+                    segments.extend(std::iter::repeat_n(one, options.wildcard_min_len));
+                    any
+                },
+            },
+            '[' => match parse_class(&mut chars, position, options)? {
+                class if group.is_some() => Segment::Class(class.ignoring_case()),
+                class => Segment::Class(class),
+            },
+            _ => {
+                literal.push(ch);
+                previous = Some(ch);
+                continue;
+            },
+        };
+        // `**/` took the separator with it, so a following `**` still starts a component
+        previous = Some(if matches!(wildcard, Segment::AnyDirs) { SEPARATOR } else { ch });
+        flush(&mut segments, &mut literal, group);
+        segments.push(wildcard);
+    }
+    if let Some(position) = group {
+        return Err(GlobError::UnterminatedGroup { position });
+    }
+    flush(&mut segments, &mut literal, group);
+    Ok(segments)
+}
+
+impl GlobPattern {
+    /// The literals and wildcards the pattern matches with, in order, whichever variant it was built as.
+    /// A pattern with alternatives has none of its own, each of its [`alternatives`](GlobPattern::alternatives)
+    /// has its segments.
+    pub fn segments(&self) -> impl Iterator<Item = Segment> {
+        match self {
+            GlobPattern::Alternatives(_) => Vec::new(),
+            _ => as_segments(self),
+        }.into_iter()
+    }
+
+    /// The patterns the value is matched against one by one, `a` and `b` for `{a,b}`, or the pattern
+    /// itself if it has no alternatives.
+    pub fn alternatives(&self) -> impl Iterator<Item = &GlobPattern> {
+        fn flatten<'a>(pattern: &'a GlobPattern, into: &mut Vec<&'a GlobPattern>) {
+            match pattern {
+                GlobPattern::Alternatives(alternatives) => alternatives.iter().for_each(|p| flatten(p, into)),
+                _ => into.push(pattern),
+            }
+        }
+        let mut patterns = Vec::new();
+        flatten(self, &mut patterns);
+        patterns.into_iter()
+    }
+}
+
+/// The segments a pattern without alternatives is made of, a pattern without `?`, classes or `**` as
+/// literals and `*`.
+pub(crate) fn as_segments(pattern: &GlobPattern) -> Vec<Segment> {
+    let literal = |s: &String| Segment::Literal(s.clone());
+    match pattern {
+        GlobPattern::MatchAny => vec![Segment::AnyChars],
+        GlobPattern::MatchFull(s) => vec![literal(s)],
+        GlobPattern::MatchStart(s) => vec![literal(s), Segment::AnyChars],
+        GlobPattern::MatchEnd(s) => vec![Segment::AnyChars, literal(s)],
+        GlobPattern::MatchBothEnds(start, end) => vec![literal(start), Segment::AnyChars, literal(end)],
+        GlobPattern::Multipart(multi) => multi
+            .iter()
+            .flat_map(|part| match part {
+                Multipart::ExactStart(s) => vec![literal(s)],
+                Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => vec![Segment::AnyChars, literal(s)],
+                Multipart::AnyEnd => vec![Segment::AnyChars],
+            })
+            .collect(),
+        GlobPattern::Segmented(segments) => segments.clone(),
+        GlobPattern::Alternatives(_) => unreachable!("alternatives are taken apart one by one"),
+    }
+}
+
+/// Matches `value` against `segments`, `*` backtracks to the last `*` only, which is enough
+/// since everything after it can then be matched anywhere further along.
+///
+/// With `ranges` the range (in units) matched by each segment is written to it,
+/// literals are placed as far left as possible.
+pub(crate) fn match_segments<U: Unit>(segments: &[Segment], value: &[U], anchoring: Anchoring,
+                                      ranges: Option<&mut Vec<Range<usize>>>) -> bool {
+    match_segments_with(segments, value, anchoring, ranges, CaseMode::Sensitive, &mut Budget::unlimited())
+}
+
+/// [`match_segments`] for segments built from a pattern folded as `case` says, folding `value` one char
+/// at a time while comparing instead of up front.
+pub(crate) fn match_segments_folded<U: Unit>(segments: &[Segment], value: &[U], anchoring: Anchoring, case: CaseMode) -> bool {
+    match_segments_with(segments, value, anchoring, None, case, &mut Budget::unlimited())
+}
+
+/// [`match_segments_folded`] writing the range matched by each segment to `ranges` like [`match_segments`],
+/// the ranges are in `value` itself rather than in its case folded form.
+pub(crate) fn match_segments_folded_ranges<U: Unit>(segments: &[Segment], value: &[U], case: CaseMode,
+                                                    ranges: &mut Vec<Range<usize>>) -> bool {
+    match_segments_with(segments, value, Anchoring::Full, Some(ranges), case, &mut Budget::unlimited())
+}
+
+/// [`match_segments_folded`] taking a step out of `budget` for every place it tries to match a segment at,
+/// `None` if it ran out before knowing whether `value` matches.
+pub(crate) fn match_segments_budgeted<U: Unit>(segments: &[Segment], value: &[U], anchoring: Anchoring, case: CaseMode,
+                                               budget: &mut Budget) -> Option<bool> {
+    let matched = match_segments_with(segments, value, anchoring, None, case, budget);
+    if budget.exceeded() {
+        None
+    } else {
+        Some(matched)
+    }
+}
+
+fn match_segments_with<U: Unit>(segments: &[Segment], value: &[U], anchoring: Anchoring,
+                                mut ranges: Option<&mut Vec<Range<usize>>>, case: CaseMode, budget: &mut Budget) -> bool {
+    if let Some(ranges) = ranges.as_deref_mut() {
+        ranges.clear();
+    }
+    if segments.iter().any(Segment::is_restricted) {
+        return match_restricted(segments, value, anchoring, ranges, case, budget);
+    }
+    let anchor_end = matches!(anchoring, Anchoring::Full | Anchoring::Suffix);
+    // (segment after the `*`, where the `*` stopped, ranges recorded up to and including the `*`)
+    let mut star = match anchoring {
+        Anchoring::Suffix | Anchoring::Within => Some((0, 0, 0)), // as if the pattern started with `*`
+        _ => None,
+    };
+    let mut seg = 0;
+    let mut pos = 0;
+    loop {
+        if !budget.step() {
+            return false;
+        }
+        let len = match segments.get(seg) {
+            Some(Segment::AnyChars) | Some(Segment::AnyPath) => {
+                star = Some((seg + 1, pos, ranges.as_deref().map_or(0, |r| r.len() + 1)));
+                Some(0)
+            },
+            Some(segment) => single_len(segment, &value[pos..], case),
+            None if pos == value.len() || !anchor_end => return true,
+            None => None,
+        };
+        if let Some(len) = len {
+            if let Some(ranges) = ranges.as_deref_mut() {
+                ranges.push(pos..pos + len);
+            }
+            seg += 1;
+            pos += len;
+            continue;
+        }
+
+        // let the last `*` match one more char and try again from there
+        match star {
+            Some((after, star_pos, recorded)) if star_pos < value.len() => {
+                let next = star_pos + U::char_len(&value[star_pos..]);
+                if let Some(ranges) = ranges.as_deref_mut() {
+                    ranges.truncate(recorded);
+                    if recorded > 0 {
+                        ranges[recorded - 1].end = next;
+                    }
+                }
+                star = Some((after, next, recorded));
+                seg = after;
+                pos = next;
+            },
+            _ => return false,
+        }
+    }
+}
+
+/// Turns the range of each segment into the literal spans of the match, in place, with an empty span
+/// between wildcards next to each other so captures can tell them apart.
+pub(crate) fn literal_spans(segments: &[Segment], ranges: &mut Vec<Range<usize>>) {
+    let mut kept = 0;
+    let mut previous_wildcard = false;
+    for (i, segment) in segments.iter().enumerate() {
+        let range = ranges[i].clone();
+        let span = match segment {
+            Segment::Literal(_) | Segment::LiteralIgnoreCase(_) => Some(range),
+            _ if previous_wildcard => Some(range.start..range.start),
+            _ => None,
+        };
+        previous_wildcard = !matches!(segment, Segment::Literal(_) | Segment::LiteralIgnoreCase(_));
+        if let Some(span) = span {
+            ranges[kept] = span;
+            kept += 1;
+        }
+    }
+    ranges.truncate(kept);
+}
+
+/// Length in units of what a segment matching a fixed number of chars matches at the start of `value`,
+/// chars of `value` are folded as `case` says before comparing.
+fn single_len<U: Unit>(segment: &Segment, value: &[U], case: CaseMode) -> Option<usize> {
+    let ch = if value.is_empty() {
+        None
+    } else {
+        let ch = U::first_char(value);
+        match case {
+            CaseMode::Sensitive => Some(ch),
+            // a char folding to several (e.g. `ß` with full case folding) is only compared by its first one here
+            CaseMode::Insensitive | CaseMode::TurkicInsensitive => case.fold_char(ch).next(),
+            CaseMode::AsciiInsensitive => Some(ch.to_ascii_lowercase()),
+        }
+    };
+    match (segment, ch) {
+        (Segment::Literal(literal), _) | (Segment::LiteralIgnoreCase(literal), _) if case.folds_chars() =>
+            folded_literal_len(literal, value, case),
+        (Segment::LiteralIgnoreCase(literal), _) => folded_literal_len(literal, value, CaseMode::Insensitive),
+        (Segment::Literal(literal), _) => {
+            let ascii = case == CaseMode::AsciiInsensitive;
+            let mut rest = value.iter().map(|&u| if ascii { u.ascii_lowercase() } else { u });
+            let mut len = 0;
+            if U::units(literal).all(|u| { len += 1; rest.next() == Some(u) }) {
+                Some(len)
+            } else {
+                None
+            }
+        },
+        (Segment::AnyChar, Some(_)) => Some(U::char_len(value)),
+        (Segment::AnyCharExceptSeparator, Some(ch)) if ch != SEPARATOR => Some(U::char_len(value)),
+        (Segment::Class(class), Some(ch)) if class.contains(ch) => Some(U::char_len(value)),
+        _ => None,
+    }
+}
+
+/// Whether all of `value` is the start of what the literal `segment` matches, for matching partially.
+fn literal_continues<U: Unit>(segment: &Segment, mut value: &[U], case: CaseMode) -> bool {
+    let (literal, case) = match segment {
+        Segment::Literal(literal) => (literal, case),
+        Segment::LiteralIgnoreCase(literal) if case.folds_chars() => (literal, case),
+        Segment::LiteralIgnoreCase(literal) => (literal, CaseMode::Insensitive),
+        _ => return false,
+    };
+    let mut expected = literal.chars();
+    while !value.is_empty() {
+        let ch = U::first_char(value);
+        let matched = match case {
+            CaseMode::Sensitive => expected.next() == Some(ch),
+            CaseMode::AsciiInsensitive => expected.next() == Some(ch.to_ascii_lowercase()),
+            CaseMode::Insensitive | CaseMode::TurkicInsensitive => case.fold_char(ch).all(|folded| expected.next() == Some(folded)),
+        };
+        if !matched {
+            return false;
+        }
+        value = &value[U::char_len(value)..];
+    }
+    true
+}
+
+/// Length in units of the start of `value` that folds to `literal`, a char has to fold to chars
+/// of the literal entirely, with full case folding `ß` matches `ss` but not a single `s`.
+/// Chars of `value` are folded as `case` says.
+fn folded_literal_len<U: Unit>(literal: &str, value: &[U], case: CaseMode) -> Option<usize> {
+    let mut expected = literal.chars();
+    let mut len = 0;
+    while !expected.as_str().is_empty() {
+        if len == value.len() {
+            return None;
+        }
+        if !case.fold_char(U::first_char(&value[len..])).all(|folded| expected.next() == Some(folded)) {
+            return None;
+        }
+        len += U::char_len(&value[len..]);
+    }
+    Some(len)
+}
+
+/// Matches patterns with wildcards that can't skip over `/`, where giving up once the last `*`
+/// can't go further isn't enough and earlier ones have to be tried too.
+/// Remembers which (segment, position) pairs didn't match so each is only tried once.
+fn match_restricted<U: Unit>(segments: &[Segment], value: &[U], anchoring: Anchoring,
+                             mut ranges: Option<&mut Vec<Range<usize>>>, case: CaseMode, budget: &mut Budget) -> bool {
+    let mut failed = vec![false; (segments.len() + 1) * (value.len() + 1)];
+    let mut matcher = Restricted {
+        segments,
+        value,
+        anchor_end: matches!(anchoring, Anchoring::Full | Anchoring::Suffix),
+        partial: false,
+        case,
+        failed: &mut failed,
+        budget,
+    };
+    if matches!(anchoring, Anchoring::Full | Anchoring::Prefix) {
+        return matcher.match_from(0, 0, &mut ranges);
+    }
+    // as if the pattern started with `*`
+    let mut start = 0;
+    loop {
+        if matcher.match_from(0, start, &mut ranges) {
+            return true;
+        }
+        if start == value.len() || matcher.budget.exceeded() {
+            return false;
+        }
+        start += U::char_len(&value[start..]);
+    }
+}
+
+/// Whether `value` (folded as `case` says while comparing) is the start of some value matching `segments`,
+/// see [`GlobPattern::is_match_prefix`](crate::GlobPattern::is_match_prefix).
+pub(crate) fn match_segments_partial<U: Unit>(segments: &[Segment], value: &[U], case: CaseMode) -> bool {
+    let mut failed = vec![false; (segments.len() + 1) * (value.len() + 1)];
+    let mut matcher = Restricted {
+        segments,
+        value,
+        anchor_end: true,
+        partial: true,
+        case,
+        failed: &mut failed,
+        budget: &mut Budget::unlimited(),
+    };
+    matcher.match_from(0, 0, &mut None)
+}
+
+struct Restricted<'a, U> {
+    segments: &'a [Segment],
+    value: &'a [U],
+    anchor_end: bool,
+    /// the value only has to be the start of one that matches, once it runs out the rest of the pattern
+    /// can match whatever would follow
+    partial: bool,
+    case: CaseMode,
+    failed: &'a mut [bool],
+    budget: &'a mut Budget,
+}
+
+impl<U: Unit> Restricted<'_, U> {
+    fn match_from(&mut self, seg: usize, pos: usize, ranges: &mut Option<&mut Vec<Range<usize>>>) -> bool {
+        let key = seg * (self.value.len() + 1) + pos;
+        if self.failed[key] || !self.budget.step() {
+            return false;
+        }
+        if self.partial && pos == self.value.len() {
+            return true;
+        }
+        let segment = match self.segments.get(seg) {
+            Some(segment) => segment,
+            None => return pos == self.value.len() || !self.anchor_end,
+        };
+
+        let recorded = ranges.as_deref().map_or(0, Vec::len);
+        let mut end = pos;
+        let matched = if segment.is_any_length() {
+            // try every place the wildcard can stop at, shortest first, a partial value can run out
+            // in the middle of a directory name
+            loop {
+                let can_stop = !matches!(segment, Segment::AnyDirs | Segment::AnyVisibleDirs) || end == pos ||
+                    self.char_before_is_separator(end) || (self.partial && end == self.value.len());
+                if can_stop && self.try_next(seg, pos, end, ranges) {
+                    break true;
+                }
+                if self.budget.exceeded() {
+                    break false;
+                }
+                if end == self.value.len() ||
+                    (matches!(segment, Segment::AnyCharsExceptSeparator) && U::first_char(&self.value[end..]) == SEPARATOR) ||
+                    (matches!(segment, Segment::AnyVisibleChars | Segment::AnyVisibleDirs) && self.hidden_at(end)) {
+                    break false;
+                }
+                end += U::char_len(&self.value[end..]);
+            }
+        } else {
+            match single_len(segment, &self.value[pos..], self.case) {
+                Some(len) => self.try_next(seg, pos, pos + len, ranges),
+                None => self.partial && literal_continues(segment, &self.value[pos..], self.case),
+            }
+        };
+        if !matched {
+            if let Some(ranges) = ranges.as_deref_mut() {
+                ranges.truncate(recorded);
+            }
+            self.failed[key] = true;
+        }
+        matched
+    }
+
+    /// Records `pos..end` for the segment at `seg` and matches the rest from `end`.
+    fn try_next(&mut self, seg: usize, pos: usize, end: usize, ranges: &mut Option<&mut Vec<Range<usize>>>) -> bool {
+        if let Some(ranges) = ranges.as_deref_mut() {
+            ranges.truncate(seg);
+            ranges.push(pos..end);
+        }
+        self.match_from(seg + 1, end, ranges)
+    }
+
+    /// Whether a path component starting with `.` starts at `pos`.
+    fn hidden_at(&self, pos: usize) -> bool {
+        U::first_char(&self.value[pos..]) == '.' && (pos == 0 || self.char_before_is_separator(pos))
+    }
+
+    fn char_before_is_separator(&self, pos: usize) -> bool {
+        // `/` is one unit whether the units are chars or bytes
+        pos > 0 && U::first_char(&self.value[pos - 1..]) == SEPARATOR
+    }
+}
@@ -0,0 +1,381 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+use crate::{CaseMode, Glob, GlobError, GlobPattern, MatchOptions, Multipart, Segment};
+
+/// Many patterns matched at once, indexed by their literal starts and ends so matching a value
+/// only checks the patterns that could match it instead of every pattern.
+///
+/// Patterns are identified by their index in the order they were given. Unlike [`GlobList`](crate::GlobList)
+/// a leading `!` isn't special.
+#[derive(Debug,Clone,Default)]
+pub struct GlobSet {
+    globs: Vec<Glob>,
+    /// one per case mode, each looked up with the value folded that way
+    case_sensitive: Index,
+    ignore_case: Index,
+    ascii_ignore_case: Index,
+    turkic_ignore_case: Index,
+}
+
+impl GlobSet {
+    pub fn new(globs: Vec<Glob>) -> GlobSet {
+        let mut set = GlobSet::default();
+        for (i, glob) in globs.iter().enumerate() {
+            let index = match glob.case {
+                CaseMode::Sensitive => &mut set.case_sensitive,
+                CaseMode::Insensitive => &mut set.ignore_case,
+                CaseMode::AsciiInsensitive => &mut set.ascii_ignore_case,
+                CaseMode::TurkicInsensitive => &mut set.turkic_ignore_case,
+            };
+            if glob.normalized {
+                // values are normalized before matching, their literals can't be looked up as they are
+                index.unanchored.push((i, String::new()));
+            } else {
+                index.add(&glob.pattern, i);
+            }
+        }
+        set.globs = globs;
+        set
+    }
+
+    pub fn build(patterns: &[String]) -> Result<GlobSet, GlobError> {
+        GlobSet::build_with_options(patterns, MatchOptions::new())
+    }
+
+    pub fn build_ignore_case(patterns: &[String]) -> Result<GlobSet, GlobError> {
+        GlobSet::build_with_options(patterns, MatchOptions { case_sensitive: false, ..MatchOptions::new() })
+    }
+
+    /// Builds each pattern with `options`.
+    pub fn build_with_options(patterns: &[String], options: MatchOptions) -> Result<GlobSet, GlobError> {
+        patterns
+            .iter()
+            .map(|p| Glob::builder(p).match_options(options).build())
+            .collect::<Result<Vec<_>, _>>()
+            .map(GlobSet::new)
+    }
+
+    pub fn len(&self) -> usize {
+        self.globs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+
+    /// The pattern at `index`.
+    pub fn get(&self, index: usize) -> Option<&Glob> {
+        self.globs.get(index)
+    }
+
+    /// Whether any pattern matches `value`.
+    pub fn is_match(&self, value: &str) -> bool {
+        self.indexes().any(|(index, case)| {
+            let value = case.fold(value);
+            index.candidates(&value, &mut |i, exact| exact || self.globs[i].matches_folded(&value))
+        })
+    }
+
+    /// The index of every pattern matching `value`, in ascending order.
+    pub fn matches(&self, value: &str) -> Vec<usize> {
+        let mut matched = Vec::new();
+        self.matches_into(value, &mut matched);
+        matched
+    }
+
+    /// Same as [`GlobSet::matches`], but replaces the contents of `matched` instead of allocating,
+    /// so it can be reused for every value.
+    pub fn matches_into(&self, value: &str, matched: &mut Vec<usize>) {
+        matched.clear();
+        for (index, case) in self.indexes() {
+            let value = case.fold(value);
+            index.candidates(&value, &mut |i, exact| {
+                if exact || self.globs[i].matches_folded(&value) {
+                    matched.push(i);
+                }
+                false
+            });
+        }
+        // a pattern with alternatives can be a candidate more than once
+        matched.sort_unstable();
+        matched.dedup();
+    }
+
+    /// The indexes with any patterns in them.
+    fn indexes(&self) -> impl Iterator<Item = (&Index, CaseMode)> {
+        IntoIterator::into_iter([
+            (&self.case_sensitive, CaseMode::Sensitive),
+            (&self.ignore_case, CaseMode::Insensitive),
+            (&self.ascii_ignore_case, CaseMode::AsciiInsensitive),
+            (&self.turkic_ignore_case, CaseMode::TurkicInsensitive),
+        ])
+        .filter(|(index, _)| !index.is_empty())
+    }
+}
+
+impl FromIterator<Glob> for GlobSet {
+    fn from_iter<I: IntoIterator<Item = Glob>>(iter: I) -> GlobSet {
+        GlobSet::new(iter.into_iter().collect())
+    }
+}
+
+/// Patterns by the literal part a value has to have to match them.
+#[derive(Debug,Clone,Default)]
+struct Index {
+    exact: HashMap<String, Vec<usize>>,
+    prefixes: PrefixTrie,
+    suffixes: Affixes,
+    /// patterns without a literal start or end, with the longest literal a value has to contain, if any
+    unanchored: Vec<(usize, String)>,
+}
+
+/// Patterns by literal end, looked up by the end of the value for each length there is.
+#[derive(Debug,Clone,Default)]
+struct Affixes {
+    patterns: HashMap<String, Vec<usize>>,
+    /// ascending, without duplicates
+    lens: Vec<usize>,
+}
+
+impl Affixes {
+    fn add(&mut self, affix: String, pattern: usize) {
+        if let Err(i) = self.lens.binary_search(&affix.len()) {
+            self.lens.insert(i, affix.len());
+        }
+        self.patterns.entry(affix).or_default().push(pattern);
+    }
+}
+
+/// Patterns by literal start, so however many patterns share a start (`api/v1/*`, `api/v2/*`, `api/v2/users*`)
+/// a value is walked down it once for all of them.
+#[derive(Debug,Clone,Default)]
+struct PrefixTrie {
+    /// the root first, none when empty
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug,Clone,Default)]
+struct TrieNode {
+    /// the next byte of a start and the node it leads to, sorted by byte
+    children: Vec<(u8, usize)>,
+    /// patterns whose literal start is the bytes leading here
+    patterns: Vec<usize>,
+}
+
+impl PrefixTrie {
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn add(&mut self, prefix: &str, pattern: usize) {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+        let mut node = 0;
+        for byte in prefix.bytes() {
+            node = match self.nodes[node].children.binary_search_by_key(&byte, |&(b, _)| b) {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(i, (byte, child));
+                    child
+                },
+            };
+        }
+        self.nodes[node].patterns.push(pattern);
+    }
+
+    /// The patterns with a literal start `value` starts with, shortest start first.
+    fn matching<'t>(&'t self, value: &'t str) -> impl Iterator<Item = &'t usize> + 't {
+        let mut node = if self.nodes.is_empty() { None } else { Some(0) };
+        let mut bytes = value.bytes();
+        std::iter::from_fn(move || {
+            let current = node?;
+            node = bytes.next().and_then(|byte| {
+                let children = &self.nodes[current].children;
+                children.binary_search_by_key(&byte, |&(b, _)| b).ok().map(|i| children[i].1)
+            });
+            Some(&self.nodes[current].patterns)
+        })
+        .flatten()
+    }
+}
+
+/// The literal part of a pattern indexed.
+enum Literal {
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+}
+
+impl Index {
+    fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.prefixes.is_empty() && self.suffixes.lens.is_empty() && self.unanchored.is_empty()
+    }
+
+    fn add(&mut self, pattern: &GlobPattern, i: usize) {
+        if let GlobPattern::Alternatives(alternatives) = pattern {
+            for alternative in alternatives {
+                self.add(alternative, i);
+            }
+            return;
+        }
+        match literal(pattern) {
+            Literal::Exact(exact) => self.exact.entry(exact).or_default().push(i),
+            Literal::Prefix(prefix) => self.prefixes.add(&prefix, i),
+            Literal::Suffix(suffix) => self.suffixes.add(suffix, i),
+            Literal::Contains(inner) => self.unanchored.push((i, inner)),
+        }
+    }
+
+    /// Calls `visit` with every pattern that could match `value`, and whether it's a literal pattern
+    /// known to match without matching it, until it returns true. Returns whether it did.
+    fn candidates(&self, value: &str, visit: &mut dyn FnMut(usize, bool) -> bool) -> bool {
+        if self.exact.get(value).into_iter().flatten().any(|&i| visit(i, true)) {
+            return true;
+        }
+        let prefixes = self.prefixes.matching(value);
+        let suffixes = self.suffixes.lens
+            .iter()
+            .take_while(|&&len| len <= value.len())
+            .filter(|&&len| value.is_char_boundary(value.len() - len))
+            .filter_map(|&len| self.suffixes.patterns.get(&value[value.len() - len..]))
+            .flatten();
+        let unanchored = self.unanchored
+            .iter()
+            .filter(|(_, inner)| value.contains(inner.as_str()))
+            .map(|(i, _)| i);
+        prefixes.chain(suffixes).chain(unanchored).any(|&i| visit(i, false))
+    }
+}
+
+/// The literal part of a pattern without alternatives a value most likely has to have to match it.
+fn literal(pattern: &GlobPattern) -> Literal {
+    let (prefix, suffix, inner) = match pattern {
+        GlobPattern::MatchFull(full) => return Literal::Exact(full.clone()),
+        GlobPattern::MatchAny | GlobPattern::Alternatives(_) => (None, None, None),
+        GlobPattern::MatchStart(start) => (Some(start), None, None),
+        GlobPattern::MatchEnd(end) => (None, Some(end), None),
+        GlobPattern::MatchBothEnds(start, end) => (Some(start), Some(end), None),
+        GlobPattern::Multipart(multi) => {
+            let prefix = match multi.first() {
+                Some(Multipart::ExactStart(start)) => Some(start),
+                _ => None,
+            };
+            let suffix = match multi.last() {
+                Some(Multipart::AnyUntilExactEnd(end)) => Some(end),
+                _ => None,
+            };
+            let inner = multi
+                .iter()
+                .filter_map(|part| match part {
+                    Multipart::AnyUntil(s) => Some(s),
+                    _ => None,
+                })
+                .max_by_key(|s| s.len());
+            (prefix, suffix, inner)
+        },
+        GlobPattern::Segmented(segments) => {
+            let prefix = match segments.first() {
+                Some(Segment::Literal(start)) => Some(start),
+                _ => None,
+            };
+            let suffix = match segments.last() {
+                Some(Segment::Literal(end)) => Some(end),
+                _ => None,
+            };
+            let inner = segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    Segment::Literal(s) => Some(s),
+                    _ => None,
+                })
+                .max_by_key(|s| s.len());
+            (prefix, suffix, inner)
+        },
+    };
+    // the longer one narrows the candidates down more
+    match (prefix, suffix) {
+        (Some(prefix), Some(suffix)) if suffix.len() > prefix.len() => Literal::Suffix(suffix.clone()),
+        (Some(prefix), _) => Literal::Prefix(prefix.clone()),
+        (None, Some(suffix)) => Literal::Suffix(suffix.clone()),
+        (None, None) => Literal::Contains(inner.cloned().unwrap_or_default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlobSet;
+    use crate::Glob;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_like_each_pattern() {
+        let patterns = patterns(&["*.rs", "src/*", "Cargo.toml", "*test*", "a*b*c", "*", "{*.md,docs/*}", "log-??.txt", "*.lock"]);
+        let set = GlobSet::build(&patterns).unwrap();
+        let values = ["lib.rs", "src/main.rs", "Cargo.toml", "my_tests.txt", "abc", "README.md", "docs/x", "log-01.txt", "", "Cargo.lock"];
+        for value in values.iter() {
+            let expected: Vec<usize> = patterns
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| crate::glob_match_case_sensitive(p, value).unwrap())
+                .map(|(i, _)| i)
+                .collect();
+            assert_eq!(set.matches(value), expected, "{}", value);
+            assert_eq!(set.is_match(value), !expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn ignore_case_and_ascii() {
+        let set: GlobSet = vec![
+            Glob::builder("*.JPG").case_insensitive(true).build().unwrap(),
+            Glob::builder("IMG_*").ascii_case_insensitive(true).build().unwrap(),
+            Glob::new("exact").unwrap(),
+        ].into_iter().collect();
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.matches("img_001.jpg"), vec![0, 1]);
+        assert_eq!(set.matches("EXACT"), Vec::<usize>::new());
+        assert!(set.is_match("exact") && !set.is_match("photo.png"));
+        let mut matched = vec![7];
+        set.matches_into("IMG_2.JPG", &mut matched);
+        assert_eq!(matched, vec![0, 1]);
+        set.matches_into("exact", &mut matched);
+        assert_eq!(matched, vec![2]);
+        assert!(GlobSet::build_ignore_case(&patterns(&["*.TXT"])).unwrap().is_match("a.txt"));
+    }
+
+    #[test]
+    fn shared_prefixes() {
+        let set = GlobSet::build(&patterns(&["api/v1/*", "api/v2/*", "api/v2/users*", "api/*/health", "é*", "éa*"])).unwrap();
+        assert_eq!(set.matches("api/v2/users/7"), vec![1, 2]);
+        assert_eq!(set.matches("api/v1/health"), vec![0, 3]);
+        assert_eq!(set.matches("api/v3"), Vec::<usize>::new());
+        assert_eq!(set.matches("éab"), vec![4, 5]);
+        assert!(!set.is_match("api") && !set.is_match("e"));
+    }
+}
@@ -0,0 +1,160 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::ops::Deref;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Glob, GlobError, GlobList};
+
+/// A [`Glob`] behind an [`Arc`], cloning it only bumps a reference count.
+///
+/// Derefs to the [`Glob`], so it matches like one. Meant for sharing patterns between threads or
+/// rule sets that get swapped out as a whole, e.g. through `arc-swap`, without copying every pattern.
+#[derive(Debug,Clone)]
+pub struct SharedGlob(Arc<Glob>);
+
+impl SharedGlob {
+    /// Builds a case sensitive pattern, same as `SharedGlob::from(Glob::new(pattern)?)`.
+    pub fn new(pattern: &str) -> Result<SharedGlob, GlobError> {
+        Glob::new(pattern).map(SharedGlob::from)
+    }
+
+    pub fn as_glob(&self) -> &Glob {
+        &self.0
+    }
+
+    /// Whether both are clones of the same pattern, not just equal ones.
+    pub fn ptr_eq(&self, other: &SharedGlob) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for SharedGlob {
+    type Target = Glob;
+
+    fn deref(&self) -> &Glob {
+        &self.0
+    }
+}
+
+impl AsRef<Glob> for SharedGlob {
+    fn as_ref(&self) -> &Glob {
+        &self.0
+    }
+}
+
+impl From<Glob> for SharedGlob {
+    fn from(glob: Glob) -> SharedGlob {
+        SharedGlob(Arc::new(glob))
+    }
+}
+
+impl From<Arc<Glob>> for SharedGlob {
+    fn from(glob: Arc<Glob>) -> SharedGlob {
+        SharedGlob(glob)
+    }
+}
+
+/// A [`GlobList`] that can be matched against from many threads while occasionally being modified.
+///
+/// Clones share the same list. Everything is done through the guards from [`read`](SharedGlobList::read)
+/// and [`write`](SharedGlobList::write), so the whole [`GlobList`] API is available without being
+/// repeated here, and several calls can be made under a single lock.
+#[derive(Debug,Clone,Default)]
+pub struct SharedGlobList(Arc<RwLock<GlobList>>);
+
+impl SharedGlobList {
+    pub fn new() -> SharedGlobList {
+        SharedGlobList::default()
+    }
+
+    // a panic while holding the lock can't leave a GlobList half modified in any way that matters,
+    // so there's no reason to keep failing because of a poisoned lock
+
+    /// Locks the list for reading, for doing several things with it without it changing in between.
+    pub fn read(&self) -> RwLockReadGuard<'_, GlobList> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the list for writing, blocking any matching until the guard is dropped.
+    pub fn write(&self) -> RwLockWriteGuard<'_, GlobList> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Swaps in a new list, returning the old one.
+    pub fn replace(&self, glob_list: GlobList) -> GlobList {
+        std::mem::replace(&mut *self.write(), glob_list)
+    }
+}
+
+impl From<GlobList> for SharedGlobList {
+    fn from(glob_list: GlobList) -> SharedGlobList {
+        SharedGlobList(Arc::new(RwLock::new(glob_list)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{Glob, GlobCaseSensitive, GlobIgnoreCase, GlobList, SharedGlob, SharedGlobList};
+
+    #[test]
+    fn shared_glob_clones_share_the_pattern() {
+        let glob = SharedGlob::from(Glob::builder("*.RS").case_insensitive(true).build().unwrap());
+        let clone = glob.clone();
+        assert!(clone.ptr_eq(&glob));
+        assert!(!SharedGlob::new("*.RS").unwrap().ptr_eq(&glob));
+        assert!(thread::spawn(move || clone.is_match("lib.rs")).join().unwrap());
+        assert_eq!(glob.as_glob().as_str(), "*.RS");
+    }
+
+    #[test]
+    fn shared_glob_list_clones_see_modifications() {
+        let shared = SharedGlobList::new();
+        let handle = shared.clone();
+        assert!(!handle.read().any_match("main.rs"));
+        let id = shared.write().add_case_sensitive(GlobCaseSensitive::build("*.rs").unwrap());
+        assert!(handle.read().any_match("main.rs"));
+        assert_eq!(handle.write().remove("*.rs"), vec![id]);
+        assert!(!shared.read().any_match("main.rs"));
+        shared.write().add_case_sensitive(GlobCaseSensitive::build("*.rs").unwrap());
+        let old = shared.replace(GlobList::new());
+        assert!(!old.is_empty());
+        assert!(handle.read().is_empty());
+    }
+
+    #[test]
+    fn shared_glob_list_matches_from_many_threads() {
+        let shared = SharedGlobList::from(GlobList::build_ignore_case(&["*.RS".to_string()]).unwrap());
+        let threads : Vec<_> = (0..4)
+            .map(|i| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    if i == 0 {
+                        shared.write().add_ignore_case(GlobIgnoreCase::build("*.toml").unwrap());
+                    }
+                    shared.read().any_match("lib.rs")
+                })
+            })
+            .collect();
+        assert!(threads.into_iter().all(|t| t.join().unwrap()));
+        assert!(shared.read().any_match("Cargo.TOML"));
+        assert_eq!(shared.read().iter().count(), 2);
+    }
+}
@@ -0,0 +1,202 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Patterns checked at compile time and built on first use, for `static` patterns without a `Result`.
+
+use std::sync::OnceLock;
+
+use crate::Glob;
+
+/// A pattern for a `static`, checked when the program is compiled and built the first time it's matched,
+/// e.g. `static RUST_FILES: StaticGlob = StaticGlob::new("*.rs");`.
+///
+/// An invalid pattern fails to compile, since `new` is evaluated at compile time for a `static`, see also
+/// [`glob!`](crate::glob!). Called at runtime it panics instead.
+#[derive(Debug)]
+pub struct StaticGlob {
+    pattern: &'static str,
+    case_insensitive: bool,
+    glob: OnceLock<Glob>,
+}
+
+impl StaticGlob {
+    pub const fn new(pattern: &'static str) -> StaticGlob {
+        check_pattern(pattern);
+        StaticGlob { pattern, case_insensitive: false, glob: OnceLock::new() }
+    }
+
+    pub const fn new_ignore_case(pattern: &'static str) -> StaticGlob {
+        check_pattern(pattern);
+        StaticGlob { pattern, case_insensitive: true, glob: OnceLock::new() }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.pattern
+    }
+
+    /// The pattern, built the first time it's needed.
+    pub fn as_glob(&self) -> &Glob {
+        self.glob.get_or_init(|| {
+            Glob::builder(self.pattern)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                // only the few errors that show up after expanding braces get past check_pattern
+                .unwrap_or_else(|e| panic!("invalid pattern '{}': {}", self.pattern, e))
+        })
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.as_glob().is_match(value)
+    }
+}
+
+/// A `&'static` [`StaticGlob`], checked at compile time, `glob!("*.rs")` or `glob!("readme*", ignore_case)`.
+#[macro_export]
+macro_rules! glob {
+    ($pattern:expr) => {{
+        static GLOB: $crate::StaticGlob = $crate::StaticGlob::new($pattern);
+        &GLOB
+    }};
+    ($pattern:expr, ignore_case) => {{
+        static GLOB: $crate::StaticGlob = $crate::StaticGlob::new_ignore_case($pattern);
+        &GLOB
+    }};
+}
+
+/// Panics on the errors [`Glob::new`] would give for `pattern`, except ones only showing up after expanding
+/// braces (`*{*a,b}`). In a const context the panic is a compile error.
+pub const fn check_pattern(pattern: &str) {
+    let bytes = pattern.as_bytes();
+    if bytes.is_empty() {
+        panic!("empty pattern");
+    }
+    let mut braces = 0;
+    let mut group = false;
+    let mut previous = 0u8; // 0 at the start
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => {
+                i = check_class(bytes, i + 1);
+                previous = b']';
+                continue;
+            },
+            b'(' if !group && i + 3 < bytes.len() && bytes[i + 1] == b'?' && bytes[i + 2] == b'i' && bytes[i + 3] == b':' => {
+                group = true;
+                i += 4;
+                continue;
+            },
+            b')' if group => {
+                group = false;
+                i += 1;
+                continue;
+            },
+            b'{' => braces += 1,
+            b'}' if braces > 0 => braces -= 1,
+            b'*' if i + 1 < bytes.len() && bytes[i + 1] == b'*' => {
+                // `**` has to be a whole path component
+                let component_start = previous == 0 || previous == b'/';
+                let end = i + 2 == bytes.len();
+                if !component_start || !(end || bytes[i + 2] == b'/') {
+                    panic!("adjacent wildcards, `**` has to be a whole path component");
+                }
+                i += 2;
+                previous = b'*';
+                continue;
+            },
+            _ => (),
+        }
+        previous = bytes[i];
+        i += 1;
+    }
+    if braces > 0 {
+        panic!("unterminated braces");
+    }
+    if group {
+        panic!("unterminated case insensitive group");
+    }
+}
+
+/// Checks the class starting at `start` right after its `[`, returning where it ends after its `]`.
+const fn check_class(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+    if i < bytes.len() && (bytes[i] == b'!' || bytes[i] == b'^') {
+        i += 1;
+    }
+    let mut first = true;
+    loop {
+        if i >= bytes.len() {
+            panic!("unterminated class");
+        }
+        if bytes[i] == b']' && !first {
+            return i + 1;
+        }
+        first = false;
+        let (low, len) = decode(bytes, i);
+        i += len;
+        if i + 1 < bytes.len() && bytes[i] == b'-' && bytes[i + 1] != b']' {
+            let (high, len) = decode(bytes, i + 1);
+            if high < low {
+                panic!("reversed range in class");
+            }
+            i += 1 + len;
+        }
+    }
+}
+
+/// The char starting at `i` in UTF-8 `bytes` and its length in bytes.
+const fn decode(bytes: &[u8], i: usize) -> (u32, usize) {
+    let b = bytes[i] as u32;
+    let len = if b < 0x80 { 1 } else if b < 0xe0 { 2 } else if b < 0xf0 { 3 } else { 4 };
+    // the bits of the first byte that are part of the char
+    let mut ch = if len == 1 { b } else { b & (0x7f >> len) };
+    let mut j = 1;
+    while j < len {
+        ch = ch << 6 | (bytes[i + j] & 0x3f) as u32;
+        j += 1;
+    }
+    (ch, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_pattern;
+    use crate::{Glob, StaticGlob};
+
+    static RUST_FILES: StaticGlob = StaticGlob::new("src/**/*.{rs,toml}");
+
+    #[test]
+    fn static_glob() {
+        assert!(RUST_FILES.is_match("src/a/lib.rs") && !RUST_FILES.is_match("tests/lib.rs"));
+        assert_eq!(RUST_FILES.as_str(), "src/**/*.{rs,toml}");
+        assert!(crate::glob!("*.rs").is_match("main.rs"));
+        assert!(crate::glob!("README*", ignore_case).is_match("readme.md"));
+        assert!(crate::glob!("**/**/x").is_match("a/b/x"));
+    }
+
+    #[test]
+    fn check_pattern_agrees_with_building() {
+        let patterns = ["*.rs", "**/a", "a/**", "**", "a/**/b", "**/**", "**/**/x", "a/**/**/b", "**/***", "[]]", "[!a-z]*", "[ä-ö]", "{a,{b,c}}", "a}",
+                        "", "a**", "***", "**a", "a/***", "[abc", "[z-a]", "[ö-ä]", "{a,b", "[!]",
+                        "(?i:*.jpg)", "(?i:**/a)", "a)b", "(?i:a)b)", "a(?i:b", "(?i:[a-c)"];
+        for pattern in patterns.iter() {
+            let checked = std::panic::catch_unwind(|| check_pattern(pattern)).is_ok();
+            assert_eq!(checked, Glob::new(pattern).is_ok(), "{}", pattern);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching a value that arrives in chunks, e.g. over a socket, without keeping it around.
+
+use crate::{CaseMode, Dfa, Glob, GlobError};
+
+/// Matches a [`Glob`] against a value pushed a chunk at a time, running the pattern compiled to a [`Dfa`]
+/// over each chunk as it comes so nothing needs to be buffered.
+#[derive(Debug,Clone)]
+pub struct StreamMatcher {
+    dfa: Dfa,
+    case: CaseMode,
+    state: u32,
+    /// start of a UTF-8 char split between chunks pushed as bytes
+    pending: Vec<u8>,
+}
+
+impl StreamMatcher {
+    /// Uses the DFA of a glob built with [`GlobBuilder::dfa`](crate::GlobBuilder::dfa), or compiles one,
+    /// which fails with [`GlobError::DfaTooLarge`] for patterns needing too many states.
+    pub fn new(glob: &Glob) -> Result<StreamMatcher, GlobError> {
+        let dfa = match &glob.dfa {
+            Some(dfa) => dfa.clone(),
+            None => glob.pattern.compile_dfa()?,
+        };
+        Ok(StreamMatcher {
+            state: dfa.start_state(),
+            dfa,
+            case: glob.case,
+            pending: Vec::new(),
+        })
+    }
+
+    pub fn push_str(&mut self, chunk: &str) {
+        for ch in chunk.chars() {
+            if Dfa::is_dead(self.state) {
+                return;
+            }
+            self.push_char(ch);
+        }
+    }
+
+    /// Same as [`StreamMatcher::push_str`] for bytes, which don't have to be valid UTF-8 and can split a
+    /// char between chunks. Bytes that aren't valid UTF-8 are matched as `U+FFFD`, one per byte.
+    pub fn push_bytes(&mut self, mut chunk: &[u8]) {
+        while !self.pending.is_empty() && !chunk.is_empty() {
+            // finish the char split between chunks one byte at a time
+            let mut pending = std::mem::take(&mut self.pending);
+            pending.push(chunk[0]);
+            chunk = &chunk[1..];
+            let rest = self.push_utf8(&pending);
+            self.pending.extend_from_slice(rest);
+        }
+        let rest = self.push_utf8(chunk);
+        self.pending.extend_from_slice(rest);
+    }
+
+    /// Pushes the valid UTF-8 in `bytes`, returning an incomplete char at the end if there is one.
+    fn push_utf8<'b>(&mut self, mut bytes: &'b [u8]) -> &'b [u8] {
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(valid) => {
+                    self.push_str(valid);
+                    return &[];
+                },
+                Err(e) => {
+                    let (valid, rest) = bytes.split_at(e.valid_up_to());
+                    self.push_str(std::str::from_utf8(valid).unwrap());
+                    match e.error_len() {
+                        Some(invalid) => {
+                            for _ in 0..invalid {
+                                self.push_char(char::REPLACEMENT_CHARACTER);
+                            }
+                            bytes = &rest[invalid..];
+                        },
+                        None => return rest,
+                    }
+                },
+            }
+        }
+    }
+
+    fn push_char(&mut self, ch: char) {
+        self.state = step(&self.dfa, self.case, self.state, ch);
+    }
+
+    /// Whether everything pushed so far matches the pattern, a char left incomplete by
+    /// [`StreamMatcher::push_bytes`] counting as invalid bytes.
+    pub fn is_match(&self) -> bool {
+        let state = self.pending
+            .iter()
+            .fold(self.state, |state, _| step(&self.dfa, self.case, state, char::REPLACEMENT_CHARACTER));
+        self.dfa.is_accepting(state)
+    }
+
+    /// Whether pushing more could still make it match, once it can't there's no need to read the rest.
+    pub fn can_match(&self) -> bool {
+        !Dfa::is_dead(self.state)
+    }
+
+    /// Starts over with an empty value.
+    pub fn reset(&mut self) {
+        self.state = self.dfa.start_state();
+        self.pending.clear();
+    }
+}
+
+/// The state after `ch` case folded the way the pattern was.
+pub(crate) fn step(dfa: &Dfa, case: CaseMode, state: u32, ch: char) -> u32 {
+    match case {
+        CaseMode::Sensitive => dfa.step(state, ch),
+        CaseMode::Insensitive | CaseMode::TurkicInsensitive => case.fold_char(ch).fold(state, |state, ch| dfa.step(state, ch)),
+        CaseMode::AsciiInsensitive => dfa.step(state, ch.to_ascii_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, StreamMatcher};
+
+    #[test]
+    fn stream_matches_like_whole_value() {
+        let glob = Glob::builder("GET /*/index.HTML").case_insensitive(true).build().unwrap();
+        let mut stream = StreamMatcher::new(&glob).unwrap();
+        for chunk in ["get /do", "cs/ind", "ex.html"].iter() {
+            assert!(!stream.is_match() && stream.can_match());
+            stream.push_str(chunk);
+        }
+        assert!(stream.is_match());
+        stream.push_str("?");
+        assert!(!stream.is_match() && stream.can_match());
+
+        stream.reset();
+        stream.push_str("POST");
+        assert!(!stream.can_match() && !stream.is_match());
+    }
+
+    #[test]
+    fn stream_bytes_split_chars() {
+        let glob = Glob::builder("*ö?").dfa(true).build().unwrap();
+        let mut stream = StreamMatcher::new(&glob).unwrap();
+        let value = "smörgås".as_bytes();
+        stream.push_bytes(&value[..3]); // ö split in the middle
+        assert!(!stream.is_match());
+        stream.push_bytes(&value[3..4]);
+        assert!(!stream.is_match());
+        stream.push_bytes(&value[4..5]);
+        assert!(stream.is_match());
+        stream.push_bytes(b"g");
+        assert!(!stream.is_match());
+
+        // an incomplete char at the end counts as invalid bytes, each matched by `?`
+        stream.reset();
+        stream.push_bytes("xö".as_bytes());
+        stream.push_bytes(&"å".as_bytes()[..1]);
+        assert!(stream.is_match());
+        stream.push_bytes(b"\xff");
+        assert!(!stream.is_match());
+    }
+}
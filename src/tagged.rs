@@ -0,0 +1,123 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+use crate::{EntryId, Glob, GlobList};
+
+/// A [`GlobList`] with a tag attached to each pattern, e.g. the handler a route pattern leads to.
+#[derive(Debug,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaggedGlobList<T> {
+    list: GlobList,
+    tags: HashMap<EntryId, T>,
+}
+
+impl<T> Default for TaggedGlobList<T> {
+    fn default() -> TaggedGlobList<T> {
+        TaggedGlobList { list: GlobList::new(), tags: HashMap::new() }
+    }
+}
+
+impl<T> TaggedGlobList<T> {
+    pub fn new() -> TaggedGlobList<T> {
+        TaggedGlobList::default()
+    }
+
+    /// Adds `glob` like [`GlobList::add`] with `tag` attached to it.
+    pub fn add_tagged(&mut self, glob: Glob, tag: T) -> EntryId {
+        let id = self.list.add(glob);
+        self.tags.insert(id, tag);
+        id
+    }
+
+    /// Adds a negated pattern like [`GlobList::add_negated`], which has no tag.
+    pub fn add_negated(&mut self, glob: Glob) -> EntryId {
+        self.list.add_negated(glob)
+    }
+
+    pub fn tag(&self, id: EntryId) -> Option<&T> {
+        self.tags.get(&id)
+    }
+
+    pub fn as_glob_list(&self) -> &GlobList {
+        &self.list
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// The tags of the patterns matching `value` in insertion order, empty unless [`GlobList::any_match`]
+    /// accepts it. With negated patterns only the tags of patterns after the last matching negated one count.
+    pub fn any_match_tagged(&self, value: &str) -> Vec<&T> {
+        let matches = self.list.matches(value);
+        // a value is only included if the last matching pattern isn't negated
+        let included = match matches.iter().rposition(|m| m.negated) {
+            Some(last_negated) => &matches[last_negated + 1..],
+            None => &matches[..],
+        };
+        included.iter().filter_map(|m| self.tags.get(&m.id)).collect()
+    }
+
+    /// The tag of the first pattern in insertion order matching `value`, see [`TaggedGlobList::any_match_tagged`].
+    pub fn first_match_tagged(&self, value: &str) -> Option<&T> {
+        self.any_match_tagged(value).into_iter().next()
+    }
+}
+
+impl<T> FromIterator<(Glob, T)> for TaggedGlobList<T> {
+    fn from_iter<I: IntoIterator<Item = (Glob, T)>>(iter: I) -> TaggedGlobList<T> {
+        let mut list = TaggedGlobList::new();
+        for (glob, tag) in iter {
+            list.add_tagged(glob, tag);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Glob, TaggedGlobList};
+
+    #[test]
+    fn tags_of_matching_patterns() {
+        let mut routes = TaggedGlobList::new();
+        let users = routes.add_tagged(Glob::new("/users/*").unwrap(), 1);
+        routes.add_tagged(Glob::builder("/USERS/*/posts").case_insensitive(true).build().unwrap(), 2);
+        routes.add_tagged(Glob::new("/static/*").unwrap(), 3);
+        assert_eq!(routes.tag(users), Some(&1));
+        assert_eq!(routes.any_match_tagged("/users/42/posts"), vec![&1, &2]);
+        assert_eq!(routes.first_match_tagged("/users/42/posts"), Some(&1));
+        assert_eq!(routes.any_match_tagged("/static/app.js"), vec![&3]);
+        assert!(routes.any_match_tagged("/admin").is_empty());
+        assert_eq!(routes.first_match_tagged("/admin"), None);
+    }
+
+    #[test]
+    fn negated_patterns_hide_earlier_tags() {
+        let mut list : TaggedGlobList<&str> = vec![(Glob::new("*.rs").unwrap(), "rust")].into_iter().collect();
+        list.add_negated(Glob::new("target/*").unwrap());
+        list.add_tagged(Glob::new("target/keep*").unwrap(), "kept");
+        assert_eq!(list.any_match_tagged("src/lib.rs"), vec![&"rust"]);
+        assert!(list.any_match_tagged("target/gen.rs").is_empty());
+        assert_eq!(list.any_match_tagged("target/keep.rs"), vec![&"kept"]);
+        assert!(list.as_glob_list().any_match("target/keep.rs"));
+    }
+}
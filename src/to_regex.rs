@@ -0,0 +1,213 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Translating patterns to regexes, for systems that only accept those.
+
+use std::fmt::Write;
+
+use crate::{CaseMode, CharClass, Glob, GlobPattern, Multipart, Segment};
+
+/// The chars with a meaning in a regex, also within a class.
+const META: &str = "\\.+*?()|[]{}^$#&-~";
+
+impl GlobPattern {
+    /// A regex matching the same values, e.g. `(?s)^.*\.rs$` for `*.rs`.
+    ///
+    /// Uses only syntax most regex engines share: `^`, `$`, `.`, `*`, classes, non-capturing
+    /// groups and the `s` flag, so `.` matches newlines like a wildcard does.
+    pub fn to_regex_string(&self) -> String {
+        to_regex_string(self, false)
+    }
+}
+
+impl Glob {
+    /// Same as [`GlobPattern::to_regex_string`], ignoring case with the `i` flag (or classes of both cases of
+    /// each ASCII letter, see [`GlobBuilder::ascii_case_insensitive`](crate::GlobBuilder::ascii_case_insensitive))
+    /// when the pattern does. A regex folds single chars only, so `ß` doesn't match `ss` even with `full-case-folding`,
+    /// and doesn't know [`CaseFold::Turkic`](crate::CaseFold::Turkic), `I` matches `i` rather than `ı` in the regex.
+    pub fn to_regex_string(&self) -> String {
+        match self.case {
+            CaseMode::Sensitive => to_regex_string(&self.pattern, false),
+            CaseMode::Insensitive | CaseMode::TurkicInsensitive => format!("(?i){}", to_regex_string(&self.pattern, false)),
+            CaseMode::AsciiInsensitive => to_regex_string(&self.pattern, true),
+        }
+    }
+
+    /// The pattern compiled to a [`regex::Regex`] matching the same values, enabled by the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub fn regex(&self) -> Result<regex::Regex, regex::Error> {
+        regex::Regex::new(&self.to_regex_string())
+    }
+}
+
+fn to_regex_string(pattern: &GlobPattern, ascii_case: bool) -> String {
+    let mut regex = String::from("(?s)^");
+    write_pattern(&mut regex, pattern, ascii_case);
+    regex.push('$');
+    regex
+}
+
+fn write_pattern(regex: &mut String, pattern: &GlobPattern, ascii_case: bool) {
+    let literal = |regex: &mut String, s: &str| write_literal(regex, s, ascii_case);
+    match pattern {
+        GlobPattern::MatchAny => regex.push_str(".*"),
+        GlobPattern::MatchEnd(end) => {
+            regex.push_str(".*");
+            literal(regex, end);
+        },
+        GlobPattern::MatchStart(start) => {
+            literal(regex, start);
+            regex.push_str(".*");
+        },
+        GlobPattern::MatchBothEnds(start, end) => {
+            literal(regex, start);
+            regex.push_str(".*");
+            literal(regex, end);
+        },
+        GlobPattern::MatchFull(full) => literal(regex, full),
+        GlobPattern::Multipart(multi) => {
+            for part in multi {
+                match part {
+                    Multipart::ExactStart(start) => literal(regex, start),
+                    Multipart::AnyUntil(s) | Multipart::AnyUntilExactEnd(s) => {
+                        regex.push_str(".*");
+                        literal(regex, s);
+                    },
+                    Multipart::AnyEnd => regex.push_str(".*"),
+                }
+            }
+        },
+        GlobPattern::Segmented(segments) => {
+            for segment in segments {
+                match segment {
+                    Segment::Literal(s) => literal(regex, s),
+                    Segment::AnyChar => regex.push('.'),
+                    Segment::AnyChars | Segment::AnyPath => regex.push_str(".*"),
+                    Segment::Class(class) => write_class(regex, class, ascii_case),
+                    Segment::AnyCharExceptSeparator => regex.push_str("[^/]"),
+                    Segment::AnyCharsExceptSeparator => regex.push_str("[^/]*"),
+                    Segment::AnyDirs => regex.push_str("(?:[^/]*/)*"),
+                    // no `.` right after a `/`, written without look-arounds
+                    Segment::AnyVisibleChars => regex.push_str("[^/]*(?:/+[^./][^/]*)*/*"),
+                    Segment::AnyVisibleDirs => regex.push_str("(?:(?:[^./][^/]*)?/)*"),
+                    Segment::LiteralIgnoreCase(s) => {
+                        regex.push_str("(?i:");
+                        write_literal(regex, s, false);
+                        regex.push(')');
+                    },
+                }
+            }
+        },
+        GlobPattern::Alternatives(alternatives) => {
+            regex.push_str("(?:");
+            for (i, alternative) in alternatives.iter().enumerate() {
+                if i > 0 {
+                    regex.push('|');
+                }
+                write_pattern(regex, alternative, ascii_case);
+            }
+            regex.push(')');
+        },
+    }
+}
+
+fn write_char(regex: &mut String, ch: char) {
+    if META.contains(ch) {
+        regex.push('\\');
+    }
+    regex.push(ch);
+}
+
+fn write_literal(regex: &mut String, literal: &str, ascii_case: bool) {
+    for ch in literal.chars() {
+        if ascii_case && ch.is_ascii_alphabetic() {
+            write!(regex, "[{}{}]", ch.to_ascii_lowercase(), ch.to_ascii_uppercase()).unwrap();
+        } else {
+            write_char(regex, ch);
+        }
+    }
+}
+
+fn write_class(regex: &mut String, class: &CharClass, ascii_case: bool) {
+    regex.push('[');
+    if class.is_negated() {
+        regex.push('^');
+    }
+    let mut write_range = |first: char, last: char| {
+        write_char(regex, first);
+        if first != last {
+            regex.push('-');
+            write_char(regex, last);
+        }
+    };
+    for &(first, last) in class.ranges() {
+        write_range(first, last);
+        if ascii_case {
+            // the pattern is folded to lowercase, add the uppercase letters of the range
+            let (first, last) = (first.max('a'), last.min('z'));
+            if first <= last {
+                write_range(first.to_ascii_uppercase(), last.to_ascii_uppercase());
+            }
+        }
+    }
+    regex.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{build_glob_pattern, Glob};
+
+    #[test]
+    fn regex_string_escapes_literals() {
+        assert_eq!(build_glob_pattern("*.rs").unwrap().to_regex_string(), r"(?s)^.*\.rs$");
+        assert_eq!(build_glob_pattern("a(1)+*").unwrap().to_regex_string(), r"(?s)^a\(1\)\+.*$");
+        assert_eq!(build_glob_pattern("[!a-c]?{x,y}").unwrap().to_regex_string(), r"(?s)^(?:[^a-c].x|[^a-c].y)$");
+        assert_eq!(Glob::builder("*.TXT").case_insensitive(true).build().unwrap().to_regex_string(), r"(?i)(?s)^.*\.txt$");
+        assert_eq!(Glob::builder("A[b-]").ascii_case_insensitive(true).build().unwrap().to_regex_string(), r"(?s)^[aA][bB\-]$");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_matches_like_glob() {
+        let values = ["", "a", "main.rs", "src/lib.rs", "src/a/b/lib.rs", "a.b.c", "$^.", "x\ny", "Read Me.MD", "ab/cd", ".git", "a/.b", "src/.a/lib.rs", "src//lib.rs"];
+        let globs = vec![
+            Glob::new("*").unwrap(),
+            Glob::new("*.rs").unwrap(),
+            Glob::new("src/*").unwrap(),
+            Glob::new("a*b*c").unwrap(),
+            Glob::new("*.*.*").unwrap(),
+            Glob::new("$^.").unwrap(),
+            Glob::new("x?y").unwrap(),
+            Glob::new("[!a-m]*").unwrap(),
+            Glob::new("{*.md,*.rs}").unwrap(),
+            Glob::builder("src/**/*.rs").literal_separator(true).build().unwrap(),
+            Glob::builder("*/*").literal_separator(true).build().unwrap(),
+            Glob::builder("read me.*").case_insensitive(true).build().unwrap(),
+            Glob::builder("[r]EAD*md").ascii_case_insensitive(true).build().unwrap(),
+            Glob::builder("**/*").literal_leading_dot(true).build().unwrap(),
+            Glob::builder("src/**/*.rs").literal_leading_dot(true).build().unwrap(),
+            Glob::builder("*").literal_leading_dot(true).build().unwrap(),
+        ];
+        for glob in &globs {
+            let regex = glob.regex().unwrap();
+            for value in values.iter() {
+                assert_eq!(regex.is_match(value), glob.is_match(value), "{} ({}) on {:?}", glob.as_str(), regex, value);
+            }
+        }
+    }
+}
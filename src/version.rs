@@ -0,0 +1,91 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! Matching dotted version numbers a component at a time.
+
+use crate::{Glob, GlobError};
+
+/// A pattern for versions like `1.2.*` matched component by component, so it matches `1.2.10` but not
+/// `1.20.1` the way a plain `1.2*` would.
+///
+/// The pattern and the version are split at every `.` and each component of the pattern is matched like
+/// a [`Glob`] against one of the version, a pre-release or build suffix stays on its component (`3-rc`
+/// in `1.2.3-rc.1`). A `*` as the last component matches one or more components, `1.*` matches
+/// `1.2.3-rc.1` too.
+#[derive(Debug,Clone)]
+pub struct VersionGlob {
+    source: String,
+    components: Vec<Glob>,
+    /// the last component was a lone `*`, which isn't in `components`
+    any_rest: bool,
+}
+
+impl VersionGlob {
+    pub fn new(pattern: &str) -> Result<VersionGlob, GlobError> {
+        let mut components = Vec::new();
+        let mut start = 0;
+        for component in pattern.split('.') {
+            components.push(Glob::new(component).map_err(|e| e.position().map_or(e, |p| e.at(start + p)))?);
+            start += component.len() + 1;
+        }
+        let any_rest = pattern == "*" || pattern.ends_with(".*");
+        if any_rest {
+            components.pop();
+        }
+        Ok(VersionGlob { source: pattern.to_string(), components, any_rest })
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_match(&self, version: &str) -> bool {
+        let mut components = version.split('.');
+        self.components.iter().all(|glob| components.next().is_some_and(|c| glob.is_match(c)))
+            && match components.next() {
+                Some(next) => self.any_rest && !next.is_empty() && components.all(|c| !c.is_empty()),
+                None => !self.any_rest,
+            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GlobError, VersionGlob};
+
+    #[test]
+    fn component_wise() {
+        let glob = VersionGlob::new("1.2.*").unwrap();
+        for &(version, expected) in [("1.2.10", true), ("1.2.0", true), ("1.2.3-rc.1", true), ("1.20.1", false),
+                                     ("1.2", false), ("1.2.", false), ("11.2.1", false)].iter() {
+            assert_eq!(glob.is_match(version), expected, "{}", version);
+        }
+        let glob = VersionGlob::new("1.*.0").unwrap();
+        assert!(glob.is_match("1.7.0"));
+        assert!(!glob.is_match("1.7.1.0"));
+        assert!(VersionGlob::new("2.[0-4].?").unwrap().is_match("2.3.9"));
+        assert!(VersionGlob::new("*").unwrap().is_match("0.1"));
+        assert!(!VersionGlob::new("1.2").unwrap().is_match("1.2.0"));
+    }
+
+    #[test]
+    fn errors_point_into_the_pattern() {
+        assert!(matches!(VersionGlob::new("1.[2"), Err(GlobError::UnterminatedClass { position: 2 })));
+    }
+}
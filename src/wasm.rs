@@ -0,0 +1,86 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! `wasm-bindgen` bindings, so JavaScript matches with the same semantics as Rust.
+//!
+//! Exported as `Glob` and `GlobList` classes taking patterns as strings, building throws on invalid patterns.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Glob, GlobError, GlobList};
+
+fn to_js(error: GlobError) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+#[wasm_bindgen(js_name = Glob)]
+pub struct WasmGlob(Glob);
+
+#[wasm_bindgen(js_class = Glob)]
+impl WasmGlob {
+    #[wasm_bindgen(constructor)]
+    pub fn new(pattern: &str, ignore_case: Option<bool>) -> Result<WasmGlob, JsError> {
+        Glob::builder(pattern)
+            .case_insensitive(ignore_case.unwrap_or(false))
+            .build()
+            .map(WasmGlob)
+            .map_err(to_js)
+    }
+
+    #[wasm_bindgen(js_name = isMatch)]
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn pattern(&self) -> String {
+        self.0.as_str().to_string()
+    }
+}
+
+#[wasm_bindgen(js_name = GlobList)]
+pub struct WasmGlobList(GlobList);
+
+#[wasm_bindgen(js_class = GlobList)]
+impl WasmGlobList {
+    /// Patterns starting with `!` are negated, like for [`GlobList::build`].
+    #[wasm_bindgen(constructor)]
+    pub fn new(patterns: Vec<String>, ignore_case: Option<bool>) -> Result<WasmGlobList, JsError> {
+        let list = if ignore_case.unwrap_or(false) {
+            GlobList::build_ignore_case(&patterns)
+        } else {
+            GlobList::build(&patterns)
+        };
+        list.map(WasmGlobList).map_err(to_js)
+    }
+
+    #[wasm_bindgen(js_name = anyMatch)]
+    pub fn any_match(&self, value: &str) -> bool {
+        self.0.any_match(value)
+    }
+
+    #[wasm_bindgen(js_name = allMatch)]
+    pub fn all_match(&self, value: &str) -> bool {
+        self.0.all_match(value)
+    }
+
+    /// The values any pattern matches, e.g. the names of the files picked in a browser.
+    pub fn filter(&self, values: Vec<String>) -> Vec<String> {
+        values.into_iter().filter(|value| self.0.any_match(value)).collect()
+    }
+}
@@ -0,0 +1,152 @@
+/*
+    globber - a very basic library to perform wildcard matching on strings
+    Copyright (C) 2021 Carl Erik Patrik Iwarson
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU Affero General Public License as published
+    by the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU Affero General Public License for more details.
+
+    You should have received a copy of the GNU Affero General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+*/
+
+//! The `globber` command line tool, run as a process for its output and exit codes.
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn globber(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_globber"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+/// A fresh directory with `files` in it.
+fn tree(name: &str, files: &[&str]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("globber-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for file in files {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+    root
+}
+
+#[test]
+fn filters_lines() {
+    let output = globber(&["*.rs"], "lib.rs\nREADME.md\nmain.rs\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "lib.rs\nmain.rs\n");
+    let output = globber(&["-i", "*.MD"], "lib.rs\nREADME.md\n");
+    assert_eq!(stdout(&output), "README.md\n");
+    let output = globber(&["-c", "*.rs"], "lib.rs\nmain.rs\nx\n");
+    assert_eq!(stdout(&output), "2\n");
+}
+
+#[test]
+fn invert() {
+    let output = globber(&["--invert", "*.rs"], "lib.rs\nREADME.md\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "README.md\n");
+    let output = globber(&["-v", "*"], "lib.rs\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn color() {
+    let output = globber(&["--color=always", "src/*.rs"], "src/lib.rs\nREADME.md\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "\x1b[1;31msrc/\x1b[0mlib\x1b[1;31m.rs\x1b[0m\n");
+    let output = globber(&["--color=always", "*.{rs,toml}"], "Cargo.toml\n");
+    assert_eq!(stdout(&output), "Cargo\x1b[1;31m.toml\x1b[0m\n");
+    assert_eq!(stdout(&globber(&["--color=never", "*.rs"], "lib.rs\n")), "lib.rs\n");
+
+    // nothing to highlight in lines that didn't match, or in a count
+    assert_eq!(stdout(&globber(&["--color=always", "-v", "*.rs"], "lib.rs\nREADME.md\n")), "README.md\n");
+    assert_eq!(stdout(&globber(&["--color=always", "-c", "*.rs"], "lib.rs\nmain.rs\n")), "2\n");
+    assert_eq!(globber(&["--color=sometimes", "*"], "").status.code(), Some(2));
+}
+
+#[test]
+#[cfg(feature = "full-case-folding")]
+fn color_highlights_overlapping_spans_once() {
+    // both `s` are the one `ß`
+    let output = globber(&["--color=always", "-i", "s*s"], "ß\nxßy\n");
+    assert_eq!(stdout(&output), "\x1b[1;31mß\x1b[0m\n");
+    let output = globber(&["--color=always", "-i", "*s?s*"], "xßsy\n");
+    assert_eq!(stdout(&output), "x\x1b[1;31mß\x1b[0m\x1b[1;31ms\x1b[0my\n");
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(globber(&["-q", "*.rs"], "lib.rs\n").status.code(), Some(0));
+    assert_eq!(globber(&["*.rs"], "README.md\n").status.code(), Some(1));
+    assert_eq!(globber(&["src/[a-"], "").status.code(), Some(2));
+    assert_eq!(globber(&["--nope", "*"], "").status.code(), Some(2));
+    assert_eq!(globber(&[], "").status.code(), Some(2));
+}
+
+#[test]
+fn walk() {
+    let root = tree("walk", &["src/lib.rs", "src/a/mod.rs", "notes.txt"]);
+    let dir = root.to_str().unwrap();
+    let output = globber(&["--walk", dir, "*.rs"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "src/a/mod.rs\nsrc/lib.rs\n");
+    let output = globber(&["--walk", dir, "-v", "*.rs"], "");
+    assert_eq!(stdout(&output), "notes.txt\n");
+    assert_eq!(globber(&["--walk", dir, "*.md"], "").status.code(), Some(1));
+    assert_eq!(globber(&["--walk", root.join("missing").to_str().unwrap(), "*"], "").status.code(), Some(2));
+
+    // metacharacters in the directory's own name are taken literally
+    let odd = tree("walk-[odd]", &["a.rs"]);
+    assert_eq!(stdout(&globber(&["--walk", odd.to_str().unwrap(), "*"], "")), "a.rs\n");
+    #[cfg(unix)]
+    {
+        // a symlinked directory is skipped, not listed as a file
+        std::os::unix::fs::symlink(root.join("src"), root.join("link")).unwrap();
+        assert_eq!(stdout(&globber(&["--walk", dir, "*"], "")), "notes.txt\nsrc/a/mod.rs\nsrc/lib.rs\n");
+    }
+    fs::remove_dir_all(root).unwrap();
+    fs::remove_dir_all(odd).unwrap();
+}
+
+#[test]
+fn bench() {
+    let root = tree("bench", &[]);
+    fs::create_dir_all(&root).unwrap();
+    let patterns = root.join("patterns");
+    let input = root.join("input");
+    fs::write(&patterns, "# comment\n*.rs\n\nsrc/*\n").unwrap();
+    fs::write(&input, "src/lib.rs\nREADME.md\nsrc/main.rs\n").unwrap();
+    let (patterns, input) = (patterns.to_str().unwrap(), input.to_str().unwrap());
+    let output = globber(&["bench", patterns, input, "--runs", "3"], "");
+    assert_eq!(output.status.code(), Some(0));
+    let out = stdout(&output);
+    assert!(out.starts_with("2 patterns, 3 input lines, 3 run(s)"), "{}", out);
+    assert!(out.lines().any(|line| line.ends_with("  *.rs") && line.contains(" 2 ")), "{}", out);
+    assert_eq!(globber(&["bench", patterns, input, "--runs", "0"], "").status.code(), Some(2));
+    assert_eq!(globber(&["bench", patterns], "").status.code(), Some(2));
+    fs::remove_dir_all(root).unwrap();
+}